@@ -21,17 +21,21 @@
 //!
 //! ## Usage
 //! ```bash
-//! cargo run --example builtin_functions -- [date_time|function_factory|regexp]
+//! cargo run --example builtin_functions -- [date_time|function_factory|regexp|regexp_extract|regexp_extract_all]
 //! ```
 //!
 //! Each subcommand runs a corresponding example:
 //! - `date_time` — examples of date-time related functions and queries
 //! - `function_factory` — register `CREATE FUNCTION` handler to implement SQL macros
 //! - `regexp` — examples of using regular expression functions
+//! - `regexp_extract` — extract a capture group of a match, checking the results
+//! - `regexp_extract_all` — extract every match of a capture group as a list
 
 mod date_time;
 mod function_factory;
 mod regexp;
+mod regexp_extract;
+mod regexp_extract_all;
 
 use std::str::FromStr;
 
@@ -41,6 +45,8 @@ enum ExampleKind {
     DateTime,
     FunctionFactory,
     Regexp,
+    RegexpExtract,
+    RegexpExtractAll,
 }
 
 impl AsRef<str> for ExampleKind {
@@ -49,6 +55,8 @@ impl AsRef<str> for ExampleKind {
             Self::DateTime => "date_time",
             Self::FunctionFactory => "function_factory",
             Self::Regexp => "regexp",
+            Self::RegexpExtract => "regexp_extract",
+            Self::RegexpExtractAll => "regexp_extract_all",
         }
     }
 }
@@ -61,13 +69,21 @@ impl FromStr for ExampleKind {
             "date_time" => Ok(Self::DateTime),
             "function_factory" => Ok(Self::FunctionFactory),
             "regexp" => Ok(Self::Regexp),
+            "regexp_extract" => Ok(Self::RegexpExtract),
+            "regexp_extract_all" => Ok(Self::RegexpExtractAll),
             _ => Err(DataFusionError::Execution(format!("Unknown example: {s}"))),
         }
     }
 }
 
 impl ExampleKind {
-    const ALL: [Self; 3] = [Self::DateTime, Self::FunctionFactory, Self::Regexp];
+    const ALL: [Self; 5] = [
+        Self::DateTime,
+        Self::FunctionFactory,
+        Self::Regexp,
+        Self::RegexpExtract,
+        Self::RegexpExtractAll,
+    ];
 
     const EXAMPLE_NAME: &str = "builtin_functions";
 
@@ -93,6 +109,8 @@ async fn main() -> Result<()> {
         ExampleKind::DateTime => date_time::date_time().await?,
         ExampleKind::FunctionFactory => function_factory::function_factory().await?,
         ExampleKind::Regexp => regexp::regexp().await?,
+        ExampleKind::RegexpExtract => regexp_extract::regexp_extract().await?,
+        ExampleKind::RegexpExtractAll => regexp_extract_all::regexp_extract_all().await?,
     }
 
     Ok(())
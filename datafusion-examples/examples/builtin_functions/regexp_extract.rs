@@ -0,0 +1,134 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, Int64Array, RecordBatch, StringArray};
+use arrow::util::pretty::pretty_format_batches;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::prelude::*;
+
+/// This example demonstrates how to use the `regexp_extract` function, which
+/// returns the specified capture group of the first match
+///
+/// Every query is printed and checked against its expected values, so that
+/// running the example fails if the results change
+///
+/// the full list of supported features and
+/// syntax can be found at
+/// https://docs.rs/regex/latest/regex/#syntax
+pub async fn regexp_extract() -> Result<()> {
+    let ctx = SessionContext::new();
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "values",
+            Arc::new(StringArray::from(vec![
+                Some("100-200"),
+                Some("key=a, key=b"),
+                Some("no digits"),
+                None,
+            ])) as ArrayRef,
+        ),
+        (
+            "patterns",
+            Arc::new(StringArray::from(vec![
+                Some(r"(\d+)-(\d+)"),
+                Some(r"key=(\w)"),
+                Some(r"(\d+)"),
+                Some(r"(\d+)"),
+            ])) as ArrayRef,
+        ),
+        (
+            "idx",
+            Arc::new(Int64Array::from(vec![2, 1, 1, 1])) as ArrayRef,
+        ),
+    ])?;
+    ctx.register_batch("examples", batch)?;
+
+    for (sql, expected) in scalar_cases().into_iter().chain(array_cases()) {
+        check(&ctx, sql, &expected).await?;
+    }
+
+    Ok(())
+}
+
+/// Calls with a literal value for every argument
+fn scalar_cases() -> Vec<(&'static str, Vec<Option<&'static str>>)> {
+    vec![
+        (
+            r"select regexp_extract('100-200', '(\d+)-(\d+)', 2)",
+            vec![Some("200")],
+        ),
+        // the capture group defaults to 1
+        (
+            r"select regexp_extract('100-200', '(\d+)-(\d+)')",
+            vec![Some("100")],
+        ),
+        // group 0 is the entire match
+        (
+            r"select regexp_extract('id: 100-200', '(\d+)-(\d+)', 0)",
+            vec![Some("100-200")],
+        ),
+        // a value without a match yields an empty string
+        (r"select regexp_extract('abc', '(\d+)', 1)", vec![Some("")]),
+        // the flags follow the capture group
+        (
+            r"select regexp_extract('ABC', '(b)', 1, 'i')",
+            vec![Some("B")],
+        ),
+        // an integer after the capture group selects the occurrence of the match
+        (
+            r"select regexp_extract('a=1, b=2', '(\w)=(\d)', 1, 2)",
+            vec![Some("b")],
+        ),
+        (r"select regexp_extract(NULL, '(\d+)', 1)", vec![None]),
+    ]
+}
+
+/// Calls with columns of the `examples` table
+fn array_cases() -> Vec<(&'static str, Vec<Option<&'static str>>)> {
+    vec![
+        // a scalar pattern is compiled once and applied to every row
+        (
+            r"select regexp_extract(values, '(\d+)-(\d+)', 2) from examples",
+            vec![Some("200"), Some(""), Some(""), None],
+        ),
+        // the pattern and the capture group can also be columns
+        (
+            "select regexp_extract(values, patterns, idx) from examples",
+            vec![Some("200"), Some("a"), Some(""), None],
+        ),
+    ]
+}
+
+/// Runs `sql`, prints its result and checks that its only column holds the
+/// `expected` values
+async fn check(ctx: &SessionContext, sql: &str, expected: &[Option<&str>]) -> Result<()> {
+    let batches = ctx.sql(sql).await?.collect().await?;
+    println!("{sql}\n{}", pretty_format_batches(&batches)?);
+
+    let actual = batches
+        .iter()
+        .flat_map(|batch| batch.column(0).as_string::<i32>().iter())
+        .collect::<Vec<_>>();
+    if actual != expected {
+        return Err(DataFusionError::Execution(format!(
+            "unexpected result of `{sql}`: expected {expected:?}, got {actual:?}"
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, RecordBatch, StringArray};
+use datafusion::common::assert_batches_eq;
+use datafusion::error::Result;
+use datafusion::prelude::*;
+
+/// This example demonstrates how to use the `regexp_extract_all` function,
+/// which returns the specified capture group of every match as a list
+///
+/// the full list of supported features and
+/// syntax can be found at
+/// https://docs.rs/regex/latest/regex/#syntax
+pub async fn regexp_extract_all() -> Result<()> {
+    let ctx = SessionContext::new();
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "values",
+            Arc::new(StringArray::from(vec![
+                Some("100-200, 300-400"),
+                Some("key=a, key=b, key=c"),
+                Some("no matches here"),
+                None,
+            ])) as ArrayRef,
+        ),
+        (
+            "patterns",
+            Arc::new(StringArray::from(vec![
+                Some(r"(\d+)-(\d+)"),
+                Some(r"key=(\w)"),
+                Some(r"(\d+)"),
+                Some(r"(\d+)"),
+            ])) as ArrayRef,
+        ),
+    ])?;
+    ctx.register_batch("examples", batch)?;
+
+    // a scalar pattern is compiled once and applied to every row, rows without
+    // a match produce an empty list and NULL values produce a NULL list
+    let result = ctx
+        .sql(r"select regexp_extract_all(values, '(\d+)-(\d+)', 2) from examples")
+        .await?
+        .collect()
+        .await?;
+
+    assert_batches_eq!(
+        &[
+            "+------------------------------------------------------------------+",
+            "| regexp_extract_all(examples.values,Utf8(\"(\\d+)-(\\d+)\"),Int64(2)) |",
+            "+------------------------------------------------------------------+",
+            "| [200, 400]                                                       |",
+            "| []                                                               |",
+            "| []                                                               |",
+            "|                                                                  |",
+            "+------------------------------------------------------------------+",
+        ],
+        &result
+    );
+
+    // the pattern can also be a column, the capture group defaults to 1
+    let result = ctx
+        .sql("select regexp_extract_all(values, patterns) from examples")
+        .await?
+        .collect()
+        .await?;
+
+    assert_batches_eq!(
+        &[
+            "+-------------------------------------------------------+",
+            "| regexp_extract_all(examples.values,examples.patterns) |",
+            "+-------------------------------------------------------+",
+            "| [100, 300]                                            |",
+            "| [a, b, c]                                             |",
+            "| []                                                    |",
+            "|                                                       |",
+            "+-------------------------------------------------------+",
+        ],
+        &result
+    );
+
+    // the optional limit caps the number of matches returned for each row
+    let result = ctx
+        .sql("select regexp_extract_all(values, patterns, 1, 2) from examples")
+        .await?
+        .collect()
+        .await?;
+
+    assert_batches_eq!(
+        &[
+            "+-------------------------------------------------------------------------+",
+            "| regexp_extract_all(examples.values,examples.patterns,Int64(1),Int64(2)) |",
+            "+-------------------------------------------------------------------------+",
+            "| [100, 300]                                                              |",
+            "| [a, b]                                                                  |",
+            "| []                                                                      |",
+            "|                                                                         |",
+            "+-------------------------------------------------------------------------+",
+        ],
+        &result
+    );
+
+    // unnest turns every extracted match into its own row
+    let result = ctx
+        .sql(
+            "select unnest(regexp_extract_all(values, patterns)) as extracted \
+             from examples",
+        )
+        .await?
+        .collect()
+        .await?;
+
+    assert_batches_eq!(
+        &[
+            "+-----------+",
+            "| extracted |",
+            "+-----------+",
+            "| 100       |",
+            "| 300       |",
+            "| a         |",
+            "| b         |",
+            "| c         |",
+            "+-----------+",
+        ],
+        &result
+    );
+
+    Ok(())
+}
@@ -1387,7 +1387,26 @@ impl SessionStateBuilder {
             physical_optimizer_rules,
         } = self;
 
-        let config = config.unwrap_or_default();
+        #[cfg_attr(not(feature = "regex_expressions"), allow(unused_mut))]
+        let mut config = config.unwrap_or_default();
+        // the regex functions of the session share their compiled patterns,
+        // and the options of regexp_extract are set like the other options
+        #[cfg(feature = "regex_expressions")]
+        {
+            use datafusion_functions::regex::regexpextract::RegexpExtractConfig;
+            use datafusion_functions::regex::SharedRegexes;
+            if SharedRegexes::from_config(config.options()).is_none() {
+                config = config.with_option_extension(SharedRegexes::default());
+            }
+            if config
+                .options()
+                .extensions
+                .get::<RegexpExtractConfig>()
+                .is_none()
+            {
+                config = config.with_option_extension(RegexpExtractConfig::default());
+            }
+        }
         let runtime_env = runtime_env.unwrap_or_else(|| Arc::new(RuntimeEnv::default()));
 
         let mut state = SessionState {
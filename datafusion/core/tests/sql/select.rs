@@ -414,3 +414,78 @@ async fn test_select_no_projection() -> Result<()> {
     ");
     Ok(())
 }
+
+/// `regexp_extract` is immutable, so its results must not depend on how the
+/// input is partitioned or split into batches
+#[tokio::test]
+async fn test_regexp_extract_reproducible_across_partitions() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("s", DataType::Utf8, true),
+        Field::new("p", DataType::Utf8, true),
+    ]));
+    // 4 partitions of 3 batches of 250 rows, with a mix of matches, non
+    // matches, NULLs and per row patterns
+    let partitions = (0..4)
+        .map(|partition| {
+            (0..3)
+                .map(|batch| {
+                    let ids = (0..250)
+                        .map(|row| (partition * 3 + batch) * 250 + row)
+                        .collect::<Vec<i64>>();
+                    let values = ids
+                        .iter()
+                        .map(|id| match id % 7 {
+                            0 => None,
+                            1 => Some(format!(
+                                "no digits {}",
+                                "x".repeat(*id as usize % 5)
+                            )),
+                            _ => Some(format!("{id}-key{} rest", id % 13)),
+                        })
+                        .collect::<StringArray>();
+                    let patterns = ids
+                        .iter()
+                        .map(|id| match id % 3 {
+                            0 => Some(r"(\d+)"),
+                            1 => Some(r"key(\d*)"),
+                            _ => None,
+                        })
+                        .collect::<StringArray>();
+                    RecordBatch::try_new(
+                        Arc::clone(&schema),
+                        vec![
+                            Arc::new(Int64Array::from(ids)),
+                            Arc::new(values),
+                            Arc::new(patterns),
+                        ],
+                    )
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let sql = r"SELECT id, regexp_extract(s, '(\d+)-(\w+)', 2) AS scalar_pattern, regexp_extract(s, p, 1) AS pattern_column FROM t ORDER BY id";
+    let mut results = vec![];
+    for (target_partitions, batch_size) in [(1, 8192), (4, 100), (16, 7), (3, 1)] {
+        let config = SessionConfig::new()
+            .with_target_partitions(target_partitions)
+            .with_batch_size(batch_size);
+        let ctx = SessionContext::new_with_config(config);
+        let table = datafusion::datasource::MemTable::try_new(
+            Arc::clone(&schema),
+            partitions.clone(),
+        )?;
+        ctx.register_table("t", Arc::new(table))?;
+
+        let batches = ctx.sql(sql).await?.collect().await?;
+        let result = arrow::compute::concat_batches(&batches[0].schema(), &batches)?;
+        assert_eq!(result.num_rows(), 3000);
+        results.push(result);
+    }
+
+    for result in &results[1..] {
+        assert_eq!(result, &results[0]);
+    }
+    Ok(())
+}
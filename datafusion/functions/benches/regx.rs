@@ -21,8 +21,9 @@ use arrow::array::builder::StringBuilder;
 use arrow::array::{ArrayRef, AsArray, Int64Array, StringArray, StringViewArray};
 use arrow::compute::cast;
 use arrow::datatypes::DataType;
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
 use datafusion_functions::regex::regexpcount::regexp_count_func;
+use datafusion_functions::regex::regexpextract::{regexp_extract, regexp_extract_view};
 use datafusion_functions::regex::regexpinstr::regexp_instr_func;
 use datafusion_functions::regex::regexplike::regexp_like;
 use datafusion_functions::regex::regexpmatch::regexp_match;
@@ -48,6 +49,20 @@ fn data(rng: &mut ThreadRng) -> StringArray {
     StringArray::from(data)
 }
 
+fn long_data(rng: &mut ThreadRng) -> StringArray {
+    let mut data: Vec<String> = vec![];
+    for _ in 0..1000 {
+        data.push(
+            rng.sample_iter(&Alphanumeric)
+                .take(64)
+                .map(char::from)
+                .collect(),
+        );
+    }
+
+    StringArray::from(data)
+}
+
 fn regex(rng: &mut ThreadRng) -> StringArray {
     let samples = [
         ".*([A-Z]{1}).*".to_string(),
@@ -300,6 +315,196 @@ fn criterion_benchmark(c: &mut Criterion) {
             )
         })
     });
+
+    // the random alphanumeric data rarely contains three consecutive digits
+    for group_index in [0, 1] {
+        c.bench_function(
+            &format!("regexp_extract_1000 mostly non-matching group {group_index}"),
+            |b| {
+                let mut rng = rand::rng();
+                let data = Arc::new(data(&mut rng)) as ArrayRef;
+                let regex =
+                    Arc::new(StringArray::from(vec![r"(\d{3})[a-z]"])) as ArrayRef;
+                let group_index =
+                    Arc::new(Int64Array::from(vec![group_index; 1000])) as ArrayRef;
+
+                b.iter(|| {
+                    black_box(
+                        regexp_extract(&[
+                            Arc::clone(&data),
+                            Arc::clone(&regex),
+                            Arc::clone(&group_index),
+                        ])
+                        .expect("regexp_extract should work on valid values"),
+                    )
+                })
+            },
+        );
+    }
+
+    // the rows of group 0 only need the bounds of the match, so a column of
+    // group indices that are mostly 0 is cheaper than one of group 1
+    for (name, group_index) in [("mostly 0", 0), ("all 1", 1)] {
+        c.bench_function(&format!("regexp_extract_1000 group indices {name}"), |b| {
+            let mut rng = rand::rng();
+            let data = Arc::new(data(&mut rng)) as ArrayRef;
+            let regex =
+                Arc::new(StringArray::from(vec![r"([A-Z])([a-z])?([0-9])?([a-z])?"]))
+                    as ArrayRef;
+            let group_index = Arc::new(Int64Array::from_iter_values((0..1000).map(|i| {
+                if i % 10 == 9 {
+                    1
+                } else {
+                    group_index
+                }
+            }))) as ArrayRef;
+
+            b.iter(|| {
+                black_box(
+                    regexp_extract(&[
+                        Arc::clone(&data),
+                        Arc::clone(&regex),
+                        Arc::clone(&group_index),
+                    ])
+                    .expect("regexp_extract should work on valid values"),
+                )
+            })
+        });
+    }
+
+    // the random alphanumeric data rarely starts with the anchored prefix
+    c.bench_function("regexp_extract_1000 anchored prefix mostly absent", |b| {
+        let mut rng = rand::rng();
+        let data = Arc::new(data(&mut rng)) as ArrayRef;
+        let regex = Arc::new(StringArray::from(vec![r"^ab(\d+)"])) as ArrayRef;
+
+        b.iter(|| {
+            black_box(
+                regexp_extract(&[Arc::clone(&data), Arc::clone(&regex)])
+                    .expect("regexp_extract should work on valid values"),
+            )
+        })
+    });
+
+    c.bench_function("regexp_extract_1000 matching", |b| {
+        let mut rng = rand::rng();
+        let data = Arc::new(data(&mut rng)) as ArrayRef;
+        let regex = Arc::new(StringArray::from(vec![r"([A-Z])[a-z]"])) as ArrayRef;
+
+        b.iter(|| {
+            black_box(
+                regexp_extract(&[Arc::clone(&data), Arc::clone(&regex)])
+                    .expect("regexp_extract should work on valid values"),
+            )
+        })
+    });
+
+    // a single repeated class is scanned without the regex engine, unlike the
+    // equivalent pattern that spells out the first character
+    for (name, pattern) in [
+        ("class tokenizer", r"[0-9]+"),
+        ("regex engine", r"[0-9][0-9]*"),
+    ] {
+        c.bench_function(&format!("regexp_extract_1000 digit runs {name}"), |b| {
+            let mut rng = rand::rng();
+            let data = Arc::new(data(&mut rng)) as ArrayRef;
+            let regex = Arc::new(StringArray::from(vec![pattern])) as ArrayRef;
+
+            b.iter(|| {
+                black_box(
+                    regexp_extract(&[Arc::clone(&data), Arc::clone(&regex)])
+                        .expect("regexp_extract should work on valid values"),
+                )
+            })
+        });
+    }
+
+    // the entire later matches of a pattern with many groups only need the
+    // match bounds, not the capture groups
+    for group_index in [0, 1] {
+        c.bench_function(
+            &format!("regexp_extract_1000 many groups occurrence 2 group {group_index}"),
+            |b| {
+                let mut rng = rand::rng();
+                let data = Arc::new(data(&mut rng)) as ArrayRef;
+                let regex = Arc::new(StringArray::from(vec![
+                    r"([A-Z])([a-z])?([0-9])?([a-z])?([A-Z])?([0-9])?",
+                ])) as ArrayRef;
+                let group_index =
+                    Arc::new(Int64Array::from(vec![group_index; 1000])) as ArrayRef;
+                let occurrence = Arc::new(Int64Array::from(vec![2; 1000])) as ArrayRef;
+
+                b.iter(|| {
+                    black_box(
+                        regexp_extract(&[
+                            Arc::clone(&data),
+                            Arc::clone(&regex),
+                            Arc::clone(&group_index),
+                            Arc::clone(&occurrence),
+                        ])
+                        .expect("regexp_extract should work on valid values"),
+                    )
+                })
+            },
+        );
+    }
+
+    // the captures are longer than the 12 bytes that are inlined in a view
+    for (name, extract) in [
+        ("copying", regexp_extract as fn(&[ArrayRef]) -> _),
+        ("viewing", regexp_extract_view),
+    ] {
+        c.bench_function(&format!("regexp_extract_1000 long captures {name}"), |b| {
+            let mut rng = rand::rng();
+            let data = Arc::new(long_data(&mut rng)) as ArrayRef;
+            let regex = Arc::new(StringArray::from(vec![r"^.{8}(.+)"])) as ArrayRef;
+
+            b.iter(|| {
+                black_box(
+                    extract(&[Arc::clone(&data), Arc::clone(&regex)])
+                        .expect("regexp_extract should work on valid values"),
+                )
+            })
+        });
+    }
+
+    // the same workload over each string type, reported as the throughput of
+    // the matched bytes; the views are extracted both by copying the captures
+    // and by viewing them in place
+    for (workload, pattern) in [
+        ("long captures", r"^.{8}(.+)"),
+        ("short captures", r"([A-Z][a-z]+)"),
+    ] {
+        let mut group = c.benchmark_group(format!("regexp_extract_1000 {workload}"));
+        let mut rng = rand::rng();
+        let data = Arc::new(long_data(&mut rng)) as ArrayRef;
+        group.throughput(Throughput::Bytes(
+            data.as_string::<i32>().value_data().len() as u64,
+        ));
+        let regex = Arc::new(StringArray::from(vec![pattern])) as ArrayRef;
+        for (name, data_type, extract) in [
+            (
+                "Utf8",
+                DataType::Utf8,
+                regexp_extract as fn(&[ArrayRef]) -> _,
+            ),
+            ("LargeUtf8", DataType::LargeUtf8, regexp_extract),
+            ("Utf8View copying", DataType::Utf8View, regexp_extract),
+            ("Utf8View viewing", DataType::Utf8View, regexp_extract_view),
+        ] {
+            let data = cast(&data, &data_type).unwrap();
+            let regex = cast(&regex, &data_type).unwrap();
+            group.bench_function(name, |b| {
+                b.iter(|| {
+                    black_box(
+                        extract(&[Arc::clone(&data), Arc::clone(&regex)])
+                            .expect("regexp_extract should work on valid values"),
+                    )
+                })
+            });
+        }
+        group.finish();
+    }
 }
 
 criterion_group!(benches, criterion_benchmark);
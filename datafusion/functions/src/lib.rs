@@ -222,4 +222,14 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_regex_functions_follow_feature() {
+        // the regex functions are not registered, rather than failing to
+        // build, when the `regex_expressions` feature is disabled
+        let registered = all_default_functions()
+            .iter()
+            .any(|udf| udf.name() == "regexp_extract");
+        assert_eq!(registered, cfg!(feature = "regex_expressions"));
+    }
 }
@@ -18,25 +18,161 @@
 //! "regex" DataFusion functions
 
 use arrow::error::ArrowError;
+use datafusion_common::config::{
+    ConfigEntry, ConfigExtension, ConfigField, ConfigOptions, ExtensionOptions,
+};
+use datafusion_common::{config_err, Result};
+#[cfg(feature = "fancy_regex")]
+use fancy_regex::Regex as FancyRegex;
 use regex::Regex;
+use std::any::Any;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+pub mod regexpclassify;
 pub mod regexpcount;
+pub mod regexpextract;
+pub mod regexpextractall;
+pub mod regexpextractallnamed;
+pub mod regexpextractbytes;
+pub mod regexpextractcoalesce;
+pub mod regexpextractcoalesceindexed;
+pub mod regexpextractconcat;
+pub mod regexpextractfield;
+pub mod regexpextractformat;
+pub mod regexpextractformator;
+pub mod regexpextractjson;
+pub mod regexpextractlast;
+pub mod regexpextractlen;
+pub mod regexpextractlist;
+pub mod regexpextractmap;
+pub mod regexpextractmapvalue;
+pub mod regexpextractnamed;
+pub mod regexpextractor;
+pub mod regexpextractpair;
+pub mod regexpextractpresent;
+pub mod regexpextractrest;
+pub mod regexpextracttemplate;
+pub mod regexpextracttimestamp;
+pub mod regexpextracttyped;
+pub mod regexpextractword;
+pub mod regexpgroupcount;
+pub mod regexpgroupnames;
+pub mod regexphighlight;
 pub mod regexpinstr;
+pub mod regexpisvalid;
 pub mod regexplike;
 pub mod regexpmatch;
+pub mod regexpprefix;
 pub mod regexpreplace;
 
 // create UDFs
+make_udf_function!(regexpclassify::RegexpClassifyFunc, regexp_classify);
 make_udf_function!(regexpcount::RegexpCountFunc, regexp_count);
+make_udf_function!(regexpextract::RegexpExtractFunc, regexp_extract);
+make_udf_function!(regexpextractall::RegexpExtractAllFunc, regexp_extract_all);
+make_udf_function!(
+    regexpextractallnamed::RegexpExtractAllNamedFunc,
+    regexp_extract_all_named
+);
+make_udf_function!(
+    regexpextractbytes::RegexpExtractBytesFunc,
+    regexp_extract_bytes
+);
+make_udf_function!(
+    regexpextractcoalesce::RegexpExtractCoalesceFunc,
+    regexp_extract_coalesce
+);
+make_udf_function!(
+    regexpextractcoalesceindexed::RegexpExtractCoalesceIndexedFunc,
+    regexp_extract_coalesce_indexed
+);
+make_udf_function!(
+    regexpextractconcat::RegexpExtractConcatFunc,
+    regexp_extract_concat
+);
+make_udf_function!(
+    regexpextractfield::RegexpExtractFieldFunc,
+    regexp_extract_field
+);
+make_udf_function!(
+    regexpextractformat::RegexpExtractFormatFunc,
+    regexp_extract_format
+);
+make_udf_function!(
+    regexpextractformator::RegexpExtractFormatOrFunc,
+    regexp_extract_format_or
+);
+make_udf_function!(
+    regexpextractjson::RegexpExtractJsonFunc,
+    regexp_extract_json
+);
+make_udf_function!(
+    regexpextractlast::RegexpExtractLastFunc,
+    regexp_extract_last
+);
+make_udf_function!(regexpextractlen::RegexpExtractLenFunc, regexp_extract_len);
+make_udf_function!(
+    regexpextractlist::RegexpExtractListFunc,
+    regexp_extract_list
+);
+make_udf_function!(regexpextractmap::RegexpExtractMapFunc, regexp_extract_map);
+make_udf_function!(
+    regexpextractmapvalue::RegexpExtractMapValueFunc,
+    regexp_extract_map_value
+);
+make_udf_function!(
+    regexpextractnamed::RegexpExtractNamedFunc,
+    regexp_extract_named
+);
+make_udf_function!(regexpextractor::RegexpExtractOrFunc, regexp_extract_or);
+make_udf_function!(
+    regexpextractpair::RegexpExtractPairFunc,
+    regexp_extract_pair
+);
+make_udf_function!(
+    regexpextractpresent::RegexpExtractPresentFunc,
+    regexp_extract_present
+);
+make_udf_function!(
+    regexpextractrest::RegexpExtractRestFunc,
+    regexp_extract_rest
+);
+make_udf_function!(
+    regexpextracttemplate::RegexpExtractTemplateFunc,
+    regexp_extract_template
+);
+make_udf_function!(
+    regexpextracttimestamp::RegexpExtractTimestampFunc,
+    regexp_extract_timestamp
+);
+make_udf_function!(
+    regexpextracttyped::RegexpExtractTypedFunc,
+    regexp_extract_typed
+);
+make_udf_function!(
+    regexpextractword::RegexpExtractWordFunc,
+    regexp_extract_word
+);
+make_udf_function!(regexpgroupcount::RegexpGroupCountFunc, regexp_group_count);
+make_udf_function!(regexpgroupnames::RegexpGroupNamesFunc, regexp_group_names);
+make_udf_function!(regexphighlight::RegexpHighlightFunc, regexp_highlight);
 make_udf_function!(regexpinstr::RegexpInstrFunc, regexp_instr);
+make_udf_function!(regexpisvalid::RegexpIsValidFunc, regexp_is_valid);
 make_udf_function!(regexpmatch::RegexpMatchFunc, regexp_match);
+make_udf_function!(regexpprefix::RegexpPrefixFunc, regexp_prefix);
 make_udf_function!(regexplike::RegexpLikeFunc, regexp_like);
 make_udf_function!(regexpreplace::RegexpReplaceFunc, regexp_replace);
 
 pub mod expr_fn {
-    use datafusion_expr::Expr;
+    use datafusion_expr::{lit, Expr};
+
+    /// Returns the label of the first of a list of regular expressions that matches a string.
+    pub fn regexp_classify(values: Expr, regexes: Expr, labels: Expr) -> Expr {
+        super::regexp_classify().call(vec![values, regexes, labels])
+    }
 
     /// Returns the number of consecutive occurrences of a regular expression in a string.
     pub fn regexp_count(
@@ -56,6 +192,256 @@ pub mod expr_fn {
         super::regexp_count().call(args)
     }
 
+    /// Extracts a capture group of the first regular expression match in a string.
+    pub fn regexp_extract(
+        values: Expr,
+        regex: Expr,
+        idx: Option<Expr>,
+        flags: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(flags) = flags {
+            // the flags are positional, so the default group index has to be spelled out
+            args.push(idx.unwrap_or_else(|| lit(1_i64)));
+            args.push(flags);
+        } else if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract().call(args)
+    }
+
+    /// Extracts a capture group of every regular expression match in a string.
+    pub fn regexp_extract_all(
+        values: Expr,
+        regex: Expr,
+        idx: Option<Expr>,
+        limit: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(limit) = limit {
+            // the limit is positional, so the default group index has to be spelled out
+            args.push(idx.unwrap_or_else(|| lit(1_i64)));
+            args.push(limit);
+        } else if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract_all().call(args)
+    }
+
+    /// Extracts the named capture groups of every regular expression match in a string as maps.
+    pub fn regexp_extract_all_named(values: Expr, regex: Expr) -> Expr {
+        super::regexp_extract_all_named().call(vec![values, regex])
+    }
+
+    /// Extracts a capture group of the first regular expression match in a binary value, returning the captured bytes as `Binary`, or as `Utf8` for a function built with another [`BytesCaptureMode`](super::regexpextractbytes::BytesCaptureMode).
+    pub fn regexp_extract_bytes(
+        values: Expr,
+        regex: Expr,
+        idx: Option<Expr>,
+        flags: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(flags) = flags {
+            // the flags are positional, so the default group index has to be spelled out
+            args.push(idx.unwrap_or_else(|| lit(1_i64)));
+            args.push(flags);
+        } else if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract_bytes().call(args)
+    }
+
+    /// Extracts a capture group of the first match of the first of a list of regular expressions that matches a string.
+    pub fn regexp_extract_coalesce(
+        values: Expr,
+        regexes: Expr,
+        idx: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regexes];
+        if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract_coalesce().call(args)
+    }
+
+    /// Like [`regexp_extract_coalesce`], but returns a struct of the extracted value and the index of the regular expression that matched.
+    pub fn regexp_extract_coalesce_indexed(
+        values: Expr,
+        regexes: Expr,
+        idx: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regexes];
+        if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract_coalesce_indexed().call(args)
+    }
+
+    /// Extracts a capture group of the first regular expression match in strings joined with a separator.
+    pub fn regexp_extract_concat(
+        separator: Expr,
+        regex: Expr,
+        idx: Expr,
+        values: Vec<Expr>,
+    ) -> Expr {
+        let mut args = vec![separator, regex, idx];
+        args.extend(values);
+        super::regexp_extract_concat().call(args)
+    }
+
+    /// Extracts a capture group of the first regular expression match in a string field of a struct.
+    pub fn regexp_extract_field(
+        value: Expr,
+        field: Expr,
+        regex: Expr,
+        idx: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![value, field, regex];
+        if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract_field().call(args)
+    }
+
+    /// Assembles the capture groups of the first regular expression match in a string into a template.
+    pub fn regexp_extract_format(values: Expr, regex: Expr, template: Expr) -> Expr {
+        super::regexp_extract_format().call(vec![values, regex, template])
+    }
+
+    /// Assembles the capture groups of the first regular expression match in a string into a template, or returns a default if there is no match.
+    pub fn regexp_extract_format_or(
+        values: Expr,
+        regex: Expr,
+        template: Expr,
+        default: Expr,
+    ) -> Expr {
+        super::regexp_extract_format_or().call(vec![values, regex, template, default])
+    }
+
+    /// Extracts the named capture groups of the first regular expression match in a string as a JSON object string.
+    pub fn regexp_extract_json(
+        values: Expr,
+        regex: Expr,
+        empty_object: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(empty_object) = empty_object {
+            args.push(empty_object);
+        };
+        super::regexp_extract_json().call(args)
+    }
+
+    /// Extracts a capture group of the last regular expression match in a string.
+    pub fn regexp_extract_last(values: Expr, regex: Expr, idx: Option<Expr>) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract_last().call(args)
+    }
+
+    /// Returns the length in characters of a capture group of the first regular expression match in a string.
+    pub fn regexp_extract_len(values: Expr, regex: Expr, idx: Option<Expr>) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(idx) = idx {
+            args.push(idx);
+        }
+        super::regexp_extract_len().call(args)
+    }
+
+    /// Extracts a capture group of the first regular expression match in every string of a list.
+    pub fn regexp_extract_list(values: Expr, regex: Expr, idx: Option<Expr>) -> Expr {
+        let mut args = vec![values, regex];
+        if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract_list().call(args)
+    }
+
+    /// Returns a map from the capture group names to the values captured by the first regular expression match in a string.
+    pub fn regexp_extract_map(values: Expr, regex: Expr) -> Expr {
+        super::regexp_extract_map().call(vec![values, regex])
+    }
+
+    /// Returns the capture group of the first regular expression match in the value of `key` in a map, or NULL if the map does not contain `key`.
+    pub fn regexp_extract_map_value(
+        map: Expr,
+        key: Expr,
+        regex: Expr,
+        idx: Option<Expr>,
+    ) -> Expr {
+        let mut args = vec![map, key, regex];
+        if let Some(idx) = idx {
+            args.push(idx);
+        };
+        super::regexp_extract_map_value().call(args)
+    }
+
+    /// Returns the values captured by the named groups of the first regular expression match in a string, in the order of `names`.
+    pub fn regexp_extract_named(values: Expr, regex: Expr, names: Expr) -> Expr {
+        super::regexp_extract_named().call(vec![values, regex, names])
+    }
+
+    /// Returns the capture group of the first regular expression match in a string, or `sentinel` if there is no match.
+    pub fn regexp_extract_or(
+        values: Expr,
+        regex: Expr,
+        idx: Expr,
+        sentinel: Expr,
+    ) -> Expr {
+        super::regexp_extract_or().call(vec![values, regex, idx, sentinel])
+    }
+
+    /// Extracts two capture groups of the first regular expression match in a string as a struct.
+    pub fn regexp_extract_pair(
+        values: Expr,
+        regex: Expr,
+        idx_a: Expr,
+        idx_b: Expr,
+    ) -> Expr {
+        super::regexp_extract_pair().call(vec![values, regex, idx_a, idx_b])
+    }
+
+    /// Returns a list of the names and values of the capture groups that participated in the first regular expression match in a string.
+    pub fn regexp_extract_present(values: Expr, regex: Expr) -> Expr {
+        super::regexp_extract_present().call(vec![values, regex])
+    }
+
+    /// Returns the rest of a string after the end of the first regular expression match.
+    pub fn regexp_extract_rest(values: Expr, regex: Expr) -> Expr {
+        super::regexp_extract_rest().call(vec![values, regex])
+    }
+
+    /// Extracts a capture group of the first match of a regular expression built from a template with escaped parameters.
+    pub fn regexp_extract_template(
+        values: Expr,
+        template: Expr,
+        params: Expr,
+        idx: Expr,
+    ) -> Expr {
+        super::regexp_extract_template().call(vec![values, template, params, idx])
+    }
+
+    /// Extracts a capture group of the first regular expression match in a string and parses it as a timestamp.
+    pub fn regexp_extract_timestamp(
+        values: Expr,
+        regex: Expr,
+        idx: Expr,
+        format: Expr,
+    ) -> Expr {
+        super::regexp_extract_timestamp().call(vec![values, regex, idx, format])
+    }
+
+    /// Extracts the named capture groups of the first regular expression match in a string into a struct typed by a schema.
+    pub fn regexp_extract_typed(values: Expr, regex: Expr, schema: Expr) -> Expr {
+        super::regexp_extract_typed().call(vec![values, regex, schema])
+    }
+
+    /// Extracts a literal word from a string if it appears as a whole word.
+    pub fn regexp_extract_word(values: Expr, word: Expr) -> Expr {
+        super::regexp_extract_word().call(vec![values, word])
+    }
+
     /// Returns a list of regular expression matches in a string.
     pub fn regexp_match(values: Expr, regex: Expr, flags: Option<Expr>) -> Expr {
         let mut args = vec![values, regex];
@@ -65,6 +451,26 @@ pub mod expr_fn {
         super::regexp_match().call(args)
     }
 
+    /// Returns the literal prefix that every match of a regular expression starts with.
+    pub fn regexp_prefix(regex: Expr) -> Expr {
+        super::regexp_prefix().call(vec![regex])
+    }
+
+    /// Returns the number of capture groups of a regular expression.
+    pub fn regexp_group_count(regex: Expr) -> Expr {
+        super::regexp_group_count().call(vec![regex])
+    }
+
+    /// Returns a list of the capture group names of a regular expression, NULL for unnamed groups.
+    pub fn regexp_group_names(regex: Expr) -> Expr {
+        super::regexp_group_names().call(vec![regex])
+    }
+
+    /// Wraps every match of a regular expression in a string with the open and close markers.
+    pub fn regexp_highlight(values: Expr, regex: Expr, open: Expr, close: Expr) -> Expr {
+        super::regexp_highlight().call(vec![values, regex, open, close])
+    }
+
     /// Returns index of regular expression matches in a string.
     pub fn regexp_instr(
         values: Expr,
@@ -93,6 +499,11 @@ pub mod expr_fn {
         };
         super::regexp_instr().call(args)
     }
+
+    /// Returns whether a regular expression compiles, false for invalid patterns.
+    pub fn regexp_is_valid(regex: Expr) -> Expr {
+        super::regexp_is_valid().call(vec![regex])
+    }
     /// Returns true if a regex has at least one match in a string, false otherwise.
     pub fn regexp_like(values: Expr, regex: Expr, flags: Option<Expr>) -> Expr {
         let mut args = vec![values, regex];
@@ -120,9 +531,40 @@ pub mod expr_fn {
 /// Returns all DataFusion functions defined in this package
 pub fn functions() -> Vec<Arc<datafusion_expr::ScalarUDF>> {
     vec![
+        regexp_classify(),
         regexp_count(),
+        regexp_extract(),
+        regexp_extract_all(),
+        regexp_extract_all_named(),
+        regexp_extract_bytes(),
+        regexp_extract_coalesce(),
+        regexp_extract_coalesce_indexed(),
+        regexp_extract_concat(),
+        regexp_extract_field(),
+        regexp_extract_format(),
+        regexp_extract_format_or(),
+        regexp_extract_json(),
+        regexp_extract_last(),
+        regexp_extract_len(),
+        regexp_extract_list(),
+        regexp_extract_map(),
+        regexp_extract_map_value(),
+        regexp_extract_named(),
+        regexp_extract_or(),
+        regexp_extract_pair(),
+        regexp_extract_present(),
+        regexp_extract_rest(),
+        regexp_extract_template(),
+        regexp_extract_timestamp(),
+        regexp_extract_typed(),
+        regexp_extract_word(),
         regexp_match(),
+        regexp_prefix(),
+        regexp_group_count(),
+        regexp_group_names(),
+        regexp_highlight(),
         regexp_instr(),
+        regexp_is_valid(),
         regexp_like(),
         regexp_replace(),
     ]
@@ -164,3 +606,255 @@ pub fn compile_regex(regex: &str, flags: Option<&str>) -> Result<Regex, ArrowErr
         ArrowError::ComputeError(format!("Regular expression did not compile: {pattern}"))
     })
 }
+
+/// The compiled patterns of a single batch or of a session, see
+/// [`SharedRegexes`], which evicts the least recently used pattern once it
+/// holds `capacity` patterns.
+#[derive(Debug)]
+pub(crate) struct PatternCache<K, V = Regex> {
+    capacity: usize,
+    /// Incremented on every lookup to order the entries by their last use
+    tick: u64,
+    entries: HashMap<K, (V, u64)>,
+}
+
+impl<K: Eq + Hash + Clone, V> PatternCache<K, V> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tick: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the pattern cached for `key`, compiling it with `compile` on a
+    /// miss.
+    pub(crate) fn get_or_compile<E>(
+        &mut self,
+        key: K,
+        compile: impl FnOnce() -> Result<V, E>,
+    ) -> Result<&V, E> {
+        if !self.entries.contains_key(&key) {
+            let regex = compile()?;
+            return Ok(self.insert(key, regex));
+        }
+        Ok(self.get(&key).expect("the pattern is cached"))
+    }
+
+    /// Returns the pattern cached for `key`, if any.
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.tick += 1;
+        let (regex, last_used) = self.entries.get_mut(key)?;
+        *last_used = self.tick;
+        Some(regex)
+    }
+
+    /// Caches `regex` for `key`, evicting the least recently used pattern if
+    /// the cache is full.
+    fn insert(&mut self, key: K, regex: V) -> &V {
+        self.tick += 1;
+        while self.entries.len() >= self.capacity {
+            self.evict_least_recently_used();
+        }
+        let (regex, _) = self.entries.entry(key).or_insert((regex, self.tick));
+        regex
+    }
+
+    /// Changes the capacity, evicting the least recently used patterns above
+    /// it.
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.entries.len() > self.capacity {
+            self.evict_least_recently_used();
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        // finding the least recently used entry is linear, which is cheap
+        // compared to the compilation that caused the eviction
+        let least_recently_used = self
+            .entries
+            .iter()
+            .min_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(least_recently_used) = least_recently_used {
+            self.entries.remove(&least_recently_used);
+        }
+    }
+}
+
+/// The patterns compiled by the regex functions of a session, shared so that
+/// a pattern used by several functions of a query, such as `regexp_extract`
+/// and `regexp_count`, is compiled into a single automaton. A clone of a
+/// [`Regex`] shares the automaton of the original.
+///
+/// The patterns are shared by the queries run with the [`ConfigOptions`] this
+/// extension is registered in. The session state registers it by default, and
+/// a session without it compiles the patterns of every batch on its own.
+/// Cloning the options, as a `SET` statement does, starts an empty cache.
+///
+/// `regexp_extract` with the [`DefaultPatternCompiler`] and `regexp_count`
+/// share their patterns. `regexp_like` is rewritten to the `~` operators, and
+/// the other regex functions compile their patterns themselves.
+///
+/// At most `regex.pattern_cache_size` patterns are kept, evicting the least
+/// recently used one. The bound counts patterns rather than bytes because a
+/// [`Regex`] does not report the memory of its automaton, and the size of
+/// each one is already capped by the `size_limit` and `dfa_size_limit` of
+/// [`regex::RegexBuilder`], so the number of patterns bounds the memory.
+/// With the `fancy_regex` feature, the patterns that need backtracking are
+/// kept in a second cache with the same limit.
+///
+/// [`DefaultPatternCompiler`]: regexpextract::DefaultPatternCompiler
+#[derive(Debug)]
+pub struct SharedRegexes {
+    pattern_cache_size: usize,
+    /// The patterns keyed by the pattern and its non-empty flags
+    patterns: Mutex<PatternCache<SharedKey>>,
+    /// The patterns the regex crate rejects because they need backtracking,
+    /// keyed like `patterns`
+    #[cfg(feature = "fancy_regex")]
+    fancy_patterns: Mutex<PatternCache<SharedKey, FancyRegex>>,
+    compilations: AtomicUsize,
+}
+
+/// A pattern and its non-empty flags
+type SharedKey = (String, Option<String>);
+
+impl Default for SharedRegexes {
+    fn default() -> Self {
+        Self::new(regexpextract::DEFAULT_PATTERN_CACHE_SIZE)
+    }
+}
+
+impl ConfigExtension for SharedRegexes {
+    const PREFIX: &'static str = "regex";
+}
+
+impl ExtensionOptions for SharedRegexes {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn cloned(&self) -> Box<dyn ExtensionOptions> {
+        Box::new(Self::new(self.pattern_cache_size))
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "pattern_cache_size" => {
+                ConfigField::set(&mut self.pattern_cache_size, key, value)?;
+                lock(&self.patterns).set_capacity(self.pattern_cache_size);
+                #[cfg(feature = "fancy_regex")]
+                lock(&self.fancy_patterns).set_capacity(self.pattern_cache_size);
+                Ok(())
+            }
+            _ => config_err!("Config value \"{key}\" not found on SharedRegexes"),
+        }
+    }
+
+    fn entries(&self) -> Vec<ConfigEntry> {
+        vec![ConfigEntry {
+            key: format!("{}.pattern_cache_size", Self::PREFIX),
+            value: Some(self.pattern_cache_size.to_string()),
+            description: "Maximum number of compiled patterns the regex functions of a session share. Once the limit is reached, the least recently used pattern is evicted and compiled again if a later batch uses it. At least one pattern is always kept.",
+        }]
+    }
+}
+
+impl SharedRegexes {
+    /// Creates an empty cache keeping at most `pattern_cache_size` patterns.
+    pub fn new(pattern_cache_size: usize) -> Self {
+        Self {
+            pattern_cache_size,
+            patterns: Mutex::new(PatternCache::new(pattern_cache_size)),
+            #[cfg(feature = "fancy_regex")]
+            fancy_patterns: Mutex::new(PatternCache::new(pattern_cache_size)),
+            compilations: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the patterns shared by the functions evaluated with `config`,
+    /// if any.
+    pub fn from_config(config: &ConfigOptions) -> Option<&Self> {
+        config.extensions.get::<Self>()
+    }
+
+    /// Returns the maximum number of patterns kept.
+    pub fn pattern_cache_size(&self) -> usize {
+        self.pattern_cache_size
+    }
+
+    /// Returns the number of patterns compiled into this cache, counting a
+    /// pattern again if it was compiled again after being evicted.
+    pub fn compilations(&self) -> usize {
+        self.compilations.load(Ordering::Relaxed)
+    }
+
+    /// Returns the regex shared for `regex` and `flags`, compiling it with
+    /// `compile` if no function compiled it yet.
+    ///
+    /// `compile` must build the regex from `regex` and `flags` alone so that
+    /// every function sharing the entry sees the same automaton. It runs
+    /// without holding the lock of the cache, so a slow compilation does not
+    /// block the lookups of other patterns.
+    pub(crate) fn get_or_compile<E>(
+        &self,
+        regex: &str,
+        flags: Option<&str>,
+        compile: impl FnOnce() -> Result<Regex, E>,
+    ) -> Result<Regex, E> {
+        self.get_or_compile_in(&self.patterns, regex, flags, compile)
+    }
+
+    /// Like [`SharedRegexes::get_or_compile`], for a pattern compiled with
+    /// fancy-regex because it needs backtracking.
+    #[cfg(feature = "fancy_regex")]
+    pub(crate) fn get_or_compile_fancy<E>(
+        &self,
+        regex: &str,
+        flags: Option<&str>,
+        compile: impl FnOnce() -> Result<FancyRegex, E>,
+    ) -> Result<FancyRegex, E> {
+        self.get_or_compile_in(&self.fancy_patterns, regex, flags, compile)
+    }
+
+    fn get_or_compile_in<V: Clone, E>(
+        &self,
+        patterns: &Mutex<PatternCache<SharedKey, V>>,
+        regex: &str,
+        flags: Option<&str>,
+        compile: impl FnOnce() -> Result<V, E>,
+    ) -> Result<V, E> {
+        let key = (
+            regex.to_string(),
+            flags.filter(|flags| !flags.is_empty()).map(str::to_string),
+        );
+        let cached = lock(patterns).get(&key).cloned();
+        if let Some(regex) = cached {
+            return Ok(regex);
+        }
+
+        let regex = compile()?;
+        self.compilations.fetch_add(1, Ordering::Relaxed);
+        let mut patterns = lock(patterns);
+        // another function may have compiled the pattern in the meantime, whose
+        // regex is kept so that all of them share a single automaton
+        if let Some(regex) = patterns.get(&key) {
+            return Ok(regex.clone());
+        }
+        Ok(patterns.insert(key, regex).clone())
+    }
+}
+
+fn lock<V>(
+    patterns: &Mutex<PatternCache<SharedKey, V>>,
+) -> MutexGuard<'_, PatternCache<SharedKey, V>> {
+    // the cache is consistent between its operations, so a panic while it was
+    // locked does not invalidate it
+    patterns.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,328 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use arrow::array::{Array, ArrayRef, AsArray, StringArray};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{exec_err, plan_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::RegexSet;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns the label of the first of a list of [regular expressions](https://docs.rs/regex/latest/regex/#syntax) that matches a string, or NULL if none of them match. The regular expressions are matched together in a single pass, which is faster than a chain of `CASE WHEN regexp_like(...)` branches, and earlier regular expressions take priority when several of them match.",
+    syntax_example = "regexp_classify(str, regexps, labels)",
+    sql_example = r#"```sql
+> select regexp_classify('GET /index.html', ['^GET /api', '^GET ', '^POST '], ['api', 'read', 'write']);
++--------------------------------------------------------------------------------------------------------------------------------------------------------+
+| regexp_classify(Utf8("GET /index.html"),make_array(Utf8("^GET /api"),Utf8("^GET "),Utf8("^POST ")),make_array(Utf8("api"),Utf8("read"),Utf8("write"))) |
++--------------------------------------------------------------------------------------------------------------------------------------------------------+
+| read                                                                                                                                                   |
++--------------------------------------------------------------------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexps",
+        description = "List of the regular expressions to match against, in order of priority. Must be a constant."
+    ),
+    argument(
+        name = "labels",
+        description = "List of the labels of the regular expressions, with one label for each regular expression. Must be a constant."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpClassifyFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpClassifyFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpClassifyFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpClassifyFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_classify"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        let [values, ColumnarValue::Scalar(patterns), ColumnarValue::Scalar(labels)] =
+            args.args.as_slice()
+        else {
+            return exec_err!(
+                "regexp_classify requires a string and constant lists of patterns and labels"
+            );
+        };
+        let (ScalarValue::List(patterns), ScalarValue::List(labels)) = (patterns, labels)
+        else {
+            return exec_err!(
+                "Unsupported data types {:?} and {:?} for the patterns and labels of function regexp_classify",
+                patterns.data_type(),
+                labels.data_type()
+            );
+        };
+
+        match values {
+            ColumnarValue::Scalar(value) => {
+                let result = regexp_classify(&[
+                    value.to_array()?,
+                    Arc::clone(patterns) as ArrayRef,
+                    Arc::clone(labels) as ArrayRef,
+                ]);
+                // If all inputs are scalar, keeps output as scalar
+                let result = result.and_then(|arr| ScalarValue::try_from_array(&arr, 0));
+                result.map(ColumnarValue::Scalar)
+            }
+            ColumnarValue::Array(values) => regexp_classify(&[
+                Arc::clone(values),
+                Arc::clone(patterns) as ArrayRef,
+                Arc::clone(labels) as ArrayRef,
+            ])
+            .map(ColumnarValue::Array),
+        }
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 3 {
+            return plan_err!(
+                "regexp_classify requires 3 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (0, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (0, other) => plan_err!(
+                    "regexp_classify argument {idx} must be a string, got {other}"
+                ),
+                (_, List(field) | LargeList(field) | FixedSizeList(field, _))
+                    if matches!(field.data_type(), Utf8 | LargeUtf8 | Utf8View | Null) =>
+                {
+                    Ok(string_list_type())
+                }
+                (_, Null) => Ok(string_list_type()),
+                (_, other) => plan_err!(
+                    "regexp_classify argument {idx} must be a list of strings, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The `List<Utf8>` type of the patterns and the labels of `regexp_classify`.
+fn string_list_type() -> DataType {
+    DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+}
+
+/// Returns for each string of `args[0]` the label of the first pattern that
+/// matches it.
+///
+/// `args[1]` and `args[2]` are single element arrays with the list of patterns
+/// and the list of their labels, which are applied to every row. The strings
+/// are expected to be `Utf8` and the lists `List<Utf8>`. The patterns are
+/// compiled once into a [`RegexSet`].
+pub fn regexp_classify(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 3 {
+        return exec_err!(
+            "regexp_classify was called with {} arguments. It requires 3.",
+            args.len()
+        );
+    }
+    if args[0].data_type() != &DataType::Utf8 {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_classify",
+            args[0].data_type()
+        );
+    }
+    if let Some(arg) = args[1..]
+        .iter()
+        .find(|arg| arg.data_type() != &string_list_type() || arg.len() != 1)
+    {
+        return exec_err!(
+            "regexp_classify patterns and labels must be single lists of strings, got {} values of type {:?}",
+            arg.len(),
+            arg.data_type()
+        );
+    }
+
+    let values = args[0].as_string::<i32>();
+    let (pattern_list, label_list) = (args[1].as_list::<i32>(), args[2].as_list::<i32>());
+    if pattern_list.is_null(0) || label_list.is_null(0) {
+        return Ok(Arc::new(StringArray::new_null(values.len())));
+    }
+    let patterns = pattern_list.value(0);
+    let patterns = patterns.as_string::<i32>();
+    let labels = label_list.value(0);
+    let labels = labels.as_string::<i32>();
+    if patterns.len() != labels.len() {
+        return exec_err!(
+            "regexp_classify requires a label for each pattern; got {} patterns and {} labels",
+            patterns.len(),
+            labels.len()
+        );
+    }
+    if patterns.null_count() > 0 {
+        return exec_err!("regexp_classify patterns must not be NULL");
+    }
+
+    let set = RegexSet::new(patterns.iter().flatten()).map_err(|e| {
+        DataFusionError::Execution(format!("unable to compile patterns: {e}"))
+    })?;
+    let result = values
+        .iter()
+        .map(|value| {
+            // the set reports the matching patterns in the order they were given
+            let index = set.matches(value?).iter().next()?;
+            labels.is_valid(index).then(|| labels.value(index))
+        })
+        .collect::<StringArray>();
+    Ok(Arc::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ListArray, ListBuilder, StringBuilder};
+
+    fn string_list(values: Vec<Option<&str>>) -> ArrayRef {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        values
+            .into_iter()
+            .for_each(|v| builder.values().append_option(v));
+        builder.append(true);
+        Arc::new(builder.finish())
+    }
+
+    #[test]
+    fn test_regexp_classify() {
+        let values = StringArray::from(vec![
+            Some("GET /api/users"),
+            Some("GET /index.html"),
+            Some("POST /api/users"),
+            Some("DELETE /api/users"),
+            None,
+        ]);
+        // overlapping patterns: the first matching pattern wins
+        let patterns = string_list(vec![
+            Some("^GET /api"),
+            Some("^GET "),
+            Some("/api/"),
+            Some("^POST "),
+        ]);
+        let labels = string_list(vec![
+            Some("api read"),
+            Some("read"),
+            Some("api"),
+            Some("write"),
+        ]);
+
+        let result = regexp_classify(&[Arc::new(values), patterns, labels]).unwrap();
+        let expected = StringArray::from(vec![
+            Some("api read"),
+            Some("read"),
+            Some("api"),
+            Some("api"),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_classify_without_match() {
+        let values = StringArray::from(vec!["abc", "123", "-"]);
+        let patterns = string_list(vec![Some(r"\d"), Some("[a-z]")]);
+        // a NULL label is returned like no match
+        let labels = string_list(vec![Some("digits"), None]);
+
+        let result = regexp_classify(&[Arc::new(values), patterns, labels]).unwrap();
+        let expected = StringArray::from(vec![None, Some("digits"), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_classify_invalid_lists() {
+        let values = Arc::new(StringArray::from(vec!["a"])) as ArrayRef;
+        let err = regexp_classify(&[
+            Arc::clone(&values),
+            string_list(vec![Some("a"), Some("b")]),
+            string_list(vec![Some("x")]),
+        ])
+        .expect_err("mismatched labels should fail");
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_classify requires a label for each pattern; got 2 patterns and 1 labels"
+        );
+
+        let err = regexp_classify(&[
+            Arc::clone(&values),
+            string_list(vec![Some("(a")]),
+            string_list(vec![Some("x")]),
+        ])
+        .expect_err("invalid pattern should fail");
+        assert!(
+            err.strip_backtrace()
+                .starts_with("Execution error: unable to compile patterns"),
+            "unexpected error: {err}"
+        );
+
+        // a NULL list of patterns classifies nothing
+        let null_list = Arc::new(ListArray::new_null(
+            Arc::new(Field::new_list_field(DataType::Utf8, true)),
+            1,
+        ));
+        let result =
+            regexp_classify(&[values, null_list, string_list(vec![Some("x")])]).unwrap();
+        assert_eq!(result.as_ref(), &StringArray::new_null(1));
+    }
+}
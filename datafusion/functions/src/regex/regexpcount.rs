@@ -15,7 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::regex::{compile_and_cache_regex, compile_regex};
+use crate::regex::regexpextract::materialize_args;
+use crate::regex::{compile_and_cache_regex, compile_regex, SharedRegexes};
 use arrow::array::{Array, ArrayRef, AsArray, Datum, Int64Array, StringArrayType};
 use arrow::datatypes::{DataType, Int64Type};
 use arrow::datatypes::{
@@ -112,6 +113,7 @@ impl ScalarUDFImpl for RegexpCountFunc {
         &self,
         args: datafusion_expr::ScalarFunctionArgs,
     ) -> Result<ColumnarValue> {
+        let shared_regexes = SharedRegexes::from_config(&args.config_options);
         let args = &args.args;
 
         let len = args
@@ -123,12 +125,11 @@ impl ScalarUDFImpl for RegexpCountFunc {
 
         let is_scalar = len.is_none();
         let inferred_length = len.unwrap_or(1);
-        let args = args
-            .iter()
-            .map(|arg| arg.to_array(inferred_length))
-            .collect::<Result<Vec<_>>>()?;
+        // a constant pattern, start and flags are kept as single element
+        // arrays, so that the pattern is compiled once for the batch
+        let args = materialize_args(args, inferred_length, &[1, 2, 3])?;
 
-        let result = regexp_count_func(&args);
+        let result = regexp_count_func_with_shared_regexes(&args, shared_regexes);
         if is_scalar {
             // If all inputs are scalar, keeps output as scalar
             let result = result.and_then(|arr| ScalarValue::try_from_array(&arr, 0));
@@ -144,6 +145,15 @@ impl ScalarUDFImpl for RegexpCountFunc {
 }
 
 pub fn regexp_count_func(args: &[ArrayRef]) -> Result<ArrayRef> {
+    regexp_count_func_with_shared_regexes(args, None)
+}
+
+/// Like [`regexp_count_func`], but compiles a scalar pattern through the
+/// patterns shared by the regex functions of the session, if any.
+fn regexp_count_func_with_shared_regexes(
+    args: &[ArrayRef],
+    shared_regexes: Option<&SharedRegexes>,
+) -> Result<ArrayRef> {
     let args_len = args.len();
     if !(2..=4).contains(&args_len) {
         return exec_err!("regexp_count was called with {args_len} arguments. It requires at least 2 and at most 4.");
@@ -164,6 +174,7 @@ pub fn regexp_count_func(args: &[ArrayRef]) -> Result<ArrayRef> {
         &args[1],
         if args_len > 2 { Some(&args[2]) } else { None },
         if args_len > 3 { Some(&args[3]) } else { None },
+        shared_regexes,
     )
     .map_err(|e| e.into())
 }
@@ -177,6 +188,7 @@ pub fn regexp_count_func(args: &[ArrayRef]) -> Result<ArrayRef> {
 /// - `regex_array`: The array of regular expression patterns to search for.
 /// - `start_array` (optional): The array of start positions for the search.
 /// - `flags_array` (optional): The array of flags to modify the search behavior (e.g., case insensitivity).
+/// - `shared_regexes` (optional): The patterns shared by the regex functions of the session, see [`SharedRegexes`].
 ///
 /// The function handles different combinations of scalar and array inputs for the regex patterns, start positions,
 /// and flags. It uses a cache to store compiled regular expressions for efficiency.
@@ -188,6 +200,7 @@ pub fn regexp_count(
     regex_array: &dyn Datum,
     start_array: Option<&dyn Datum>,
     flags_array: Option<&dyn Datum>,
+    shared_regexes: Option<&SharedRegexes>,
 ) -> Result<ArrayRef, ArrowError> {
     let (regex_array, is_regex_scalar) = regex_array.get();
     let (start_array, is_start_scalar) = start_array.map_or((None, true), |start| {
@@ -208,6 +221,7 @@ pub fn regexp_count(
             is_start_scalar,
             None,
             is_flags_scalar,
+            shared_regexes,
         ),
         (Utf8, Utf8, Some(flags_array)) if *flags_array.data_type() == Utf8 => regexp_count_inner(
             values.as_string::<i32>(),
@@ -217,6 +231,7 @@ pub fn regexp_count(
             is_start_scalar,
             Some(flags_array.as_string::<i32>()),
             is_flags_scalar,
+            shared_regexes,
         ),
         (LargeUtf8, LargeUtf8, None) => regexp_count_inner(
             values.as_string::<i64>(),
@@ -226,6 +241,7 @@ pub fn regexp_count(
             is_start_scalar,
             None,
             is_flags_scalar,
+            shared_regexes,
         ),
         (LargeUtf8, LargeUtf8, Some(flags_array)) if *flags_array.data_type() == LargeUtf8 => regexp_count_inner(
             values.as_string::<i64>(),
@@ -235,6 +251,7 @@ pub fn regexp_count(
             is_start_scalar,
             Some(flags_array.as_string::<i64>()),
             is_flags_scalar,
+            shared_regexes,
         ),
         (Utf8View, Utf8View, None) => regexp_count_inner(
             values.as_string_view(),
@@ -244,6 +261,7 @@ pub fn regexp_count(
             is_start_scalar,
             None,
             is_flags_scalar,
+            shared_regexes,
         ),
         (Utf8View, Utf8View, Some(flags_array)) if *flags_array.data_type() == Utf8View => regexp_count_inner(
             values.as_string_view(),
@@ -253,6 +271,7 @@ pub fn regexp_count(
             is_start_scalar,
             Some(flags_array.as_string_view()),
             is_flags_scalar,
+            shared_regexes,
         ),
         _ => Err(ArrowError::ComputeError(
             "regexp_count() expected the input arrays to be of type Utf8, LargeUtf8, or Utf8View and the data types of the values, regex_array, and flags_array to match".to_string(),
@@ -260,6 +279,7 @@ pub fn regexp_count(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn regexp_count_inner<'a, S>(
     values: S,
     regex_array: S,
@@ -268,6 +288,7 @@ pub fn regexp_count_inner<'a, S>(
     is_start_scalar: bool,
     flags_array: Option<S>,
     is_flags_scalar: bool,
+    shared_regexes: Option<&SharedRegexes>,
 ) -> Result<ArrayRef, ArrowError>
 where
     S: StringArrayType<'a>,
@@ -311,7 +332,14 @@ where
                 Some(regex) => regex,
             };
 
-            let pattern = compile_regex(regex, flags_scalar)?;
+            let pattern = match shared_regexes {
+                Some(shared_regexes) => {
+                    shared_regexes.get_or_compile(regex, flags_scalar, || {
+                        compile_regex(regex, flags_scalar)
+                    })?
+                }
+                None => compile_regex(regex, flags_scalar)?,
+            };
 
             Ok(Arc::new(
                 values
@@ -357,7 +385,14 @@ where
                 Some(regex) => regex,
             };
 
-            let pattern = compile_regex(regex, flags_scalar)?;
+            let pattern = match shared_regexes {
+                Some(shared_regexes) => {
+                    shared_regexes.get_or_compile(regex, flags_scalar, || {
+                        compile_regex(regex, flags_scalar)
+                    })?
+                }
+                None => compile_regex(regex, flags_scalar)?,
+            };
 
             let start_array = start_array.unwrap();
 
@@ -0,0 +1,2164 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::{PatternCache, SharedRegexes};
+use arrow::array::{
+    AnyDictionaryArray, Array, ArrayAccessor, ArrayIter, ArrayRef, AsArray, BinaryArray,
+    BooleanArray, GenericStringArray, Int64Array, LargeBinaryArray, LargeStringArray,
+    OffsetSizeTrait, PrimitiveArray, RunArray, Scalar, StringArray, StringArrayType,
+    StringBuilder, StringRunBuilder, StringViewArray, StructArray, UInt32Array,
+};
+use arrow::compute::kernels::zip::zip;
+use arrow::compute::{cast, is_not_null, take};
+use arrow::datatypes::{
+    ArrowNativeType, DataType, Field, Fields, Float64Type, Int16Type, Int32Type,
+    Int64Type, RunEndIndexType,
+};
+use datafusion_common::{
+    exec_datafusion_err, exec_err, DataFusionError, Result, ScalarValue,
+};
+use datafusion_expr::expr::{Cast, ScalarFunction, TryCast};
+use datafusion_expr::ptr_eq::PtrEq;
+use datafusion_expr::simplify::{ExprSimplifyResult, SimplifyInfo};
+use datafusion_expr::{
+    ColumnarValue, Documentation, Expr, ScalarUDF, ScalarUDFImpl, Signature,
+    TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::bytes::Regex as BytesRegex;
+use regex::Regex;
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, OnceLock};
+
+mod appenders;
+mod compilers;
+#[cfg(feature = "fancy_regex")]
+mod fancy;
+mod options;
+#[cfg(test)]
+mod tests;
+
+use appenders::{
+    CollapsingAppender, ExtractAppender, FixedWidthAppender, MatchMaskAppender,
+    TruncatingAppender, ViewSliceAppender,
+};
+pub(crate) use compilers::compile_pattern;
+use compilers::{
+    ComplexityLimitedCompiler, PosixSyntaxCompiler, PrecompiledPattern,
+    PrecompiledPatternCompiler, PreloadedPatternCompiler, SharingPatternCompiler,
+};
+pub use compilers::{DefaultPatternCompiler, PatternCompiler};
+#[cfg(feature = "fancy_regex")]
+use fancy::{fancy_regexp_extract, find_fancy_occurrence_group};
+#[cfg(feature = "fancy_regex")]
+use fancy_regex::Regex as FancyRegex;
+pub use options::{InvalidUtf8Handling, RegexSyntax, RegexpExtractConfig};
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match of the specified capture group from a string. Returns an empty string if the regular expression does not match or the capture group did not participate in the match.",
+    syntax_example = "regexp_extract(str, regexp[, idx[, occurrence][, flags]])",
+    sql_example = r#"```sql
+> select regexp_extract('100-200', '(\d+)-(\d+)', 2);
++--------------------------------------------------------------+
+| regexp_extract(Utf8("100-200"),Utf8("(\d+)-(\d+)"),Int64(2)) |
++--------------------------------------------------------------+
+| 200                                                          |
++--------------------------------------------------------------+
+> select regexp_extract('Köln', '([a-zA-Z])ö([a-zA-Z]{2})', 0);
++------------------------------------------------------------------------+
+| regexp_extract(Utf8("Köln"),Utf8("([a-zA-Z])ö([a-zA-Z]{2})"),Int64(0)) |
++------------------------------------------------------------------------+
+| Köln                                                                   |
++------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against. Alternations use leftmost-first semantics like Perl, not the leftmost-longest semantics of POSIX, which the regex crate does not support: `a|ab` matches `a` in `ab`. List the longer alternative first, as in `ab|a`, to get the POSIX match.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract. Group 0 is the entire match. Defaults to 1. An integral float such as `1.0` is accepted, a fraction such as `1.5` is an error. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "occurrence",
+        description = "Optional occurrence of the match to extract the capture group from, counting from 1. Defaults to 1. Returns an empty string if there are fewer matches. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "flags",
+        description = r#"Optional regular expression flags that control the behavior of the regular expression. Plans built programmatically can pass a `RegexpExtractOptions` struct instead. The following flags are supported:
+  - **i**: case-insensitive: letters match both upper and lower case
+  - **m**: multi-line mode: ^ and $ match begin/end of line
+  - **s**: allow . to match \n
+  - **R**: enables CRLF mode: when multi-line mode is enabled, \r\n is used
+  - **U**: swap the meaning of x* and x*?
+  - **-u**: disable Unicode mode: \d, \w, \s and \b only match ASCII characters, for example \d only matches 0-9. Patterns that can match arbitrary bytes, such as `.`, must re-enable Unicode mode locally with `(?u:.)`. To restrict only part of a pattern, use `(?-u:\d)` instead of the flag."#
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractFunc {
+    signature: Signature,
+    compiler: PtrEq<Arc<dyn PatternCompiler>>,
+    /// Whether the patterns are compiled like the [`DefaultPatternCompiler`],
+    /// and so can be shared with the other regex functions, see
+    /// [`SharedRegexes`]
+    shares_patterns: bool,
+    /// The literal pattern of the call, compiled ahead of execution by
+    /// [`ScalarUDFImpl::simplify`]
+    precompiled: Option<PrecompiledPattern>,
+    /// The byte width of the `FixedSizeBinary` output, if any, see
+    /// [`RegexpExtractFunc::with_fixed_width`]
+    fixed_width: Option<i32>,
+    /// The pattern of the rows whose pattern is NULL, see
+    /// [`RegexpExtractFunc::with_default_pattern`]
+    default_pattern: Option<String>,
+    /// The transform of the strings before matching, see
+    /// [`RegexpExtractFunc::with_input_transform`]
+    input_transform: Option<InputTransform>,
+}
+
+/// A transform of the strings that [`RegexpExtractFunc`] matches against, see
+/// [`RegexpExtractFunc::with_input_transform`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputTransform {
+    /// Matches against the lowercase string, like `lower(str)`
+    Lower,
+    /// Matches against the uppercase string, like `upper(str)`
+    Upper,
+}
+
+/// Applies the optional [`InputTransform`] to the strings of a batch, one at
+/// a time.
+struct TransformedInput {
+    transform: Option<InputTransform>,
+    buffer: String,
+}
+
+impl TransformedInput {
+    fn new(transform: Option<InputTransform>) -> Self {
+        Self {
+            transform,
+            buffer: String::new(),
+        }
+    }
+
+    /// Returns `value` transformed, or unchanged without a transform.
+    fn apply<'v>(&'v mut self, value: &'v str) -> &'v str {
+        let Some(transform) = self.transform else {
+            return value;
+        };
+        if value.is_ascii() {
+            self.buffer.clear();
+            self.buffer.push_str(value);
+            match transform {
+                InputTransform::Lower => self.buffer.make_ascii_lowercase(),
+                InputTransform::Upper => self.buffer.make_ascii_uppercase(),
+            }
+        } else {
+            // mapping a character may depend on its neighbours, such as a
+            // final sigma, so only the whole string is transformed like
+            // `lower` and `upper` do
+            self.buffer = match transform {
+                InputTransform::Lower => value.to_lowercase(),
+                InputTransform::Upper => value.to_uppercase(),
+            };
+        }
+        &self.buffer
+    }
+}
+
+impl Default for RegexpExtractFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        let mut signatures = vec![
+            TypeSignature::Exact(vec![Utf8View, Utf8View]),
+            TypeSignature::Exact(vec![Utf8, Utf8]),
+            TypeSignature::Exact(vec![LargeUtf8, LargeUtf8]),
+            TypeSignature::Exact(vec![Utf8View, Utf8View, Int64]),
+            TypeSignature::Exact(vec![Utf8, Utf8, Int64]),
+            TypeSignature::Exact(vec![LargeUtf8, LargeUtf8, Int64]),
+            TypeSignature::Exact(vec![Utf8View, Utf8View, Int64, Utf8View]),
+            TypeSignature::Exact(vec![Utf8, Utf8, Int64, Utf8]),
+            TypeSignature::Exact(vec![LargeUtf8, LargeUtf8, Int64, LargeUtf8]),
+            TypeSignature::Exact(vec![
+                Utf8View,
+                Utf8View,
+                Int64,
+                RegexpExtractOptions::data_type(),
+            ]),
+            TypeSignature::Exact(vec![
+                Utf8,
+                Utf8,
+                Int64,
+                RegexpExtractOptions::data_type(),
+            ]),
+            TypeSignature::Exact(vec![
+                LargeUtf8,
+                LargeUtf8,
+                Int64,
+                RegexpExtractOptions::data_type(),
+            ]),
+            TypeSignature::Exact(vec![Utf8View, Utf8View, Int64, Int64]),
+            TypeSignature::Exact(vec![Utf8, Utf8, Int64, Int64]),
+            TypeSignature::Exact(vec![LargeUtf8, LargeUtf8, Int64, Int64]),
+            TypeSignature::Exact(vec![Utf8View, Utf8View, Int64, Int64, Utf8View]),
+            TypeSignature::Exact(vec![Utf8, Utf8, Int64, Int64, Utf8]),
+            TypeSignature::Exact(vec![LargeUtf8, LargeUtf8, Int64, Int64, LargeUtf8]),
+            TypeSignature::Exact(vec![
+                Utf8View,
+                Utf8View,
+                Int64,
+                Int64,
+                RegexpExtractOptions::data_type(),
+            ]),
+            TypeSignature::Exact(vec![
+                Utf8,
+                Utf8,
+                Int64,
+                Int64,
+                RegexpExtractOptions::data_type(),
+            ]),
+            TypeSignature::Exact(vec![
+                LargeUtf8,
+                LargeUtf8,
+                Int64,
+                Int64,
+                RegexpExtractOptions::data_type(),
+            ]),
+        ];
+        // an integral float group index, as in `regexp_extract(str, regexp, 1.0)`
+        // from generated SQL, is converted to an integer when invoked
+        let float_group_index = signatures
+            .iter()
+            .filter_map(|signature| match signature {
+                TypeSignature::Exact(types) if types.len() >= 3 => {
+                    let mut types = types.clone();
+                    types[2] = Float64;
+                    Some(TypeSignature::Exact(types))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        signatures.extend(float_group_index);
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Immutable),
+            compiler: PtrEq::from(
+                Arc::new(DefaultPatternCompiler) as Arc<dyn PatternCompiler>
+            ),
+            shares_patterns: true,
+            precompiled: None,
+            fixed_width: None,
+            default_pattern: None,
+            input_transform: None,
+        }
+    }
+
+    /// Uses `compiler` to compile the patterns instead of the
+    /// [`DefaultPatternCompiler`].
+    pub fn with_pattern_compiler(mut self, compiler: Arc<dyn PatternCompiler>) -> Self {
+        self.compiler = PtrEq::from(compiler);
+        self.shares_patterns = false;
+        self
+    }
+
+    /// Returns each extracted string as a `FixedSizeBinary` of `width` bytes
+    /// instead of as `Utf8`, see [`regexp_extract_fixed_width`].
+    pub fn with_fixed_width(mut self, width: i32) -> Self {
+        self.fixed_width = Some(width);
+        self
+    }
+
+    /// Matches against each string after applying `transform`, like
+    /// `regexp_extract(lower(str), ...)` for [`InputTransform::Lower`].
+    ///
+    /// The transform is fused into the match loop, which reuses one buffer for
+    /// the transformed ASCII strings, instead of materializing a transformed
+    /// copy of the whole array. The extracted strings are taken from the
+    /// transformed strings.
+    pub fn with_input_transform(mut self, transform: InputTransform) -> Self {
+        self.input_transform = Some(transform);
+        self
+    }
+
+    /// Compiles `patterns` with the pattern compiler of the function ahead of
+    /// execution, failing on the first invalid pattern.
+    ///
+    /// Every query that uses one of the patterns without flags, as a literal or
+    /// in a pattern column, then uses the preloaded regex instead of compiling
+    /// it, so that even the first query of a deployment with a fixed set of
+    /// rules does not pay for the compilation. The preloaded patterns are kept
+    /// by the compiler, so [`RegexpExtractFunc::with_pattern_compiler`] must
+    /// be called before this.
+    pub fn preload_patterns(mut self, patterns: &[&str]) -> Result<Self> {
+        let fallback = Arc::clone(&self.compiler);
+        let preloaded = patterns
+            .iter()
+            .map(|pattern| Ok(((*pattern).to_string(), fallback.compile(pattern, None)?)))
+            .collect::<Result<_>>()?;
+        self.compiler = PtrEq::from(Arc::new(PreloadedPatternCompiler {
+            preloaded,
+            fallback,
+        }) as Arc<dyn PatternCompiler>);
+        Ok(self)
+    }
+
+    /// Extracts with `pattern` from the rows whose pattern is NULL, which
+    /// otherwise yield NULL.
+    ///
+    /// This lets a table of rules whose pattern column is missing some
+    /// patterns fall back to a catch-all pattern. The default pattern is
+    /// compiled with the flags of the row, like the pattern it replaces.
+    pub fn with_default_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.default_pattern = Some(pattern.into());
+        self
+    }
+
+    /// Returns the literal pattern and flags precompiled by
+    /// [`ScalarUDFImpl::simplify`] as opaque bytes, or `None` if there is no
+    /// literal pattern.
+    ///
+    /// A [`Regex`] cannot be serialized, so an extension codec can store these
+    /// bytes in a serialized plan instead and pass them to
+    /// [`RegexpExtractFunc::with_serialized_pattern`] when deserializing it.
+    pub fn serialized_pattern(&self) -> Option<Vec<u8>> {
+        let precompiled = self.precompiled.as_ref()?;
+        let mut bytes = Vec::with_capacity(
+            9 + precompiled.pattern.len()
+                + precompiled.flags.as_ref().map_or(0, String::len),
+        );
+        bytes.extend_from_slice(&(precompiled.pattern.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(precompiled.pattern.as_bytes());
+        if let Some(flags) = &precompiled.flags {
+            bytes.push(1);
+            bytes.extend_from_slice(flags.as_bytes());
+        } else {
+            bytes.push(0);
+        }
+        Some(bytes)
+    }
+
+    /// Restores the pattern and flags returned by
+    /// [`RegexpExtractFunc::serialized_pattern`].
+    ///
+    /// The pattern is only compiled when the function is first evaluated, with
+    /// the pattern compiler of the function, so a function serialized with a
+    /// [`RegexpExtractFunc::with_pattern_compiler`] must be restored with the
+    /// same compiler. The patterns of the [`DefaultPatternCompiler`] are
+    /// shared by the session, see [`SharedRegexes`], so a pattern that was
+    /// already restored and evaluated before in the session, for example by
+    /// an earlier task of the same plan, is not compiled again.
+    pub fn with_serialized_pattern(mut self, bytes: &[u8]) -> Result<Self> {
+        let invalid =
+            || exec_datafusion_err!("invalid serialized regexp_extract pattern");
+        let (pattern_len, rest) = bytes.split_first_chunk::<8>().ok_or_else(invalid)?;
+        let pattern_len =
+            usize::try_from(u64::from_le_bytes(*pattern_len)).map_err(|_| invalid())?;
+        if rest.len() <= pattern_len {
+            return Err(invalid());
+        }
+        let (pattern, rest) = rest.split_at(pattern_len);
+        let flags = match rest {
+            [0] => None,
+            [1, flags @ ..] => Some(flags),
+            _ => return Err(invalid()),
+        };
+        let to_string = |bytes: &[u8]| {
+            std::str::from_utf8(bytes)
+                .map(str::to_string)
+                .map_err(|_| invalid())
+        };
+        self.precompiled = Some(PrecompiledPattern {
+            pattern: to_string(pattern)?,
+            flags: flags.map(to_string).transpose()?,
+            regex: Arc::new(OnceLock::new()),
+        });
+        Ok(self)
+    }
+
+    /// Like [`ScalarUDFImpl::invoke_with_args`], but also returns a mask of
+    /// the rows the pattern matched, see [`regexp_extract_with_match_mask`].
+    ///
+    /// This lets an operator that both filters on the match and uses the
+    /// extracted string evaluate the pattern once.
+    pub fn invoke_with_match_mask(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<(ColumnarValue, ColumnarValue)> {
+        let Some((builder, is_scalar)) =
+            self.extract_into(args, MatchMaskAppender::with_capacity)?
+        else {
+            return Ok((
+                ColumnarValue::Scalar(ScalarValue::Utf8(None)),
+                ColumnarValue::Scalar(ScalarValue::Boolean(None)),
+            ));
+        };
+        let (values, mask) = builder.finish_with_mask();
+        Ok((
+            to_columnar_value(values, is_scalar)?,
+            to_columnar_value(Arc::new(mask), is_scalar)?,
+        ))
+    }
+
+    /// Extracts into the appender created by `new_builder` for the number of
+    /// rows, returning it along with whether all the arguments are scalars,
+    /// or `None` if they are and the pattern is NULL.
+    fn extract_into<A: ExtractAppender>(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+        new_builder: impl FnOnce(usize) -> A,
+    ) -> Result<Option<(A, bool)>> {
+        let shared = SharedRegexes::from_config(&args.config_options);
+        let config = RegexpExtractConfig::from_config(&args.config_options);
+        let max_capture_length = config.max_capture_length;
+        let truncate_graphemes = config.truncate_graphemes;
+        let collapse_whitespace = config.collapse_whitespace;
+        let invalid_utf8 = config.invalid_utf8;
+        let max_complexity = config.max_pattern_complexity;
+        let max_compile_errors = config.max_compile_errors;
+        let syntax = config.syntax;
+        let mut args = args.args;
+        let flags_index = flags_index(args.get(3).map(|a| a.data_type()).as_ref());
+        resolve_options_argument(&mut args, flags_index)?;
+        if let Some(group_index) = args.get_mut(2) {
+            *group_index = integral_group_index(group_index)?;
+        }
+        let args = &args;
+        let len = args
+            .iter()
+            .fold(Option::<usize>::None, |acc, arg| match arg {
+                ColumnarValue::Scalar(_) => acc,
+                ColumnarValue::Array(a) => Some(a.len()),
+            });
+
+        let is_scalar = len.is_none();
+        if is_scalar
+            && self.default_pattern.is_none()
+            && matches!(&args[1], ColumnarValue::Scalar(pattern) if pattern.is_null())
+        {
+            // a NULL pattern yields NULL without compiling anything
+            return Ok(None);
+        }
+
+        let inferred_length = len.unwrap_or(1);
+        // the pattern and the flags are compiled once if both are scalars
+        let mut args = materialize_args(args, inferred_length, &[1, flags_index])?;
+        if let Some(default_pattern) = &self.default_pattern {
+            args[1] = fill_null_patterns(&args[1], default_pattern, args[0].data_type())?;
+        }
+        if invalid_utf8 != InvalidUtf8Handling::Trust {
+            args[0] = check_utf8(&args[0], invalid_utf8)?;
+        }
+        if config.null_group_index_is_error
+            && args
+                .get(2)
+                .is_some_and(|group_index| group_index.null_count() > 0)
+        {
+            return exec_err!(
+                "regexp_extract group index must not be NULL when regexp_extract.null_group_index_is_error is enabled"
+            );
+        }
+
+        let builder = new_builder(inferred_length);
+        let builder = match max_capture_length {
+            Some(max_len) => {
+                let builder = TruncatingAppender {
+                    inner: builder,
+                    max_len,
+                    graphemes: truncate_graphemes,
+                };
+                self.extract_collapsing(
+                    &args,
+                    shared,
+                    max_complexity,
+                    max_compile_errors,
+                    syntax,
+                    collapse_whitespace,
+                    builder,
+                )?
+                .inner
+            }
+            None => self.extract_collapsing(
+                &args,
+                shared,
+                max_complexity,
+                max_compile_errors,
+                syntax,
+                collapse_whitespace,
+                builder,
+            )?,
+        };
+        Ok(Some((builder, is_scalar)))
+    }
+
+    /// Returns the pattern compiler of the function, which shares the patterns
+    /// through `shared` if they are compiled like the
+    /// [`DefaultPatternCompiler`].
+    fn sharing_compiler<'a>(
+        &'a self,
+        shared: Option<&'a SharedRegexes>,
+    ) -> SharingPatternCompiler<'a> {
+        SharingPatternCompiler {
+            inner: self.compiler.as_ref(),
+            shared: shared.filter(|_| self.shares_patterns),
+        }
+    }
+
+    /// Like [`Self::extract_with_compiler`], but collapses the whitespace of
+    /// the captures before appending them if `collapse_whitespace` is set.
+    #[allow(clippy::too_many_arguments)]
+    fn extract_collapsing<A: ExtractAppender>(
+        &self,
+        args: &[ArrayRef],
+        shared: Option<&SharedRegexes>,
+        max_complexity: Option<usize>,
+        max_compile_errors: usize,
+        syntax: RegexSyntax,
+        collapse_whitespace: bool,
+        builder: A,
+    ) -> Result<A> {
+        if !collapse_whitespace {
+            return self.extract_with_compiler(
+                args,
+                shared,
+                max_complexity,
+                max_compile_errors,
+                syntax,
+                builder,
+            );
+        }
+        let builder = CollapsingAppender {
+            inner: builder,
+            collapsed: String::new(),
+        };
+        Ok(self
+            .extract_with_compiler(
+                args,
+                shared,
+                max_complexity,
+                max_compile_errors,
+                syntax,
+                builder,
+            )?
+            .inner)
+    }
+
+    /// Extracts from the materialized `args` into `builder` with the
+    /// precompiled pattern, if any, or the pattern compiler of the function,
+    /// sharing the patterns through `shared` if the compiler allows it,
+    /// reading the patterns with `syntax`, rejecting the patterns scoring
+    /// above `max_complexity` and collecting up to `max_compile_errors`
+    /// compile errors before failing.
+    fn extract_with_compiler<A: ExtractAppender>(
+        &self,
+        args: &[ArrayRef],
+        shared: Option<&SharedRegexes>,
+        max_complexity: Option<usize>,
+        max_compile_errors: usize,
+        syntax: RegexSyntax,
+        builder: A,
+    ) -> Result<A> {
+        let sharing = self.sharing_compiler(shared);
+        let compiler: &dyn PatternCompiler = &sharing;
+        let precompiled;
+        let compiler: &dyn PatternCompiler = match &self.precompiled {
+            Some(p) => {
+                precompiled = PrecompiledPatternCompiler {
+                    precompiled: p,
+                    fallback: compiler,
+                };
+                &precompiled
+            }
+            None => compiler,
+        };
+        // a rewritten pattern differs from the precompiled one unless the
+        // rewrite left it unchanged, so the precompiled regex stays valid
+        let posix;
+        let compiler: &dyn PatternCompiler = match syntax {
+            RegexSyntax::Rust => compiler,
+            RegexSyntax::Posix => {
+                posix = PosixSyntaxCompiler { inner: compiler };
+                &posix
+            }
+        };
+        let limited;
+        let compiler: &dyn PatternCompiler = match max_complexity {
+            Some(max_complexity) => {
+                limited = ComplexityLimitedCompiler {
+                    inner: compiler,
+                    max_complexity,
+                };
+                &limited
+            }
+            None => compiler,
+        };
+        let cache_size = shared.map_or(
+            DEFAULT_PATTERN_CACHE_SIZE,
+            SharedRegexes::pattern_cache_size,
+        );
+        regexp_extract_into(
+            args,
+            compiler,
+            cache_size,
+            max_compile_errors,
+            self.input_transform,
+            builder,
+        )
+    }
+}
+
+/// Converts the output of an invocation to a scalar if all inputs are scalars.
+fn to_columnar_value(array: ArrayRef, is_scalar: bool) -> Result<ColumnarValue> {
+    if is_scalar {
+        ScalarValue::try_from_array(&array, 0).map(ColumnarValue::Scalar)
+    } else {
+        Ok(ColumnarValue::Array(array))
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        match self.fixed_width {
+            Some(width) => {
+                validate_fixed_width(width)?;
+                Ok(DataType::FixedSizeBinary(width))
+            }
+            None => Ok(DataType::Utf8),
+        }
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        if let Some(width) = self.fixed_width {
+            let byte_width = validate_fixed_width(width)?;
+            let Some((builder, is_scalar)) = self.extract_into(args, |len| {
+                FixedWidthAppender::with_capacity(len, byte_width)
+            })?
+            else {
+                return Ok(ColumnarValue::Scalar(ScalarValue::FixedSizeBinary(
+                    width, None,
+                )));
+            };
+            return to_columnar_value(builder.finish(), is_scalar);
+        }
+
+        let Some((builder, is_scalar)) =
+            self.extract_into(args, |len| StringBuilder::with_capacity(len, 0))?
+        else {
+            return Ok(ColumnarValue::Scalar(ScalarValue::Utf8(None)));
+        };
+        to_columnar_value(builder.finish(), is_scalar)
+    }
+
+    /// Compiles a literal pattern during planning, so that the first batch of
+    /// every partition does not pay for the compilation.
+    fn simplify(
+        &self,
+        args: Vec<Expr>,
+        info: &dyn SimplifyInfo,
+    ) -> Result<ExprSimplifyResult> {
+        if self.precompiled.is_some() {
+            return Ok(ExprSimplifyResult::Original(args));
+        }
+
+        let pattern = match args.get(1).and_then(string_literal) {
+            Some(Some(pattern)) => pattern.to_string(),
+            _ => return Ok(ExprSimplifyResult::Original(args)),
+        };
+        let flags_index = match args.get(3).map(|arg| info.get_data_type(arg)) {
+            None => 3,
+            Some(Ok(data_type)) => flags_index(Some(&data_type)),
+            Some(Err(_)) => return Ok(ExprSimplifyResult::Original(args)),
+        };
+        let flags = match args.get(flags_index) {
+            None => None,
+            Some(Expr::Literal(ScalarValue::Struct(options), _)) => {
+                RegexpExtractOptions::try_from_struct(options)?
+                    .map(|options| options.flags())
+            }
+            Some(flags) => match string_literal(flags) {
+                Some(flags) => flags.map(str::to_string),
+                None => return Ok(ExprSimplifyResult::Original(args)),
+            },
+        };
+
+        // the pattern is shared with the other regex functions of the query
+        // if the session that is planning it shares its patterns
+        let shared = info
+            .execution_props()
+            .config_options()
+            .and_then(|config| SharedRegexes::from_config(config));
+        let compiler = self.sharing_compiler(shared);
+        // an invalid pattern is reported when the expression is evaluated
+        let Ok(regex) = compiler.compile(&pattern, flags.as_deref()) else {
+            return Ok(ExprSimplifyResult::Original(args));
+        };
+        let precompiled = PrecompiledPattern {
+            pattern,
+            flags,
+            regex: Arc::new(OnceLock::from(regex)),
+        };
+        let udf = ScalarUDF::new_from_impl(Self {
+            signature: self.signature.clone(),
+            compiler: self.compiler.clone(),
+            shares_patterns: self.shares_patterns,
+            precompiled: Some(precompiled),
+            fixed_width: self.fixed_width,
+            default_pattern: self.default_pattern.clone(),
+            input_transform: self.input_transform,
+        });
+        Ok(ExprSimplifyResult::Simplified(Expr::ScalarFunction(
+            ScalarFunction::new_udf(Arc::new(udf), args),
+        )))
+    }
+
+    /// `regexp_extract` is pure: it has no side effects and evaluates all of
+    /// its arguments, so it does not short-circuit and common subexpression
+    /// elimination may share it. Conditional expressions such as `CASE` and
+    /// `coalesce` only evaluate it for the rows that reach it, which may skip
+    /// invalid patterns of the other rows.
+    fn short_circuits(&self) -> bool {
+        false
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Options of `regexp_extract`, an alternative to the flags string for plans
+/// that are built programmatically.
+///
+/// Pass [`RegexpExtractOptions::to_scalar`] as the fourth argument of
+/// `regexp_extract` instead of the flags. The options must be a constant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RegexpExtractOptions {
+    /// Letters match both upper and lower case, like the `i` flag
+    pub case_insensitive: bool,
+    /// `^` and `$` match the begin and end of lines, like the `m` flag
+    pub multiline: bool,
+    /// `.` matches `\n`, like the `s` flag
+    pub dotall: bool,
+}
+
+impl RegexpExtractOptions {
+    /// The struct type of the options argument.
+    pub fn data_type() -> DataType {
+        DataType::Struct(Fields::from(vec![
+            Field::new("case_insensitive", DataType::Boolean, false),
+            Field::new("multiline", DataType::Boolean, false),
+            Field::new("dotall", DataType::Boolean, false),
+        ]))
+    }
+
+    /// Returns the options as a struct scalar.
+    pub fn to_scalar(&self) -> ScalarValue {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(BooleanArray::from(vec![self.case_insensitive])),
+            Arc::new(BooleanArray::from(vec![self.multiline])),
+            Arc::new(BooleanArray::from(vec![self.dotall])),
+        ];
+        let DataType::Struct(fields) = Self::data_type() else {
+            unreachable!("the options are a struct")
+        };
+        ScalarValue::Struct(Arc::new(StructArray::new(fields, columns, None)))
+    }
+
+    /// Reads the options from the first row of `options`, returns `None` if
+    /// it is NULL.
+    fn try_from_struct(options: &StructArray) -> Result<Option<Self>> {
+        if options.is_null(0) {
+            return Ok(None);
+        }
+        let option = |name: &str| match options.column_by_name(name) {
+            Some(column) if column.data_type() == &DataType::Boolean => {
+                let column = column.as_boolean();
+                Ok(column.is_valid(0) && column.value(0))
+            }
+            _ => exec_err!(
+                "regexp_extract options must have a boolean field '{name}', got {}",
+                options.data_type()
+            ),
+        };
+        Ok(Some(Self {
+            case_insensitive: option("case_insensitive")?,
+            multiline: option("multiline")?,
+            dotall: option("dotall")?,
+        }))
+    }
+
+    /// Returns the equivalent flags string.
+    fn flags(&self) -> String {
+        [
+            (self.case_insensitive, 'i'),
+            (self.multiline, 'm'),
+            (self.dotall, 's'),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, flag)| enabled.then_some(flag))
+        .collect()
+    }
+}
+
+/// Returns the string of `expr` if it is a string literal, possibly NULL.
+///
+/// Casts between string types leave the string unchanged, so a literal
+/// wrapped in such casts, like `CAST('(\d+)' AS VARCHAR)` in generated SQL,
+/// is seen through.
+fn string_literal(expr: &Expr) -> Option<Option<&str>> {
+    match expr {
+        Expr::Literal(
+            ScalarValue::Utf8(value)
+            | ScalarValue::LargeUtf8(value)
+            | ScalarValue::Utf8View(value),
+            _,
+        ) => Some(value.as_deref()),
+        Expr::Cast(Cast { expr, data_type })
+        | Expr::TryCast(TryCast { expr, data_type })
+            if matches!(
+                data_type,
+                DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+            ) =>
+        {
+            string_literal(expr)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the index of the flags argument, which follows the occurrence if
+/// the fourth argument is an integer.
+fn flags_index(fourth_arg_type: Option<&DataType>) -> usize {
+    match fourth_arg_type {
+        Some(DataType::Int64) => 4,
+        _ => 3,
+    }
+}
+
+/// Converts a `Float64` group index to an `Int64`, failing on the first index
+/// that is not integral, such as `1.5`. Any other group index is returned
+/// unchanged.
+fn integral_group_index(group_index: &ColumnarValue) -> Result<ColumnarValue> {
+    let to_integer = |index: f64| {
+        // a fraction of an infinity or NaN is NaN
+        if index.fract() == 0.0 {
+            // an index beyond the range of i64 saturates, which is out of range
+            // for every pattern anyway
+            Ok(index as i64)
+        } else {
+            exec_err!("regexp_extract group index must be an integer, got {index}")
+        }
+    };
+    match group_index {
+        ColumnarValue::Scalar(ScalarValue::Float64(index)) => Ok(ColumnarValue::Scalar(
+            ScalarValue::Int64(index.map(to_integer).transpose()?),
+        )),
+        ColumnarValue::Array(indices) if indices.data_type() == &DataType::Float64 => {
+            let indices = indices
+                .as_primitive::<Float64Type>()
+                .iter()
+                .map(|index| index.map(to_integer).transpose())
+                .collect::<Result<Int64Array>>()?;
+            Ok(ColumnarValue::Array(Arc::new(indices)))
+        }
+        _ => Ok(group_index.clone()),
+    }
+}
+
+/// Replaces an options struct passed as the flags argument with the
+/// equivalent flags string.
+fn resolve_options_argument(
+    args: &mut [ColumnarValue],
+    flags_index: usize,
+) -> Result<()> {
+    let options = match args.get(flags_index) {
+        Some(ColumnarValue::Scalar(ScalarValue::Struct(options))) => {
+            RegexpExtractOptions::try_from_struct(options)?
+        }
+        Some(ColumnarValue::Array(options))
+            if matches!(options.data_type(), DataType::Struct(_)) =>
+        {
+            return exec_err!("regexp_extract options must be a constant");
+        }
+        _ => return Ok(()),
+    };
+
+    let flags = options.map(|options| options.flags());
+    args[flags_index] = ColumnarValue::Scalar(match args[0].data_type() {
+        DataType::LargeUtf8 => ScalarValue::LargeUtf8(flags),
+        DataType::Utf8View => ScalarValue::Utf8View(flags),
+        _ => ScalarValue::Utf8(flags),
+    });
+    Ok(())
+}
+
+/// Replaces the NULL patterns of `patterns` with `default_pattern`.
+///
+/// Dictionary encoded patterns with NULLs are decoded to `value_type`, the
+/// type of the values, since the default need not be one of the dictionary
+/// values.
+fn fill_null_patterns(
+    patterns: &ArrayRef,
+    default_pattern: &str,
+    value_type: &DataType,
+) -> Result<ArrayRef> {
+    if patterns.logical_null_count() == 0 {
+        return Ok(Arc::clone(patterns));
+    }
+    let patterns = match patterns.data_type() {
+        DataType::Dictionary(_, _) => cast(patterns, value_type)?,
+        _ => Arc::clone(patterns),
+    };
+    let default_pattern = ScalarValue::from(default_pattern)
+        .cast_to(patterns.data_type())?
+        .to_array()?;
+    Ok(zip(
+        &is_not_null(&patterns)?,
+        &patterns,
+        &Scalar::new(default_pattern),
+    )?)
+}
+
+/// Converts the arguments of the `regexp_extract` family of functions to arrays.
+///
+/// Scalars at `scalar_indices`, such as the pattern, are kept as single
+/// element arrays so that the pattern is compiled only once, all other
+/// arguments are expanded to `inferred_length`.
+pub(crate) fn materialize_args(
+    args: &[ColumnarValue],
+    inferred_length: usize,
+    scalar_indices: &[usize],
+) -> Result<Vec<ArrayRef>> {
+    args.iter()
+        .enumerate()
+        .map(|(idx, arg)| match arg {
+            ColumnarValue::Scalar(scalar) if scalar_indices.contains(&idx) => {
+                scalar.to_array()
+            }
+            arg => arg.to_array(inferred_length),
+        })
+        .collect()
+}
+
+/// Invokes a function of the `regexp_extract` family on `args`.
+///
+/// The arguments are materialized with [`materialize_args`] and passed to
+/// `eval`. If all arguments are scalars the result is returned as a scalar.
+pub(crate) fn invoke_materialized(
+    args: &[ColumnarValue],
+    scalar_indices: &[usize],
+    eval: impl FnOnce(&[ArrayRef]) -> Result<ArrayRef>,
+) -> Result<ColumnarValue> {
+    let len = args
+        .iter()
+        .fold(Option::<usize>::None, |acc, arg| match arg {
+            ColumnarValue::Scalar(_) => acc,
+            ColumnarValue::Array(a) => Some(a.len()),
+        });
+
+    let is_scalar = len.is_none();
+    let inferred_length = len.unwrap_or(1);
+    let args = materialize_args(args, inferred_length, scalar_indices)?;
+
+    let result = eval(&args);
+    if is_scalar {
+        // If all inputs are scalar, keeps output as scalar
+        let result = result.and_then(|arr| ScalarValue::try_from_array(&arr, 0));
+        result.map(ColumnarValue::Scalar)
+    } else {
+        result.map(ColumnarValue::Array)
+    }
+}
+
+/// The patterns of a batch, compiled on first use.
+///
+/// A scalar pattern, a single element array, applies to every row and is
+/// compiled once. Otherwise every distinct pattern of the batch is compiled
+/// once and cached.
+pub(crate) struct RowPatterns<'a, S> {
+    patterns: S,
+    scalar_regex: Option<Regex>,
+    regex_cache: HashMap<&'a str, Regex>,
+}
+
+impl<'a, S> RowPatterns<'a, S>
+where
+    S: StringArrayType<'a>,
+{
+    /// Creates the patterns of a batch of `num_rows` rows, returning an error
+    /// mentioning `name` if `patterns` is neither a scalar nor of `num_rows`
+    /// elements.
+    pub(crate) fn try_new(name: &str, patterns: S, num_rows: usize) -> Result<Self> {
+        let scalar_regex = if patterns.len() == 1 && !patterns.is_null(0) {
+            // the pattern is a scalar: compile it once for all rows
+            Some(compile_pattern(patterns.value(0), None)?)
+        } else if patterns.len() != 1 && num_rows != patterns.len() {
+            return exec_err!(
+                "{name} pattern array must be the same length as the values array; got {} and {}",
+                patterns.len(),
+                num_rows
+            );
+        } else {
+            None
+        };
+        Ok(Self {
+            patterns,
+            scalar_regex,
+            regex_cache: HashMap::new(),
+        })
+    }
+
+    /// Returns true if the pattern of row `i` is NULL.
+    pub(crate) fn is_null(&self, i: usize) -> bool {
+        self.patterns.is_null(self.index(i))
+    }
+
+    /// Returns true if the pattern of row `i` is not NULL.
+    pub(crate) fn is_valid(&self, i: usize) -> bool {
+        self.patterns.is_valid(self.index(i))
+    }
+
+    /// Returns the compiled pattern of row `i`, which must not be NULL.
+    pub(crate) fn get(&mut self, i: usize) -> Result<&Regex> {
+        if let Some(regex) = &self.scalar_regex {
+            return Ok(regex);
+        }
+        let pattern = self.patterns.value(self.index(i));
+        let regex = match self.regex_cache.entry(pattern) {
+            Entry::Occupied(occupied_entry) => occupied_entry.into_mut(),
+            Entry::Vacant(vacant_entry) => {
+                vacant_entry.insert(compile_pattern(pattern, None)?)
+            }
+        };
+        Ok(regex)
+    }
+
+    fn index(&self, i: usize) -> usize {
+        if self.patterns.len() == 1 {
+            0
+        } else {
+            i
+        }
+    }
+}
+
+/// Extracts the specified capture group of the first match of a regular
+/// expression from each string of `args[0]`.
+///
+/// `args[1]` holds the patterns, either one per row or a single pattern that
+/// is applied to every row. The optional `args[2]` holds the capture group
+/// index, which defaults to 1, and the optional `args[3]` holds the flags,
+/// either one per row or a single value for every row. If `args[3]` is an
+/// `Int64` array instead, it holds the occurrence of the match to extract
+/// from, which defaults to 1, and the optional flags move to `args[4]`.
+///
+/// If `args[0]` is run-end encoded, so is the output, and with a scalar
+/// pattern each run value is extracted from once.
+///
+/// Each string is matched in place in the buffer of `args[0]` in time linear
+/// in its length, so even a very large `LargeUtf8` value is neither copied
+/// nor split into chunks, and a match may span any position of it.
+pub fn regexp_extract(args: &[ArrayRef]) -> Result<ArrayRef> {
+    regexp_extract_with_compiler(args, &DefaultPatternCompiler)
+}
+
+/// Like [`regexp_extract`], but compiles the patterns with `compiler`.
+pub fn regexp_extract_with_compiler(
+    args: &[ArrayRef],
+    compiler: &dyn PatternCompiler,
+) -> Result<ArrayRef> {
+    regexp_extract_with_cache_size(args, compiler, DEFAULT_PATTERN_CACHE_SIZE)
+}
+
+/// The default of the `regex.pattern_cache_size` option, see
+/// [`SharedRegexes`].
+pub(crate) const DEFAULT_PATTERN_CACHE_SIZE: usize = 1024;
+
+/// The default of the `regexp_extract.max_compile_errors` option, which fails
+/// on the first pattern that does not compile.
+const FAIL_FAST: usize = 1;
+
+/// Like [`regexp_extract`], but also returns a mask of the rows the pattern
+/// matched.
+///
+/// The mask tells a row without a match apart from a row whose capture group
+/// is empty, which both extract the empty string, so that a filter on the
+/// match does not have to evaluate the pattern again. It is NULL where the
+/// extracted string is NULL.
+pub fn regexp_extract_with_match_mask(
+    args: &[ArrayRef],
+) -> Result<(ArrayRef, BooleanArray)> {
+    let len = args.first().map_or(0, |a| a.len());
+    regexp_extract_into(
+        args,
+        &DefaultPatternCompiler,
+        DEFAULT_PATTERN_CACHE_SIZE,
+        FAIL_FAST,
+        None,
+        MatchMaskAppender::with_capacity(len),
+    )
+    .map(MatchMaskAppender::finish_with_mask)
+}
+
+/// Like [`regexp_extract`], but returns each extracted string as a
+/// `FixedSizeBinary` of `width` bytes, for consumers that need fixed-width
+/// values.
+///
+/// Strings shorter than `width` bytes are right-padded with NUL bytes, so
+/// that a row without a match is all NUL bytes. Longer strings are truncated
+/// to their first `width` bytes, which may cut a multibyte character.
+pub fn regexp_extract_fixed_width(args: &[ArrayRef], width: i32) -> Result<ArrayRef> {
+    let byte_width = validate_fixed_width(width)?;
+    let len = args.first().map_or(0, |a| a.len());
+    regexp_extract_into(
+        args,
+        &DefaultPatternCompiler,
+        DEFAULT_PATTERN_CACHE_SIZE,
+        FAIL_FAST,
+        None,
+        FixedWidthAppender::with_capacity(len, byte_width),
+    )
+    .map(ExtractAppender::finish)
+}
+
+/// Like [`regexp_extract`], but returns a `Utf8View` array whose views point
+/// into the data buffers of the values `args[0]` instead of copying the
+/// extracted strings.
+///
+/// Only extracted strings longer than the 12 bytes that fit inline in a view
+/// are referenced, shorter ones are inlined as usual. The output keeps the
+/// whole data buffers of the values alive, even if only small parts of them
+/// are extracted.
+///
+/// Dictionary encoded values are decoded to views into the buffer of the
+/// dictionary values, so that the extracted strings reference the dictionary
+/// values without copying them either.
+pub fn regexp_extract_view(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let Some(values) = args.first() else {
+        return exec_err!(
+            "regexp_extract was called with 0 arguments. It requires at least 2 and at most 5, the fifth being the flags after an occurrence."
+        );
+    };
+    let decoded;
+    let args = match values.data_type() {
+        DataType::Dictionary(_, _) => {
+            decoded = decode_dictionary_values(args)?;
+            &decoded
+        }
+        _ => args,
+    };
+    regexp_extract_into(
+        args,
+        &DefaultPatternCompiler,
+        DEFAULT_PATTERN_CACHE_SIZE,
+        FAIL_FAST,
+        None,
+        ViewSliceAppender::new(&args[0]),
+    )
+    .map(ExtractAppender::finish)
+}
+
+/// Casts the dictionary encoded values `args[0]` to `Utf8View`, whose views
+/// point into the buffer of the dictionary values, along with the pattern
+/// and the flags that must be of the same type.
+fn decode_dictionary_values(args: &[ArrayRef]) -> Result<Vec<ArrayRef>> {
+    let flags_index = flags_index(args.get(3).map(|a| a.data_type()));
+    args.iter()
+        .enumerate()
+        .map(|(i, arg)| match arg.data_type() {
+            // dictionary encoded patterns are compiled per dictionary value
+            DataType::Dictionary(_, _) if i == 1 => Ok(Arc::clone(arg)),
+            _ if i <= 1 || i == flags_index => Ok(cast(arg, &DataType::Utf8View)?),
+            _ => Ok(Arc::clone(arg)),
+        })
+        .collect()
+}
+
+fn validate_fixed_width(width: i32) -> Result<usize> {
+    match usize::try_from(width) {
+        Ok(width) => Ok(width),
+        Err(_) => {
+            exec_err!("regexp_extract fixed width must not be negative, got {width}")
+        }
+    }
+}
+
+/// Like [`regexp_extract_with_compiler`], but keeps at most `cache_size`
+/// compiled patterns when the patterns are an array.
+fn regexp_extract_with_cache_size(
+    args: &[ArrayRef],
+    compiler: &dyn PatternCompiler,
+    cache_size: usize,
+) -> Result<ArrayRef> {
+    if let Some(DataType::RunEndEncoded(run_ends, _)) =
+        args.first().map(|a| a.data_type())
+    {
+        return match run_ends.data_type() {
+            DataType::Int16 => {
+                run_end_regexp_extract::<Int16Type>(args, compiler, cache_size)
+            }
+            DataType::Int32 => {
+                run_end_regexp_extract::<Int32Type>(args, compiler, cache_size)
+            }
+            DataType::Int64 => {
+                run_end_regexp_extract::<Int64Type>(args, compiler, cache_size)
+            }
+            other => exec_err!(
+                "Unsupported run end type {other:?} for function regexp_extract"
+            ),
+        };
+    }
+
+    let len = args.first().map_or(0, |a| a.len());
+    regexp_extract_into(
+        args,
+        compiler,
+        cache_size,
+        FAIL_FAST,
+        None,
+        StringBuilder::with_capacity(len, 0),
+    )
+    .map(ExtractAppender::finish)
+}
+
+/// Checks that the strings of `values` are valid UTF-8, failing on the first
+/// invalid row with [`InvalidUtf8Handling::Error`] and replacing the invalid
+/// sequences with [`InvalidUtf8Handling::Lossy`].
+///
+/// Run-end encoded values are returned unchanged.
+fn check_utf8(values: &ArrayRef, handling: InvalidUtf8Handling) -> Result<ArrayRef> {
+    let checked = match values.data_type() {
+        DataType::Utf8 => check_utf8_rows::<StringArray>(
+            &BinaryArray::from(values.as_string::<i32>().clone()),
+            handling,
+        )?,
+        DataType::LargeUtf8 => check_utf8_rows::<LargeStringArray>(
+            &LargeBinaryArray::from(values.as_string::<i64>().clone()),
+            handling,
+        )?,
+        DataType::Utf8View => check_utf8_rows::<StringViewArray>(
+            &values.as_string_view().clone().to_binary_view(),
+            handling,
+        )?,
+        _ => None,
+    };
+    Ok(checked.unwrap_or_else(|| Arc::clone(values)))
+}
+
+/// Returns the rows as an array of type `T` with the invalid UTF-8 sequences
+/// replaced, or `None` if all of them are valid.
+fn check_utf8_rows<'a, T>(
+    rows: impl ArrayAccessor<Item = &'a [u8]> + Copy,
+    handling: InvalidUtf8Handling,
+) -> Result<Option<ArrayRef>>
+where
+    T: Array + FromIterator<Option<String>> + 'static,
+{
+    let first_invalid = ArrayIter::new(rows)
+        .enumerate()
+        .find_map(|(i, row)| std::str::from_utf8(row?).err().map(|error| (i, error)));
+    let Some((row, error)) = first_invalid else {
+        return Ok(None);
+    };
+    match handling {
+        InvalidUtf8Handling::Trust => Ok(None),
+        InvalidUtf8Handling::Error => {
+            exec_err!("regexp_extract input row {row} is not valid UTF-8: {error}")
+        }
+        InvalidUtf8Handling::Lossy => {
+            let replaced = ArrayIter::new(rows)
+                .map(|row| row.map(|row| String::from_utf8_lossy(row).into_owned()))
+                .collect::<T>();
+            Ok(Some(Arc::new(replaced)))
+        }
+    }
+}
+
+/// Extracts with run-end encoded values, into a run-end encoded array.
+///
+/// If the pattern and the flags are scalars and neither the capture group nor
+/// the occurrence change within a run, each run value is extracted from once
+/// and the output keeps the runs of the input. Otherwise the values are
+/// decoded, extracted from row by row and encoded again.
+fn run_end_regexp_extract<R: RunEndIndexType>(
+    args: &[ArrayRef],
+    compiler: &dyn PatternCompiler,
+    cache_size: usize,
+) -> Result<ArrayRef> {
+    let values = args[0].as_run::<R>();
+    let len = values.len();
+    let offset = values.offset();
+
+    // the logical rows spanned by each run within the slice of the array
+    let run_ends = values.run_ends().values();
+    let physical_range = if len == 0 {
+        0..0
+    } else {
+        values.get_start_physical_index()..values.get_end_physical_index() + 1
+    };
+    let runs = physical_range
+        .clone()
+        .map(|physical_index| {
+            let start = if physical_index == physical_range.start {
+                0
+            } else {
+                run_ends[physical_index - 1].as_usize() - offset
+            };
+            let end = run_ends[physical_index].as_usize().min(offset + len) - offset;
+            (start, end)
+        })
+        .collect::<Vec<_>>();
+
+    let flags_index = flags_index(args.get(3).map(|a| a.data_type()));
+    let is_run_constant = |arg: &ArrayRef| {
+        arg.len() == len
+            && arg.as_primitive_opt::<Int64Type>().is_some_and(|arg| {
+                let at = |i: usize| arg.is_valid(i).then(|| arg.value(i));
+                runs.iter()
+                    .all(|&(start, end)| (start + 1..end).all(|i| at(i) == at(start)))
+            })
+    };
+    let is_per_run = args.len() >= 2
+        && args[1].len() == 1
+        && args.iter().enumerate().skip(2).all(|(i, arg)| {
+            if i == flags_index {
+                arg.len() == 1
+            } else {
+                is_run_constant(arg)
+            }
+        });
+
+    if is_per_run {
+        let run_starts =
+            UInt32Array::from_iter_values(runs.iter().map(|&(start, _)| start as u32));
+        let mut run_args = vec![
+            values
+                .values()
+                .slice(physical_range.start, physical_range.len()),
+            Arc::clone(&args[1]),
+        ];
+        for (i, arg) in args.iter().enumerate().skip(2) {
+            run_args.push(if i == flags_index {
+                Arc::clone(arg)
+            } else {
+                take(arg.as_ref(), &run_starts, None)?
+            });
+        }
+        let run_values = regexp_extract_with_cache_size(&run_args, compiler, cache_size)?;
+
+        let run_ends = runs
+            .iter()
+            .map(|&(_, end)| {
+                R::Native::from_usize(end).ok_or_else(|| {
+                    DataFusionError::Execution(format!(
+                        "regexp_extract run end {end} overflows {}",
+                        R::DATA_TYPE
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let run_ends = PrimitiveArray::<R>::from_iter_values(run_ends);
+        return Ok(Arc::new(RunArray::<R>::try_new(
+            &run_ends,
+            run_values.as_ref(),
+        )?));
+    }
+
+    let physical_indices =
+        UInt32Array::from_iter_values(physical_range.zip(&runs).flat_map(
+            |(physical_index, &(start, end))| {
+                std::iter::repeat_n(physical_index as u32, end - start)
+            },
+        ));
+    let mut decoded_args = args.to_vec();
+    decoded_args[0] = take(values.values().as_ref(), &physical_indices, None)?;
+    let decoded = regexp_extract_with_cache_size(&decoded_args, compiler, cache_size)?;
+
+    let mut builder = StringRunBuilder::<R>::with_capacity(len, 0);
+    builder.extend(decoded.as_string::<i32>().iter());
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Extracts into `builder`, which is returned to be finished by the caller.
+fn regexp_extract_into<A: ExtractAppender>(
+    args: &[ArrayRef],
+    compiler: &dyn PatternCompiler,
+    cache_size: usize,
+    max_compile_errors: usize,
+    transform: Option<InputTransform>,
+    builder: A,
+) -> Result<A> {
+    let args_len = args.len();
+    let flags_index = flags_index(args.get(3).map(|a| a.data_type()));
+    if args_len < 2 || args_len > flags_index + 1 {
+        return exec_err!(
+            "regexp_extract was called with {args_len} arguments. It requires at least 2 and at most 5, the fifth being the flags after an occurrence."
+        );
+    }
+
+    let group_index_array = args.get(2).map(|a| a.as_primitive::<Int64Type>());
+    let occurrence_array =
+        (flags_index == 4).then(|| args[3].as_primitive::<Int64Type>());
+
+    match (args[0].data_type(), args[1].data_type()) {
+        (DataType::Utf8, DataType::Utf8) => concrete_regexp_extract(
+            args[0].as_string::<i32>(),
+            args[1].as_string::<i32>(),
+            group_index_array,
+            occurrence_array,
+            args.get(flags_index).map(|a| a.as_string::<i32>()),
+            compiler,
+            cache_size,
+            max_compile_errors,
+            transform,
+            builder,
+        ),
+        (DataType::LargeUtf8, DataType::LargeUtf8) => concrete_regexp_extract(
+            args[0].as_string::<i64>(),
+            args[1].as_string::<i64>(),
+            group_index_array,
+            occurrence_array,
+            args.get(flags_index).map(|a| a.as_string::<i64>()),
+            compiler,
+            cache_size,
+            max_compile_errors,
+            transform,
+            builder,
+        ),
+        (DataType::Utf8View, DataType::Utf8View) => concrete_regexp_extract(
+            args[0].as_string_view(),
+            args[1].as_string_view(),
+            group_index_array,
+            occurrence_array,
+            args.get(flags_index).map(|a| a.as_string_view()),
+            compiler,
+            cache_size,
+            max_compile_errors,
+            transform,
+            builder,
+        ),
+        (DataType::Utf8, DataType::Dictionary(_, _)) => dictionary_regexp_extract(
+            args[0].as_string::<i32>(),
+            args[1].as_any_dictionary(),
+            group_index_array,
+            occurrence_array,
+            args.get(flags_index).map(|a| a.as_string::<i32>()),
+            compiler,
+            cache_size,
+            max_compile_errors,
+            transform,
+            builder,
+        ),
+        (DataType::LargeUtf8, DataType::Dictionary(_, _)) => dictionary_regexp_extract(
+            args[0].as_string::<i64>(),
+            args[1].as_any_dictionary(),
+            group_index_array,
+            occurrence_array,
+            args.get(flags_index).map(|a| a.as_string::<i64>()),
+            compiler,
+            cache_size,
+            max_compile_errors,
+            transform,
+            builder,
+        ),
+        (DataType::Utf8View, DataType::Dictionary(_, _)) => dictionary_regexp_extract(
+            args[0].as_string_view(),
+            args[1].as_any_dictionary(),
+            group_index_array,
+            occurrence_array,
+            args.get(flags_index).map(|a| a.as_string_view()),
+            compiler,
+            cache_size,
+            max_compile_errors,
+            transform,
+            builder,
+        ),
+        other => {
+            exec_err!("Unsupported data type {other:?} for function regexp_extract")
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn concrete_regexp_extract<'a, S, A>(
+    values: S,
+    pattern_array: S,
+    group_index_array: Option<&Int64Array>,
+    occurrence_array: Option<&Int64Array>,
+    flags_array: Option<S>,
+    compiler: &dyn PatternCompiler,
+    cache_size: usize,
+    max_compile_errors: usize,
+    transform: Option<InputTransform>,
+    mut builder: A,
+) -> Result<A>
+where
+    S: StringArrayType<'a>,
+    A: ExtractAppender,
+{
+    let is_flags_scalar = flags_array.as_ref().is_none_or(|flags| flags.len() == 1);
+
+    if pattern_array.len() == 1 && is_flags_scalar {
+        // the pattern and the flags are scalars: compile once for all rows
+        if pattern_array.is_null(0) {
+            (0..values.len()).for_each(|_| builder.append_null());
+            return Ok(builder);
+        }
+        let (pattern, flags) =
+            (pattern_array.value(0), flags_at(flags_array.as_ref(), 0));
+        #[cfg_attr(
+            not(feature = "fancy_regex"),
+            allow(clippy::infallible_destructuring_match)
+        )]
+        let regex = match CompiledPattern::compile(compiler, pattern, flags)? {
+            CompiledPattern::Regex(regex) => regex,
+            #[cfg(feature = "fancy_regex")]
+            CompiledPattern::Fancy(regex) => {
+                return fancy_regexp_extract(
+                    values,
+                    &regex,
+                    group_index_array,
+                    occurrence_array,
+                    transform,
+                    builder,
+                );
+            }
+        };
+        let prefix = compiler.anchored_prefix(pattern, flags);
+        let tokenizer = compiler
+            .repeated_class(pattern, flags)
+            .map(ClassTokenizer::new);
+        let bytes_regex = match (&tokenizer, transform) {
+            (None, None) => compiler.ascii_bytes_regex(pattern, flags),
+            _ => None,
+        };
+        if let Some(bytes_regex) = &bytes_regex {
+            let any_values = values.as_any();
+            if let Some(values) = any_values.downcast_ref::<StringArray>() {
+                return bytes_regexp_extract(
+                    values,
+                    &regex,
+                    bytes_regex,
+                    group_index_array,
+                    occurrence_array,
+                    builder,
+                );
+            }
+            if let Some(values) = any_values.downcast_ref::<LargeStringArray>() {
+                return bytes_regexp_extract(
+                    values,
+                    &regex,
+                    bytes_regex,
+                    group_index_array,
+                    occurrence_array,
+                    builder,
+                );
+            }
+        }
+        let mut input = TransformedInput::new(transform);
+
+        for i in 0..values.len() {
+            let (group_index, occurrence) = match (
+                group_index_at(group_index_array, i),
+                occurrence_at(occurrence_array, i),
+            ) {
+                (Some(group_index), Some(occurrence)) if values.is_valid(i) => {
+                    (group_index, occurrence)
+                }
+                _ => {
+                    builder.append_null();
+                    continue;
+                }
+            };
+            let value = input.apply(values.value(i));
+            if prefix
+                .as_ref()
+                .is_some_and(|prefix| !value.starts_with(prefix.as_str()))
+            {
+                // the regex cannot match, only the arguments are validated
+                validate_occurrence(occurrence)?;
+                validate_group_index(&regex, group_index)?;
+                builder.append_match(None);
+                continue;
+            }
+            if let Some(tokenizer) = &tokenizer {
+                // the whole pattern is at most a single group around the
+                // repetition, so every valid group is the entire match
+                let occurrence = validate_occurrence(occurrence)?;
+                validate_group_index(&regex, group_index)?;
+                builder.append_match(tokenizer.find(value, occurrence));
+                continue;
+            }
+            builder.append_match(find_occurrence_group(
+                &regex,
+                value,
+                group_index,
+                occurrence,
+            )?);
+        }
+    } else {
+        if pattern_array.len() != 1 && values.len() != pattern_array.len() {
+            return exec_err!(
+                "regexp_extract pattern array must be the same length as the values array; got {} and {}",
+                pattern_array.len(),
+                values.len()
+            );
+        }
+        if let Some(flags_array) = flags_array.as_ref().filter(|_| !is_flags_scalar) {
+            if values.len() != flags_array.len() {
+                return exec_err!(
+                    "regexp_extract flags array must be the same length as the values array; got {} and {}",
+                    flags_array.len(),
+                    values.len()
+                );
+            }
+        }
+
+        // scalar flags are looked up once, so that only the pattern varies
+        // between the cache keys of the rows
+        let scalar_flags = is_flags_scalar.then(|| flags_at(flags_array.as_ref(), 0));
+        let mut regex_cache = PatternCache::new(cache_size);
+        let mut compile_errors = CompileErrors::new(max_compile_errors);
+        let mut input = TransformedInput::new(transform);
+        for i in 0..values.len() {
+            let pattern_index = if pattern_array.len() == 1 { 0 } else { i };
+            let (group_index, occurrence) = match (
+                group_index_at(group_index_array, i),
+                occurrence_at(occurrence_array, i),
+            ) {
+                (Some(group_index), Some(occurrence))
+                    if values.is_valid(i) && pattern_array.is_valid(pattern_index) =>
+                {
+                    (group_index, occurrence)
+                }
+                _ => {
+                    builder.append_null();
+                    continue;
+                }
+            };
+            let pattern = pattern_array.value(pattern_index);
+            let flags = scalar_flags.unwrap_or_else(|| flags_at(flags_array.as_ref(), i));
+            let Some(regex) = compile_errors.collect(
+                regex_cache.get_or_compile((pattern, flags), || {
+                    CompiledPattern::compile(compiler, pattern, flags)
+                }),
+            )?
+            else {
+                builder.append_null();
+                continue;
+            };
+            builder.append_match(regex.find_occurrence_group(
+                input.apply(values.value(i)),
+                group_index,
+                occurrence,
+            )?);
+        }
+        compile_errors.finish()?;
+    }
+
+    Ok(builder)
+}
+
+/// Extracts with a constant pattern that only matches runs of ASCII
+/// characters, see [`PatternCompiler::ascii_bytes_regex`], by matching
+/// `bytes_regex` over the value buffer of `values` instead of its strings.
+///
+/// `regex` is the same pattern compiled for strings, which the group indices
+/// are validated against.
+fn bytes_regexp_extract<O: OffsetSizeTrait, A: ExtractAppender>(
+    values: &GenericStringArray<O>,
+    regex: &Regex,
+    bytes_regex: &BytesRegex,
+    group_index_array: Option<&Int64Array>,
+    occurrence_array: Option<&Int64Array>,
+    mut builder: A,
+) -> Result<A> {
+    let data = values.value_data();
+    let offsets = values.value_offsets();
+    for i in 0..values.len() {
+        let (group_index, occurrence) = match (
+            group_index_at(group_index_array, i),
+            occurrence_at(occurrence_array, i),
+        ) {
+            (Some(group_index), Some(occurrence)) if values.is_valid(i) => {
+                (group_index, occurrence)
+            }
+            _ => {
+                builder.append_null();
+                continue;
+            }
+        };
+        let occurrence = validate_occurrence(occurrence)?;
+        let group_index = validate_group_index(regex, group_index)?;
+        let value = &data[offsets[i].as_usize()..offsets[i + 1].as_usize()];
+        let group = find_bytes_group(bytes_regex, value, group_index, occurrence);
+        // SAFETY: the pattern only matches ASCII characters, so the group is
+        // a run of ASCII bytes, which is valid UTF-8
+        builder.append_match(
+            group.map(|group| unsafe { std::str::from_utf8_unchecked(group) }),
+        );
+    }
+    Ok(builder)
+}
+
+/// Like [`find_occurrence_group`] with a validated group index and
+/// occurrence, but over the bytes of a value.
+fn find_bytes_group<'h>(
+    regex: &BytesRegex,
+    value: &'h [u8],
+    group_index: usize,
+    occurrence: usize,
+) -> Option<&'h [u8]> {
+    if group_index == 0 {
+        return regex
+            .find_iter(value)
+            .nth(occurrence - 1)
+            .map(|m| m.as_bytes());
+    }
+    if occurrence == 1 && !regex.is_match(value) {
+        return None;
+    }
+    regex
+        .captures_iter(value)
+        .nth(occurrence - 1)
+        .map(|captures| captures.get(group_index).map_or(&[][..], |m| m.as_bytes()))
+}
+
+/// Extracts with a dictionary encoded pattern array.
+///
+/// Each distinct dictionary value is compiled at most once per distinct flags,
+/// on first use, and rows look up their compiled pattern through the
+/// dictionary keys.
+#[allow(clippy::too_many_arguments)]
+fn dictionary_regexp_extract<'a, S, A>(
+    values: S,
+    pattern_array: &dyn AnyDictionaryArray,
+    group_index_array: Option<&Int64Array>,
+    occurrence_array: Option<&Int64Array>,
+    flags_array: Option<S>,
+    compiler: &dyn PatternCompiler,
+    cache_size: usize,
+    max_compile_errors: usize,
+    transform: Option<InputTransform>,
+    mut builder: A,
+) -> Result<A>
+where
+    S: StringArrayType<'a>,
+    A: ExtractAppender,
+{
+    let dictionary = pattern_array.values();
+    let patterns: Vec<Option<&str>> = match dictionary.data_type() {
+        DataType::Utf8 => dictionary.as_string::<i32>().iter().collect(),
+        DataType::LargeUtf8 => dictionary.as_string::<i64>().iter().collect(),
+        DataType::Utf8View => dictionary.as_string_view().iter().collect(),
+        other => {
+            return exec_err!(
+                "Unsupported dictionary value type {other:?} for function regexp_extract"
+            )
+        }
+    };
+
+    let is_pattern_scalar = pattern_array.len() == 1;
+    if !is_pattern_scalar && values.len() != pattern_array.len() {
+        return exec_err!(
+            "regexp_extract pattern array must be the same length as the values array; got {} and {}",
+            pattern_array.len(),
+            values.len()
+        );
+    }
+
+    let keys = pattern_array.normalized_keys();
+    let mut compiled = PatternCache::new(cache_size);
+    let mut compile_errors = CompileErrors::new(max_compile_errors);
+    let mut input = TransformedInput::new(transform);
+    for i in 0..values.len() {
+        let pattern_index = if is_pattern_scalar { 0 } else { i };
+        let key = keys[pattern_index];
+        let (pattern, group_index, occurrence) = match (
+            patterns[key],
+            group_index_at(group_index_array, i),
+            occurrence_at(occurrence_array, i),
+        ) {
+            (Some(pattern), Some(group_index), Some(occurrence))
+                if values.is_valid(i) && pattern_array.is_valid(pattern_index) =>
+            {
+                (pattern, group_index, occurrence)
+            }
+            _ => {
+                builder.append_null();
+                continue;
+            }
+        };
+
+        let flags = flags_at(flags_array.as_ref(), i);
+        let Some(regex) =
+            compile_errors.collect(compiled.get_or_compile((key, flags), || {
+                CompiledPattern::compile(compiler, pattern, flags)
+            }))?
+        else {
+            builder.append_null();
+            continue;
+        };
+        builder.append_match(regex.find_occurrence_group(
+            input.apply(values.value(i)),
+            group_index,
+            occurrence,
+        )?);
+    }
+    compile_errors.finish()?;
+
+    Ok(builder)
+}
+
+/// The distinct errors of the patterns of a batch that did not compile, see
+/// `regexp_extract.max_compile_errors`.
+///
+/// The rows whose pattern does not compile yield NULL until `max_errors`
+/// errors are collected, and the batch fails with all of them once it is
+/// complete or the limit is reached.
+#[derive(Debug)]
+struct CompileErrors {
+    max_errors: usize,
+    errors: Vec<String>,
+}
+
+impl CompileErrors {
+    fn new(max_errors: usize) -> Self {
+        Self {
+            max_errors,
+            errors: vec![],
+        }
+    }
+
+    /// Returns the compiled pattern, or `None` after collecting the error if
+    /// it is not the last one allowed.
+    fn collect<T>(&mut self, compiled: Result<T>) -> Result<Option<T>> {
+        let error = match compiled {
+            Ok(compiled) => return Ok(Some(compiled)),
+            // failing fast keeps the error of the pattern as it is
+            Err(error) if self.max_errors <= 1 => return Err(error),
+            Err(DataFusionError::Execution(message)) => message,
+            Err(error) => error.strip_backtrace(),
+        };
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+        if self.errors.len() >= self.max_errors {
+            return Err(self.combined());
+        }
+        Ok(None)
+    }
+
+    /// Fails with the collected errors, if any.
+    fn finish(self) -> Result<()> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        Err(self.combined())
+    }
+
+    fn combined(&self) -> DataFusionError {
+        let limit = if self.errors.len() >= self.max_errors {
+            " (stopped at the limit set by regexp_extract.max_compile_errors)"
+        } else {
+            ""
+        };
+        exec_datafusion_err!(
+            "regexp_extract failed to compile {} patterns{limit}:\n{}",
+            self.errors.len(),
+            self.errors.join("\n")
+        )
+    }
+}
+
+/// Finds the matches of a pattern that repeats a single class, see
+/// [`PatternCompiler::repeated_class`], by scanning for the maximal runs of
+/// the characters of the class.
+#[derive(Debug)]
+struct ClassTokenizer {
+    /// Whether each ASCII character is in the class.
+    ascii: [bool; 128],
+    /// The sorted, disjoint ranges of the class.
+    ranges: Vec<RangeInclusive<char>>,
+}
+
+impl ClassTokenizer {
+    fn new(ranges: Vec<RangeInclusive<char>>) -> Self {
+        let mut ascii = [false; 128];
+        for (c, in_class) in ascii.iter_mut().enumerate() {
+            let c = char::from(c as u8);
+            *in_class = ranges.iter().any(|range| range.contains(&c));
+        }
+        Self { ascii, ranges }
+    }
+
+    fn contains(&self, c: char) -> bool {
+        if c.is_ascii() {
+            return self.ascii[c as usize];
+        }
+        self.ranges
+            .binary_search_by(|range| {
+                if *range.end() < c {
+                    Ordering::Less
+                } else if *range.start() > c {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// Returns the `occurrence`-th maximal run of the class in `value`,
+    /// counting from 1, or `None` if there are fewer runs.
+    fn find<'h>(&self, value: &'h str, occurrence: usize) -> Option<&'h str> {
+        let mut runs = 0;
+        let mut start = None;
+        for (i, c) in value.char_indices() {
+            match (self.contains(c), start) {
+                (true, None) => start = Some(i),
+                (false, Some(run_start)) => {
+                    runs += 1;
+                    if runs == occurrence {
+                        return Some(&value[run_start..i]);
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        start
+            .filter(|_| runs + 1 == occurrence)
+            .map(|run_start| &value[run_start..])
+    }
+}
+
+/// Like [`compile_pattern`], but looks the pattern up in `regex_cache` first
+/// and caches it after compiling it.
+pub(crate) fn compile_and_cache_pattern<'strings, 'cache>(
+    pattern: &'strings str,
+    flags: Option<&'strings str>,
+    regex_cache: &'cache mut HashMap<(&'strings str, Option<&'strings str>), Regex>,
+) -> Result<&'cache Regex>
+where
+    'strings: 'cache,
+{
+    let regex = match regex_cache.entry((pattern, flags)) {
+        Entry::Occupied(occupied_entry) => occupied_entry.into_mut(),
+        Entry::Vacant(vacant_entry) => {
+            vacant_entry.insert(compile_pattern(pattern, flags)?)
+        }
+    };
+    Ok(regex)
+}
+
+/// Returns the flags of row `i`, a NULL flags value means no flags.
+fn flags_at<'a, S>(flags_array: Option<&S>, i: usize) -> Option<&'a str>
+where
+    S: StringArrayType<'a>,
+{
+    let flags_array = flags_array?;
+    let flags_index = if flags_array.len() == 1 { 0 } else { i };
+    flags_array
+        .is_valid(flags_index)
+        .then(|| flags_array.value(flags_index))
+}
+
+/// Returns the capture group index of row `i`.
+///
+/// Defaults to group 1 when no group index argument was given and returns
+/// `None` for a NULL group index, which yields a NULL result.
+pub(crate) fn group_index_at(
+    group_index_array: Option<&Int64Array>,
+    i: usize,
+) -> Option<i64> {
+    match group_index_array {
+        None => Some(1),
+        Some(group_index_array) if group_index_array.is_null(i) => None,
+        Some(group_index_array) => Some(group_index_array.value(i)),
+    }
+}
+
+/// Returns the occurrence of row `i`.
+///
+/// Defaults to the first match when no occurrence argument was given and
+/// returns `None` for a NULL occurrence, which yields a NULL result.
+fn occurrence_at(occurrence_array: Option<&Int64Array>, i: usize) -> Option<i64> {
+    match occurrence_array {
+        None => Some(1),
+        Some(occurrence_array) if occurrence_array.is_null(i) => None,
+        Some(occurrence_array) => Some(occurrence_array.value(i)),
+    }
+}
+
+/// Checks that `group_index` refers to a capture group of `regex`.
+pub(crate) fn validate_group_index(regex: &Regex, group_index: i64) -> Result<usize> {
+    let group_count = regex.captures_len() - 1;
+    match usize::try_from(group_index) {
+        Ok(group_index) if group_index <= group_count => Ok(group_index),
+        _ => exec_err!(
+            "Regex group index {group_index} is out of range, the pattern '{}' has {group_count} capture groups",
+            regex.as_str()
+        ),
+    }
+}
+
+/// Returns the capture group `group_index` of the first match of `regex` in
+/// `value`, or the empty string if there is no match or the group did not
+/// participate in the match.
+pub(crate) fn extract_group<'h>(
+    regex: &Regex,
+    value: &'h str,
+    group_index: i64,
+) -> Result<&'h str> {
+    Ok(find_group(regex, value, group_index)?.unwrap_or(""))
+}
+
+/// Like [`extract_group`], but returns `None` if there is no match.
+pub(crate) fn find_group<'h>(
+    regex: &Regex,
+    value: &'h str,
+    group_index: i64,
+) -> Result<Option<&'h str>> {
+    let group_index = validate_group_index(regex, group_index)?;
+    if group_index == 0 {
+        // the entire match does not need the capture groups to be resolved,
+        // chosen per row so that a column of group indices that are mostly 0
+        // only resolves the groups of the other rows
+        return Ok(regex.find(value).map(|m| m.as_str()));
+    }
+    // checking for a match does not track the capture groups, which makes the
+    // common case of a value that does not match cheaper
+    if !regex.is_match(value) {
+        return Ok(None);
+    }
+    Ok(regex
+        .captures(value)
+        .map(|captures| captures.get(group_index).map_or("", |m| m.as_str())))
+}
+
+/// Returns `occurrence` as a `usize` if it is a valid occurrence of a match,
+/// counting from 1.
+fn validate_occurrence(occurrence: i64) -> Result<usize> {
+    match usize::try_from(occurrence) {
+        Ok(occurrence) if occurrence > 0 => Ok(occurrence),
+        _ => exec_err!(
+            "regexp_extract occurrence must be greater than 0, got {occurrence}"
+        ),
+    }
+}
+
+/// A pattern compiled by a [`PatternCompiler`], with fancy-regex if the regex
+/// crate rejected it because it needs backtracking.
+enum CompiledPattern {
+    Regex(Regex),
+    #[cfg(feature = "fancy_regex")]
+    Fancy(FancyRegex),
+}
+
+impl CompiledPattern {
+    /// Compiles `pattern` with `compiler`, falling back to
+    /// [`PatternCompiler::compile_fancy`] with the `fancy_regex` feature.
+    fn compile(
+        compiler: &dyn PatternCompiler,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Result<Self> {
+        let error = match compiler.compile(pattern, flags) {
+            Ok(regex) => return Ok(Self::Regex(regex)),
+            Err(error) => error,
+        };
+        #[cfg(feature = "fancy_regex")]
+        if let Some(regex) = compiler.compile_fancy(pattern, flags)? {
+            return Ok(Self::Fancy(regex));
+        }
+        Err(error)
+    }
+
+    /// See [`find_occurrence_group`].
+    fn find_occurrence_group<'h>(
+        &self,
+        value: &'h str,
+        group_index: i64,
+        occurrence: i64,
+    ) -> Result<Option<&'h str>> {
+        match self {
+            Self::Regex(regex) => {
+                find_occurrence_group(regex, value, group_index, occurrence)
+            }
+            #[cfg(feature = "fancy_regex")]
+            Self::Fancy(regex) => {
+                find_fancy_occurrence_group(regex, value, group_index, occurrence)
+            }
+        }
+    }
+}
+
+/// Like [`find_group`], but returns the capture group of the `occurrence`-th
+/// match, counting from 1, or `None` if there are fewer matches.
+fn find_occurrence_group<'h>(
+    regex: &Regex,
+    value: &'h str,
+    group_index: i64,
+    occurrence: i64,
+) -> Result<Option<&'h str>> {
+    let occurrence = match validate_occurrence(occurrence)? {
+        1 => return find_group(regex, value, group_index),
+        occurrence => occurrence,
+    };
+    let group_index = validate_group_index(regex, group_index)?;
+    if group_index == 0 {
+        // like in `find_group`, the entire matches do not need the capture
+        // groups to be resolved
+        return Ok(regex
+            .find_iter(value)
+            .nth(occurrence - 1)
+            .map(|m| m.as_str()));
+    }
+    Ok(regex
+        .captures_iter(value)
+        .nth(occurrence - 1)
+        .map(|captures| captures.get(group_index).map_or("", |m| m.as_str())))
+}
@@ -0,0 +1,334 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The builders of the output arrays of `regexp_extract`
+
+use arrow::array::AsArray;
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, BooleanBuilder, FixedSizeBinaryBuilder,
+    GenericStringBuilder, OffsetSizeTrait, StringBuilder, StringViewBuilder,
+};
+use arrow::datatypes::DataType;
+use std::ops::Range;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Appends the extracted strings to the output array of `regexp_extract`.
+pub(crate) trait ExtractAppender {
+    fn append_value(&mut self, value: &str);
+
+    fn append_null(&mut self);
+
+    /// Appends the extracted group of a row, `None` if the pattern did not
+    /// match the row, which yields the empty string.
+    fn append_match(&mut self, group: Option<&str>) {
+        self.append_value(group.unwrap_or(""))
+    }
+
+    fn finish(self) -> ArrayRef;
+}
+
+impl<O: OffsetSizeTrait> ExtractAppender for GenericStringBuilder<O> {
+    fn append_value(&mut self, value: &str) {
+        GenericStringBuilder::append_value(self, value)
+    }
+
+    fn append_null(&mut self) {
+        GenericStringBuilder::append_null(self)
+    }
+
+    fn finish(mut self) -> ArrayRef {
+        Arc::new(GenericStringBuilder::finish(&mut self))
+    }
+}
+
+impl ExtractAppender for StringViewBuilder {
+    fn append_value(&mut self, value: &str) {
+        StringViewBuilder::append_value(self, value)
+    }
+
+    fn append_null(&mut self) {
+        StringViewBuilder::append_null(self)
+    }
+
+    fn finish(mut self) -> ArrayRef {
+        Arc::new(StringViewBuilder::finish(&mut self))
+    }
+}
+
+/// Appends the extracted strings padded with NUL bytes or truncated to a
+/// fixed width, for [`regexp_extract_fixed_width`].
+pub(super) struct FixedWidthAppender {
+    builder: FixedSizeBinaryBuilder,
+    width: usize,
+    padded: Vec<u8>,
+}
+
+impl FixedWidthAppender {
+    pub(super) fn with_capacity(capacity: usize, width: usize) -> Self {
+        Self {
+            // the width was validated to fit in an i32
+            builder: FixedSizeBinaryBuilder::with_capacity(capacity, width as i32),
+            width,
+            padded: Vec::with_capacity(width),
+        }
+    }
+}
+
+impl ExtractAppender for FixedWidthAppender {
+    fn append_value(&mut self, value: &str) {
+        let value = value.as_bytes();
+        self.padded.clear();
+        self.padded
+            .extend_from_slice(&value[..value.len().min(self.width)]);
+        self.padded.resize(self.width, 0);
+        self.builder
+            .append_value(&self.padded)
+            .expect("the value is padded to the width of the builder");
+    }
+
+    fn append_null(&mut self) {
+        self.builder.append_null();
+    }
+
+    fn finish(mut self) -> ArrayRef {
+        Arc::new(self.builder.finish())
+    }
+}
+
+/// Appends the extracted strings as views into the data buffers of the
+/// values they were extracted from, for [`regexp_extract_view`].
+pub(super) struct ViewSliceAppender {
+    builder: StringViewBuilder,
+    /// The address range and the block of the builder of each data buffer
+    blocks: Vec<(Range<usize>, u32)>,
+    /// The index in `blocks` of the last referenced block
+    last_block: usize,
+}
+
+impl ViewSliceAppender {
+    pub(super) fn new(values: &dyn Array) -> Self {
+        let buffers = match values.data_type() {
+            DataType::Utf8 => vec![values.as_string::<i32>().values().clone()],
+            DataType::LargeUtf8 => vec![values.as_string::<i64>().values().clone()],
+            DataType::Utf8View => values.as_string_view().data_buffers().to_vec(),
+            _ => vec![],
+        };
+
+        let mut builder = StringViewBuilder::with_capacity(values.len());
+        let blocks = buffers
+            .into_iter()
+            // a view cannot address a buffer beyond u32 offsets, the strings
+            // extracted from it are copied instead
+            .filter(|buffer| buffer.len() < u32::MAX as usize)
+            .map(|buffer| {
+                let start = buffer.as_ptr() as usize;
+                let range = start..start + buffer.len();
+                (range, builder.append_block(buffer))
+            })
+            .collect();
+        Self {
+            builder,
+            blocks,
+            last_block: 0,
+        }
+    }
+
+    /// Returns the block of the builder that holds `value` and the offset of
+    /// `value` in it.
+    fn find_block(&mut self, value: &str) -> Option<(u32, u32)> {
+        let range = value.as_ptr() as usize..value.as_ptr() as usize + value.len();
+        let contains = |(block, _): &(Range<usize>, u32)| {
+            block.start <= range.start && range.end <= block.end
+        };
+        // consecutive values are usually stored in the same buffer
+        let index = if self.blocks.get(self.last_block).is_some_and(contains) {
+            self.last_block
+        } else {
+            self.blocks.iter().position(contains)?
+        };
+        self.last_block = index;
+        let (block_range, block) = &self.blocks[index];
+        Some((*block, (range.start - block_range.start) as u32))
+    }
+}
+
+impl ExtractAppender for ViewSliceAppender {
+    fn append_value(&mut self, value: &str) {
+        // strings of up to 12 bytes are inlined in the view
+        if value.len() <= 12 {
+            self.builder.append_value(value);
+            return;
+        }
+        match self.find_block(value) {
+            // SAFETY: the block was added with `append_block`, `value` lies
+            // within it and is valid UTF-8
+            Some((block, offset)) => unsafe {
+                self.builder
+                    .append_view_unchecked(block, offset, value.len() as u32)
+            },
+            None => self.builder.append_value(value),
+        }
+    }
+
+    fn append_null(&mut self) {
+        self.builder.append_null();
+    }
+
+    fn finish(mut self) -> ArrayRef {
+        Arc::new(StringViewBuilder::finish(&mut self.builder))
+    }
+}
+
+/// Appends the extracted strings along with a mask of the rows the pattern
+/// matched, for [`regexp_extract_with_match_mask`].
+pub(super) struct MatchMaskAppender {
+    values: StringBuilder,
+    mask: BooleanBuilder,
+}
+
+impl MatchMaskAppender {
+    pub(super) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: StringBuilder::with_capacity(capacity, 0),
+            mask: BooleanBuilder::with_capacity(capacity),
+        }
+    }
+
+    pub(super) fn finish_with_mask(mut self) -> (ArrayRef, BooleanArray) {
+        (Arc::new(self.values.finish()), self.mask.finish())
+    }
+}
+
+impl ExtractAppender for MatchMaskAppender {
+    fn append_value(&mut self, value: &str) {
+        self.append_match(Some(value))
+    }
+
+    fn append_null(&mut self) {
+        self.values.append_null();
+        self.mask.append_null();
+    }
+
+    fn append_match(&mut self, group: Option<&str>) {
+        self.values.append_value(group.unwrap_or(""));
+        self.mask.append_value(group.is_some());
+    }
+
+    fn finish(self) -> ArrayRef {
+        self.finish_with_mask().0
+    }
+}
+
+/// Truncates the extracted strings to at most `max_len` characters, or
+/// grapheme clusters if `graphemes` is set, before appending them to `inner`,
+/// see `regexp_extract.max_capture_length`.
+pub(super) struct TruncatingAppender<A> {
+    pub(super) inner: A,
+    pub(super) max_len: usize,
+    pub(super) graphemes: bool,
+}
+
+impl<A> TruncatingAppender<A> {
+    fn truncate<'h>(&self, value: &'h str) -> &'h str {
+        // cut at the start of the first character past the limit, so that
+        // multibyte characters are never split
+        let end = if self.graphemes {
+            value
+                .grapheme_indices(true)
+                .nth(self.max_len)
+                .map(|(end, _)| end)
+        } else {
+            value.char_indices().nth(self.max_len).map(|(end, _)| end)
+        };
+        end.map_or(value, |end| &value[..end])
+    }
+}
+
+impl<A: ExtractAppender> ExtractAppender for TruncatingAppender<A> {
+    fn append_value(&mut self, value: &str) {
+        let value = self.truncate(value);
+        self.inner.append_value(value)
+    }
+
+    fn append_null(&mut self) {
+        self.inner.append_null()
+    }
+
+    fn append_match(&mut self, group: Option<&str>) {
+        let group = group.map(|group| self.truncate(group));
+        self.inner.append_match(group)
+    }
+
+    fn finish(self) -> ArrayRef {
+        self.inner.finish()
+    }
+}
+
+/// Collapses every run of whitespace in the extracted strings to a single
+/// space and trims them before appending them to `inner`, see
+/// `regexp_extract.collapse_whitespace`.
+pub(super) struct CollapsingAppender<A> {
+    pub(super) inner: A,
+    /// The collapsed string of the last row, reused across the rows
+    pub(super) collapsed: String,
+}
+
+impl<A: ExtractAppender> ExtractAppender for CollapsingAppender<A> {
+    fn append_value(&mut self, value: &str) {
+        collapse_whitespace(value, &mut self.collapsed);
+        self.inner.append_value(&self.collapsed)
+    }
+
+    fn append_null(&mut self) {
+        self.inner.append_null()
+    }
+
+    fn append_match(&mut self, group: Option<&str>) {
+        match group {
+            Some(group) => {
+                collapse_whitespace(group, &mut self.collapsed);
+                self.inner.append_match(Some(&self.collapsed))
+            }
+            None => self.inner.append_match(None),
+        }
+    }
+
+    fn finish(self) -> ArrayRef {
+        self.inner.finish()
+    }
+}
+
+/// Writes `value` to `collapsed` with every run of whitespace replaced by a
+/// single space and without leading or trailing whitespace, in a single scan.
+pub(super) fn collapse_whitespace(value: &str, collapsed: &mut String) {
+    collapsed.clear();
+    let mut in_whitespace = false;
+    for c in value.chars() {
+        if c.is_whitespace() {
+            in_whitespace = true;
+            continue;
+        }
+        // a run is only written once a character follows it, which drops
+        // the leading and trailing whitespace
+        if in_whitespace && !collapsed.is_empty() {
+            collapsed.push(' ');
+        }
+        in_whitespace = false;
+        collapsed.push(c);
+    }
+}
@@ -0,0 +1,753 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The pattern compilers of `regexp_extract`
+
+use crate::regex::SharedRegexes;
+use datafusion_common::{exec_err, DataFusionError, Result};
+#[cfg(feature = "fancy_regex")]
+use fancy_regex::Regex as FancyRegex;
+use regex::bytes::Regex as BytesRegex;
+use regex::Regex;
+use regex_syntax::ast::parse::ParserBuilder;
+use regex_syntax::ast::print::Printer;
+use regex_syntax::ast::{
+    AssertionKind, Ast, ClassAscii, ClassAsciiKind, ClassBracketed, ClassPerl,
+    ClassPerlKind, ClassSet, ClassSetItem, Flag, Flags, FlagsItem, FlagsItemKind, Group,
+    GroupKind,
+};
+use regex_syntax::hir::{Class, Hir, HirKind, Look};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::sync::{Arc, OnceLock};
+
+/// Compiles the patterns passed to [`RegexpExtractFunc`](super::RegexpExtractFunc).
+///
+/// The default implementation, [`DefaultPatternCompiler`], compiles patterns
+/// with the default [`regex::RegexBuilder`] options. Implement this trait to
+/// control every compilation option, such as a line terminator other than
+/// `\n` for multi-line mode, for example:
+///
+/// ```
+/// # use datafusion_common::{DataFusionError, Result};
+/// # use datafusion_functions::regex::regexpextract::{PatternCompiler, RegexpExtractFunc};
+/// # use regex::{Regex, RegexBuilder};
+/// # use std::sync::Arc;
+/// #[derive(Debug)]
+/// struct SwapGreedCompiler;
+///
+/// impl PatternCompiler for SwapGreedCompiler {
+///     fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+///         let pattern = match flags {
+///             Some(flags) if !flags.is_empty() => format!("(?{flags}){pattern}"),
+///             _ => pattern.to_string(),
+///         };
+///         RegexBuilder::new(&pattern)
+///             .swap_greed(true)
+///             .build()
+///             .map_err(|e| DataFusionError::External(Box::new(e)))
+///     }
+/// }
+///
+/// let udf = RegexpExtractFunc::new().with_pattern_compiler(Arc::new(SwapGreedCompiler));
+/// ```
+pub trait PatternCompiler: Debug + Send + Sync {
+    /// Compiles `pattern` into a [`Regex`], applying the optional `flags`
+    /// argument of the call.
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex>;
+
+    /// Returns the compiler this one wraps, if it compiles every pattern to
+    /// a regex matching like the one of the wrapped compiler, such as a
+    /// compiler that only caches the regexes of another one.
+    ///
+    /// The other methods default to asking the wrapped compiler, so such a
+    /// wrapper only implements [`PatternCompiler::compile`] and this method.
+    fn wrapped(&self) -> Option<&dyn PatternCompiler> {
+        None
+    }
+
+    /// Returns the literal prefix that a value must start with for `pattern`
+    /// to match it, if there is one.
+    ///
+    /// The values of a constant pattern that do not start with the prefix are
+    /// skipped without running the regex. Returning `None`, as the default
+    /// implementation does without a [wrapped](PatternCompiler::wrapped)
+    /// compiler, is always correct.
+    fn anchored_prefix(&self, pattern: &str, flags: Option<&str>) -> Option<String> {
+        self.wrapped()?.anchored_prefix(pattern, flags)
+    }
+
+    /// Returns the character ranges of the class that `pattern` repeats, if
+    /// its matches are exactly the maximal runs of the characters of one
+    /// class, like those of `[0-9]+` or `\w+`.
+    ///
+    /// The values of such a constant pattern are scanned for the runs
+    /// without running the regex. Returning `None`, as the default
+    /// implementation does without a [wrapped](PatternCompiler::wrapped)
+    /// compiler, is always correct.
+    fn repeated_class(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Option<Vec<RangeInclusive<char>>> {
+        self.wrapped()?.repeated_class(pattern, flags)
+    }
+
+    /// Returns `pattern` compiled for matching bytes, if it only matches
+    /// non-empty runs of ASCII characters, like `[a-z]+=[0-9]+`.
+    ///
+    /// The values of a constant pattern are then matched over the bytes of a
+    /// `Utf8` or `LargeUtf8` array, since every match ends on a character
+    /// boundary. Returning `None`, as the default implementation does without
+    /// a [wrapped](PatternCompiler::wrapped) compiler, is always correct.
+    fn ascii_bytes_regex(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Option<BytesRegex> {
+        self.wrapped()?.ascii_bytes_regex(pattern, flags)
+    }
+
+    /// Compiles `pattern` with fancy-regex after [`PatternCompiler::compile`]
+    /// rejected it, returning `None` if it does not need backtracking, so that
+    /// the error of `compile` is reported.
+    ///
+    /// The [`DefaultPatternCompiler`] only compiles the patterns with a
+    /// backreference, such as `\1`, or a look-around. Returning `None`, as the
+    /// default implementation does without a
+    /// [wrapped](PatternCompiler::wrapped) compiler, keeps every pattern on
+    /// the regex crate.
+    #[cfg(feature = "fancy_regex")]
+    fn compile_fancy(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Result<Option<FancyRegex>> {
+        match self.wrapped() {
+            Some(inner) => inner.compile_fancy(pattern, flags),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The [`PatternCompiler`] used by default, which compiles patterns with
+/// [`Regex::new`] and prepends the flags as an inline `(?flags)` group.
+///
+/// `regexp_extract` shares the patterns this compiler compiles with the other
+/// regex functions of the session, see [`SharedRegexes`].
+#[derive(Debug, Default)]
+pub struct DefaultPatternCompiler;
+
+impl PatternCompiler for DefaultPatternCompiler {
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+        compile_pattern(pattern, flags)
+    }
+
+    fn anchored_prefix(&self, pattern: &str, flags: Option<&str>) -> Option<String> {
+        anchored_literal_prefix(pattern, flags)
+    }
+
+    fn repeated_class(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Option<Vec<RangeInclusive<char>>> {
+        repeated_class_ranges(pattern, flags)
+    }
+
+    fn ascii_bytes_regex(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Option<BytesRegex> {
+        compile_ascii_bytes_pattern(pattern, flags)
+    }
+
+    #[cfg(feature = "fancy_regex")]
+    fn compile_fancy(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Result<Option<FancyRegex>> {
+        Ok(super::fancy::compile_fancy_pattern(pattern, flags))
+    }
+}
+
+/// A literal pattern and its flags together with the compiled [`Regex`].
+#[derive(Clone)]
+pub(super) struct PrecompiledPattern {
+    pub(super) pattern: String,
+    pub(super) flags: Option<String>,
+    /// Only compiled on first use for a pattern restored by
+    /// `RegexpExtractFunc::with_serialized_pattern`
+    pub(super) regex: Arc<OnceLock<Regex>>,
+}
+
+impl PrecompiledPattern {
+    /// Returns the compiled regex, compiling a restored pattern with
+    /// `compiler` on first use.
+    fn regex(&self, compiler: &dyn PatternCompiler) -> Result<Regex> {
+        if let Some(regex) = self.regex.get() {
+            return Ok(regex.clone());
+        }
+        let regex = compiler.compile(&self.pattern, self.flags.as_deref())?;
+        Ok(self.regex.get_or_init(|| regex).clone())
+    }
+}
+
+impl Debug for PrecompiledPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // whether a restored pattern was compiled yet does not matter
+        f.debug_struct("PrecompiledPattern")
+            .field("pattern", &self.pattern)
+            .field("flags", &self.flags)
+            .finish()
+    }
+}
+
+impl PartialEq for PrecompiledPattern {
+    fn eq(&self, other: &Self) -> bool {
+        // the regex is fully determined by the pattern and the flags
+        self.pattern == other.pattern && self.flags == other.flags
+    }
+}
+
+impl Eq for PrecompiledPattern {}
+
+impl Hash for PrecompiledPattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pattern.hash(state);
+        self.flags.hash(state);
+    }
+}
+
+/// Returns the precompiled regex for its own pattern and flags and compiles
+/// any other pattern, and a restored pattern that was not compiled yet, with
+/// `fallback`.
+#[derive(Debug)]
+pub(super) struct PrecompiledPatternCompiler<'a> {
+    pub(super) precompiled: &'a PrecompiledPattern,
+    pub(super) fallback: &'a dyn PatternCompiler,
+}
+
+impl PatternCompiler for PrecompiledPatternCompiler<'_> {
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+        if pattern == self.precompiled.pattern
+            && flags == self.precompiled.flags.as_deref()
+        {
+            self.precompiled.regex(self.fallback)
+        } else {
+            self.fallback.compile(pattern, flags)
+        }
+    }
+
+    fn wrapped(&self) -> Option<&dyn PatternCompiler> {
+        Some(self.fallback)
+    }
+}
+
+/// Rejects the patterns whose [`pattern_complexity`] is above
+/// `max_complexity` and compiles the others with `inner`.
+#[derive(Debug)]
+pub(super) struct ComplexityLimitedCompiler<'a> {
+    pub(super) inner: &'a dyn PatternCompiler,
+    pub(super) max_complexity: usize,
+}
+
+impl PatternCompiler for ComplexityLimitedCompiler<'_> {
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+        // a pattern that does not parse is left to `inner` to report
+        self.check(pattern, pattern_complexity(pattern, flags))?;
+        self.inner.compile(pattern, flags)
+    }
+
+    fn wrapped(&self) -> Option<&dyn PatternCompiler> {
+        Some(self.inner)
+    }
+
+    #[cfg(feature = "fancy_regex")]
+    fn compile_fancy(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Result<Option<FancyRegex>> {
+        let complexity = super::fancy::fancy_pattern_complexity(pattern, flags);
+        self.check(pattern, complexity)?;
+        self.inner.compile_fancy(pattern, flags)
+    }
+}
+
+impl ComplexityLimitedCompiler<'_> {
+    /// Rejects `pattern` if its `complexity` is above the limit.
+    fn check(&self, pattern: &str, complexity: Option<usize>) -> Result<()> {
+        match complexity.filter(|c| *c > self.max_complexity) {
+            Some(complexity) => exec_err!(
+                "regexp_extract pattern '{pattern}' has a complexity of {complexity}, above the limit of {} set by regexp_extract.max_pattern_complexity",
+                self.max_complexity
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Compiles the patterns with `inner` after rewriting them with
+/// [`posix_pattern`], for [`RegexSyntax::Posix`].
+///
+/// [`RegexSyntax::Posix`]: super::RegexSyntax::Posix
+#[derive(Debug)]
+pub(super) struct PosixSyntaxCompiler<'a> {
+    pub(super) inner: &'a dyn PatternCompiler,
+}
+
+impl PatternCompiler for PosixSyntaxCompiler<'_> {
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+        self.inner.compile(&posix_pattern(pattern, flags), flags)
+    }
+
+    fn anchored_prefix(&self, pattern: &str, flags: Option<&str>) -> Option<String> {
+        self.inner
+            .anchored_prefix(&posix_pattern(pattern, flags), flags)
+    }
+
+    fn repeated_class(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Option<Vec<RangeInclusive<char>>> {
+        self.inner
+            .repeated_class(&posix_pattern(pattern, flags), flags)
+    }
+
+    fn ascii_bytes_regex(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Option<BytesRegex> {
+        self.inner
+            .ascii_bytes_regex(&posix_pattern(pattern, flags), flags)
+    }
+
+    #[cfg(feature = "fancy_regex")]
+    fn compile_fancy(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Result<Option<FancyRegex>> {
+        // the syntax tree of the regex crate cannot hold a backreference or a
+        // look-around, so the pattern is only compiled if it needs no rewrite
+        if super::fancy::is_fancy_posix_rewritten(pattern, flags) {
+            return exec_err!(
+                "regexp_extract pattern '{pattern}' needs backtracking, which the posix syntax does not support with \\d, \\s, \\w or a word boundary, use an ASCII class such as [[:digit:]] instead"
+            );
+        }
+        self.inner.compile_fancy(pattern, flags)
+    }
+}
+
+/// Compiles the patterns with `inner` through the patterns shared by the
+/// regex functions of the session, if any, so that a pattern another function
+/// already compiled is not compiled again.
+///
+/// `inner` must compile like the [`DefaultPatternCompiler`] to share the
+/// patterns, since the other functions compile the patterns they share that
+/// way.
+#[derive(Debug)]
+pub(super) struct SharingPatternCompiler<'a> {
+    pub(super) inner: &'a dyn PatternCompiler,
+    pub(super) shared: Option<&'a SharedRegexes>,
+}
+
+impl PatternCompiler for SharingPatternCompiler<'_> {
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+        match self.shared {
+            Some(shared) => shared
+                .get_or_compile(pattern, flags, || self.inner.compile(pattern, flags)),
+            None => self.inner.compile(pattern, flags),
+        }
+    }
+
+    fn wrapped(&self) -> Option<&dyn PatternCompiler> {
+        Some(self.inner)
+    }
+
+    #[cfg(feature = "fancy_regex")]
+    fn compile_fancy(
+        &self,
+        pattern: &str,
+        flags: Option<&str>,
+    ) -> Result<Option<FancyRegex>> {
+        let Some(shared) = self.shared else {
+            return self.inner.compile_fancy(pattern, flags);
+        };
+        // a pattern that does not need backtracking is not shared, its error
+        // is the one of `compile`
+        shared
+            .get_or_compile_fancy(pattern, flags, || {
+                self.inner.compile_fancy(pattern, flags)?.ok_or(None)
+            })
+            .map(Some)
+            .or_else(|error| error.map_or(Ok(None), Err))
+    }
+}
+
+/// Returns the regexes compiled by [`RegexpExtractFunc::preload_patterns`]
+/// for the patterns without flags and compiles any other pattern with
+/// `fallback`.
+///
+/// [`RegexpExtractFunc::preload_patterns`]: super::RegexpExtractFunc::preload_patterns
+#[derive(Debug)]
+pub(super) struct PreloadedPatternCompiler {
+    pub(super) preloaded: HashMap<String, Regex>,
+    pub(super) fallback: Arc<dyn PatternCompiler>,
+}
+
+impl PatternCompiler for PreloadedPatternCompiler {
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+        match self.preloaded.get(pattern).filter(|_| flags.is_none()) {
+            Some(regex) => Ok(regex.clone()),
+            None => self.fallback.compile(pattern, flags),
+        }
+    }
+
+    fn wrapped(&self) -> Option<&dyn PatternCompiler> {
+        Some(self.fallback.as_ref())
+    }
+}
+
+/// Compiles `pattern` with the optional inline `flags`.
+///
+/// Unlike [`compile_regex`](crate::regex::compile_regex), the error includes
+/// the reason why the pattern is invalid.
+pub(crate) fn compile_pattern(pattern: &str, flags: Option<&str>) -> Result<Regex> {
+    let regex = match flags {
+        None | Some("") => Regex::new(pattern),
+        Some(flags) if flags.contains('g') => {
+            return exec_err!("regexp_extract() does not support the global flag");
+        }
+        Some(flags) => Regex::new(&format!("(?{flags}){pattern}")),
+    };
+    regex.map_err(|e| {
+        DataFusionError::Execution(format!("unable to compile pattern '{pattern}': {e}"))
+    })
+}
+
+/// Returns the literal prefix that a value must start with for `pattern` to
+/// match it, such as `GET ` for `^GET (\S+)`, or `None` if `pattern` is not
+/// anchored to the start of the value or does not begin with a literal.
+///
+/// The pattern is parsed with the same defaults as [`compile_pattern`], so
+/// flags such as `i` or `m` that make the prefix inexact disable it.
+pub(super) fn anchored_literal_prefix(
+    pattern: &str,
+    flags: Option<&str>,
+) -> Option<String> {
+    let hir = match flags {
+        None | Some("") => regex_syntax::parse(pattern),
+        Some(flags) => regex_syntax::parse(&format!("(?{flags}){pattern}")),
+    }
+    .ok()?;
+    let HirKind::Concat(items) = hir.kind() else {
+        return None;
+    };
+    let (first, rest) = items.split_first()?;
+    if !matches!(first.kind(), HirKind::Look(Look::Start)) {
+        return None;
+    }
+
+    let mut prefix = vec![];
+    for item in rest {
+        if !push_literal_prefix(item, &mut prefix) {
+            break;
+        }
+    }
+    String::from_utf8(prefix)
+        .ok()
+        .filter(|prefix| !prefix.is_empty())
+}
+
+/// Returns the complexity score of `pattern`, or `None` if it does not parse.
+///
+/// Each unbounded repetition adds its nesting depth among repetitions, so
+/// that `a+b+` scores 2 while `(a+)+`, whose inner repetition is nested in
+/// the outer one, scores 3.
+pub(super) fn pattern_complexity(pattern: &str, flags: Option<&str>) -> Option<usize> {
+    let hir = match flags {
+        None | Some("") => regex_syntax::parse(pattern),
+        Some(flags) => regex_syntax::parse(&format!("(?{flags}){pattern}")),
+    }
+    .ok()?;
+    Some(hir_complexity(&hir, 0))
+}
+
+/// Rewrites the Perl classes `\d`, `\s` and `\w` of `pattern`, their
+/// negations and the word boundaries such as `\b` to the ASCII classes and
+/// boundaries of [`RegexSyntax::Posix`].
+///
+/// The pattern is parsed with the `x` flag of `flags`, if any, and printed
+/// back from its syntax tree, so escapes, bracketed classes and comments are
+/// told apart from the classes. A Perl class becomes an ASCII class, so `\d`
+/// and `[\d.]` become `[[:digit:]]` and `[[:digit:].]`, and a word boundary
+/// is matched with Unicode mode disabled, so `\b` becomes `(?-u:\b)`. A
+/// pattern that does not parse is returned unchanged for the compiler to
+/// report.
+///
+/// [`RegexSyntax::Posix`]: super::RegexSyntax::Posix
+pub(super) fn posix_pattern(pattern: &str, flags: Option<&str>) -> String {
+    let ignore_whitespace = flags.is_some_and(|flags| flags.contains('x'));
+    let Ok(mut ast) = ParserBuilder::new()
+        .ignore_whitespace(ignore_whitespace)
+        .build()
+        .parse(pattern)
+    else {
+        return pattern.to_string();
+    };
+    if !posix_ast(&mut ast) {
+        return pattern.to_string();
+    }
+    let mut rewritten = String::with_capacity(pattern.len());
+    Printer::new()
+        .print(&ast, &mut rewritten)
+        .expect("printing to a String does not fail");
+    rewritten
+}
+
+/// Returns whether [`posix_pattern`] rewrites `pattern`, which must not use
+/// any flag.
+#[cfg(feature = "fancy_regex")]
+pub(super) fn is_posix_rewritten(pattern: &str) -> bool {
+    ParserBuilder::new()
+        .build()
+        .parse(pattern)
+        .is_ok_and(|mut ast| posix_ast(&mut ast))
+}
+
+/// Rewrites the Perl classes and word boundaries of `ast` for
+/// [`posix_pattern`], returning whether there were any.
+fn posix_ast(ast: &mut Ast) -> bool {
+    match ast {
+        Ast::ClassPerl(class) => {
+            let span = class.span;
+            *ast = Ast::class_bracketed(ClassBracketed {
+                span,
+                negated: false,
+                kind: ClassSet::Item(ClassSetItem::Ascii(ascii_class(class))),
+            });
+            true
+        }
+        Ast::ClassBracketed(class) => posix_class_set(&mut class.kind),
+        Ast::Assertion(assertion) if is_word_boundary(&assertion.kind) => {
+            let span = assertion.span;
+            let assertion = std::mem::replace(ast, Ast::empty(span));
+            let flag = |kind| FlagsItem { span, kind };
+            *ast = Ast::group(Group {
+                span,
+                kind: GroupKind::NonCapturing(Flags {
+                    span,
+                    items: vec![
+                        flag(FlagsItemKind::Negation),
+                        flag(FlagsItemKind::Flag(Flag::Unicode)),
+                    ],
+                }),
+                ast: Box::new(assertion),
+            });
+            true
+        }
+        Ast::Repetition(repetition) => posix_ast(&mut repetition.ast),
+        Ast::Group(group) => posix_ast(&mut group.ast),
+        Ast::Alternation(alternation) => posix_asts(&mut alternation.asts),
+        Ast::Concat(concat) => posix_asts(&mut concat.asts),
+        _ => false,
+    }
+}
+
+fn posix_asts(asts: &mut [Ast]) -> bool {
+    asts.iter_mut()
+        .fold(false, |rewritten, ast| posix_ast(ast) | rewritten)
+}
+
+/// Rewrites the Perl classes of a bracketed class for [`posix_pattern`].
+fn posix_class_set(set: &mut ClassSet) -> bool {
+    match set {
+        ClassSet::Item(item) => posix_class_set_item(item),
+        ClassSet::BinaryOp(op) => {
+            posix_class_set(&mut op.lhs) | posix_class_set(&mut op.rhs)
+        }
+    }
+}
+
+fn posix_class_set_item(item: &mut ClassSetItem) -> bool {
+    match item {
+        ClassSetItem::Perl(class) => {
+            *item = ClassSetItem::Ascii(ascii_class(class));
+            true
+        }
+        ClassSetItem::Bracketed(class) => posix_class_set(&mut class.kind),
+        ClassSetItem::Union(union) => {
+            union.items.iter_mut().fold(false, |rewritten, item| {
+                posix_class_set_item(item) | rewritten
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Returns the ASCII class matching like `class` with Unicode mode disabled.
+fn ascii_class(class: &ClassPerl) -> ClassAscii {
+    ClassAscii {
+        span: class.span,
+        kind: match class.kind {
+            ClassPerlKind::Digit => ClassAsciiKind::Digit,
+            ClassPerlKind::Space => ClassAsciiKind::Space,
+            ClassPerlKind::Word => ClassAsciiKind::Word,
+        },
+        negated: class.negated,
+    }
+}
+
+fn is_word_boundary(kind: &AssertionKind) -> bool {
+    !matches!(
+        kind,
+        AssertionKind::StartLine
+            | AssertionKind::EndLine
+            | AssertionKind::StartText
+            | AssertionKind::EndText
+    )
+}
+
+/// Returns the complexity score of `hir` nested in `depth` repetitions.
+pub(super) fn hir_complexity(hir: &Hir, depth: usize) -> usize {
+    match hir.kind() {
+        HirKind::Repetition(repetition) => {
+            let depth = depth + 1;
+            let score = if repetition.max.is_none() { depth } else { 0 };
+            score + hir_complexity(&repetition.sub, depth)
+        }
+        HirKind::Capture(capture) => hir_complexity(&capture.sub, depth),
+        HirKind::Concat(items) | HirKind::Alternation(items) => {
+            items.iter().map(|item| hir_complexity(item, depth)).sum()
+        }
+        _ => 0,
+    }
+}
+
+/// Appends the literal prefix of `hir` to `prefix` and returns whether all of
+/// `hir` is literal, so that the prefix continues after it.
+pub(super) fn push_literal_prefix(hir: &Hir, prefix: &mut Vec<u8>) -> bool {
+    match hir.kind() {
+        HirKind::Literal(literal) => {
+            prefix.extend_from_slice(&literal.0);
+            true
+        }
+        HirKind::Capture(capture) => push_literal_prefix(&capture.sub, prefix),
+        HirKind::Concat(items) => {
+            items.iter().all(|item| push_literal_prefix(item, prefix))
+        }
+        _ => false,
+    }
+}
+
+/// Returns the character ranges of the class that `pattern` repeats if it is
+/// a single greedy `+` repetition of a class, optionally in a capture group.
+pub(super) fn repeated_class_ranges(
+    pattern: &str,
+    flags: Option<&str>,
+) -> Option<Vec<RangeInclusive<char>>> {
+    let hir = match flags {
+        None | Some("") => regex_syntax::parse(pattern),
+        Some(flags) => regex_syntax::parse(&format!("(?{flags}){pattern}")),
+    }
+    .ok()?;
+    let repetition = match hir.kind() {
+        HirKind::Capture(capture) => capture.sub.as_ref(),
+        _ => &hir,
+    };
+    let HirKind::Repetition(repetition) = repetition.kind() else {
+        return None;
+    };
+    if repetition.min != 1 || repetition.max.is_some() || !repetition.greedy {
+        return None;
+    }
+    // a class of bytes only appears with Unicode mode disabled, where the
+    // regex may match within a character
+    let HirKind::Class(Class::Unicode(class)) = repetition.sub.kind() else {
+        return None;
+    };
+    Some(
+        class
+            .ranges()
+            .iter()
+            .map(|range| range.start()..=range.end())
+            .collect(),
+    )
+}
+
+/// Compiles `pattern` for matching bytes if every character it can match is
+/// ASCII and it cannot match the empty string, see
+/// [`PatternCompiler::ascii_bytes_regex`].
+///
+/// The pattern is parsed with the same defaults as [`compile_pattern`], so
+/// flags such as `i` that make a letter match non-ASCII characters, like the
+/// Kelvin sign for `k`, disable it.
+pub(super) fn compile_ascii_bytes_pattern(
+    pattern: &str,
+    flags: Option<&str>,
+) -> Option<BytesRegex> {
+    let pattern = match flags {
+        None | Some("") => pattern.to_string(),
+        Some(flags) => format!("(?{flags}){pattern}"),
+    };
+    let hir = regex_syntax::parse(&pattern).ok()?;
+    // an empty match may fall within a multibyte character, which the bytes
+    // of a value do not tell apart from a character boundary
+    if hir.properties().minimum_len().is_none_or(|len| len == 0) || !is_ascii_hir(&hir) {
+        return None;
+    }
+    BytesRegex::new(&pattern).ok()
+}
+
+/// Returns whether `hir` only matches ASCII characters and only looks around
+/// at ASCII characters.
+pub(super) fn is_ascii_hir(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Empty => true,
+        HirKind::Literal(literal) => literal.0.is_ascii(),
+        HirKind::Class(Class::Unicode(class)) => class.is_ascii(),
+        HirKind::Class(Class::Bytes(class)) => class.is_ascii(),
+        HirKind::Look(look) => matches!(
+            look,
+            Look::Start
+                | Look::End
+                | Look::StartLF
+                | Look::EndLF
+                | Look::StartCRLF
+                | Look::EndCRLF
+                | Look::WordAscii
+                | Look::WordAsciiNegate
+                | Look::WordStartAscii
+                | Look::WordEndAscii
+                | Look::WordStartHalfAscii
+                | Look::WordEndHalfAscii
+        ),
+        HirKind::Repetition(repetition) => is_ascii_hir(&repetition.sub),
+        HirKind::Capture(capture) => is_ascii_hir(&capture.sub),
+        HirKind::Concat(items) | HirKind::Alternation(items) => {
+            items.iter().all(is_ascii_hir)
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The fancy-regex fallback of `regexp_extract` for the patterns that need
+//! backtracking
+
+use super::appenders::ExtractAppender;
+use super::compilers::is_posix_rewritten;
+use super::{
+    group_index_at, occurrence_at, validate_occurrence, InputTransform, TransformedInput,
+};
+use arrow::array::{Int64Array, StringArrayType};
+use datafusion_common::{exec_datafusion_err, exec_err, Result};
+use fancy_regex::{Assertion, Expr, Regex as FancyRegex};
+
+/// Returns `pattern` compiled with fancy-regex if the regex crate rejects it
+/// only because it needs backtracking, as for a backreference such as `\1` or
+/// a look-around, or `None` otherwise.
+///
+/// Every other pattern keeps the regex crate, so a pattern both engines accept
+/// numbers its capture groups the same way and keeps its linear time matching.
+pub(super) fn compile_fancy_pattern(
+    pattern: &str,
+    flags: Option<&str>,
+) -> Option<FancyRegex> {
+    use regex_syntax::ast::{parse::Parser, ErrorKind};
+    let pattern = match flags {
+        None | Some("") => pattern.to_string(),
+        Some(flags) if flags.contains('g') => return None,
+        Some(flags) => format!("(?{flags}){pattern}"),
+    };
+    let error = Parser::new().parse(&pattern).err()?;
+    match error.kind() {
+        ErrorKind::UnsupportedBackreference | ErrorKind::UnsupportedLookAround => {
+            FancyRegex::new(&pattern).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Returns the complexity score of `pattern` parsed by fancy-regex, or `None`
+/// if it does not parse, scored like
+/// [`pattern_complexity`](super::compilers::pattern_complexity).
+pub(super) fn fancy_pattern_complexity(
+    pattern: &str,
+    flags: Option<&str>,
+) -> Option<usize> {
+    let tree = match flags {
+        None | Some("") => Expr::parse_tree(pattern),
+        Some(flags) => Expr::parse_tree(&format!("(?{flags}){pattern}")),
+    }
+    .ok()?;
+    Some(expr_complexity(&tree.expr, 0))
+}
+
+/// Returns the complexity score of `expr` nested in `depth` repetitions.
+fn expr_complexity(expr: &Expr, depth: usize) -> usize {
+    match expr {
+        Expr::Repeat { child, hi, .. } => {
+            let depth = depth + 1;
+            let score = if *hi == usize::MAX { depth } else { 0 };
+            score + expr_complexity(child, depth)
+        }
+        Expr::Group(child) | Expr::LookAround(child, _) | Expr::AtomicGroup(child) => {
+            expr_complexity(child, depth)
+        }
+        Expr::Concat(items) | Expr::Alt(items) => {
+            items.iter().map(|item| expr_complexity(item, depth)).sum()
+        }
+        Expr::Conditional {
+            condition,
+            true_branch,
+            false_branch,
+        } => [condition, true_branch, false_branch]
+            .iter()
+            .map(|item| expr_complexity(item, depth))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// Returns whether `pattern`, parsed by fancy-regex, has a Perl class or a
+/// word boundary that [`RegexSyntax::Posix`] restricts to ASCII.
+///
+/// [`RegexSyntax::Posix`]: super::RegexSyntax::Posix
+pub(super) fn is_fancy_posix_rewritten(pattern: &str, flags: Option<&str>) -> bool {
+    let tree = match flags {
+        None | Some("") => Expr::parse_tree(pattern),
+        Some(flags) => Expr::parse_tree(&format!("(?{flags}){pattern}")),
+    };
+    tree.is_ok_and(|tree| is_expr_posix_rewritten(&tree.expr))
+}
+
+fn is_expr_posix_rewritten(expr: &Expr) -> bool {
+    match expr {
+        // fancy-regex leaves the classes to the regex crate
+        Expr::Delegate { inner, .. } => is_posix_rewritten(inner),
+        Expr::Assertion(assertion) => matches!(
+            assertion,
+            Assertion::LeftWordBoundary
+                | Assertion::RightWordBoundary
+                | Assertion::WordBoundary
+                | Assertion::NotWordBoundary
+        ),
+        Expr::Repeat { child, .. }
+        | Expr::Group(child)
+        | Expr::LookAround(child, _)
+        | Expr::AtomicGroup(child) => is_expr_posix_rewritten(child),
+        Expr::Concat(items) | Expr::Alt(items) => {
+            items.iter().any(is_expr_posix_rewritten)
+        }
+        Expr::Conditional {
+            condition,
+            true_branch,
+            false_branch,
+        } => [condition, true_branch, false_branch]
+            .into_iter()
+            .any(|item| is_expr_posix_rewritten(item)),
+        _ => false,
+    }
+}
+
+/// Extracts with a constant pattern compiled by
+/// [`PatternCompiler::compile_fancy`](super::PatternCompiler::compile_fancy).
+pub(super) fn fancy_regexp_extract<'a, S: StringArrayType<'a>, A: ExtractAppender>(
+    values: S,
+    regex: &FancyRegex,
+    group_index_array: Option<&Int64Array>,
+    occurrence_array: Option<&Int64Array>,
+    transform: Option<InputTransform>,
+    mut builder: A,
+) -> Result<A> {
+    let mut input = TransformedInput::new(transform);
+    for i in 0..values.len() {
+        match (
+            group_index_at(group_index_array, i),
+            occurrence_at(occurrence_array, i),
+        ) {
+            (Some(group_index), Some(occurrence)) if values.is_valid(i) => {
+                builder.append_match(find_fancy_occurrence_group(
+                    regex,
+                    input.apply(values.value(i)),
+                    group_index,
+                    occurrence,
+                )?);
+            }
+            _ => builder.append_null(),
+        }
+    }
+    Ok(builder)
+}
+
+/// Like [`find_occurrence_group`], but with a fancy-regex, whose matching
+/// fails if it exceeds the backtracking limit of fancy-regex.
+pub(super) fn find_fancy_occurrence_group<'h>(
+    regex: &FancyRegex,
+    value: &'h str,
+    group_index: i64,
+    occurrence: i64,
+) -> Result<Option<&'h str>> {
+    let occurrence = validate_occurrence(occurrence)?;
+    let group_count = regex.captures_len() - 1;
+    let group_index = match usize::try_from(group_index) {
+        Ok(group_index) if group_index <= group_count => group_index,
+        _ => {
+            return exec_err!(
+                "Regex group index {group_index} is out of range, the pattern '{}' has {group_count} capture groups",
+                regex.as_str()
+            )
+        }
+    };
+    for (n, captures) in regex.captures_iter(value).enumerate() {
+        let captures = captures.map_err(|e| {
+            exec_datafusion_err!("unable to match pattern '{}': {e}", regex.as_str())
+        })?;
+        if n + 1 == occurrence {
+            return Ok(Some(captures.get(group_index).map_or("", |m| m.as_str())));
+        }
+    }
+    Ok(None)
+}
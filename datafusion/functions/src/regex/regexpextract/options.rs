@@ -0,0 +1,268 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The session options of the `regexp_extract` functions
+
+use datafusion_common::config::{
+    ConfigEntry, ConfigExtension, ConfigField, ConfigOptions, ExtensionOptions, Visit,
+};
+use datafusion_common::{config_namespace, DataFusionError, Result};
+use std::any::Any;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+config_namespace! {
+    /// The options of the `regexp_extract` functions, set with the
+    /// `regexp_extract` prefix, such as
+    /// `SET regexp_extract.null_group_index_is_error = true`.
+    ///
+    /// The session state registers them by default. A function evaluated
+    /// with [`ConfigOptions`] that do not hold them uses the defaults.
+    pub struct RegexpExtractConfig {
+        /// When set to true, `regexp_extract` fails if a row has a NULL capture
+        /// group index, for pipelines that treat a missing group index as a
+        /// bug. When set to false, such rows yield NULL as SQL requires.
+        pub null_group_index_is_error: bool, default = false
+
+        /// Maximum number of capture groups the schema of
+        /// `regexp_extract_typed` may list. Each group becomes a field of the
+        /// struct it returns, so planning fails above the limit rather than
+        /// building a huge struct type from an untrusted schema.
+        pub typed_max_groups: usize, default = 64
+
+        /// Maximum number of characters of each value `regexp_extract`
+        /// returns. Longer captures are truncated at a character boundary,
+        /// which bounds the size of the output rows for untrusted data. None
+        /// means no limit.
+        pub max_capture_length: Option<usize>, default = None
+
+        /// How `regexp_extract` treats string values that are not valid
+        /// UTF-8, which Arrow string arrays must not hold but corrupt data
+        /// may. Valid values are: trust, error, lossy. `trust` relies on the
+        /// UTF-8 guarantee without checking, `error` fails with the index of
+        /// the first invalid row and `lossy` replaces invalid sequences with
+        /// U+FFFD before matching.
+        pub invalid_utf8: InvalidUtf8Handling, default = InvalidUtf8Handling::Trust
+
+        /// Maximum complexity score of a `regexp_extract` pattern, for engines
+        /// that evaluate untrusted patterns. Each unbounded repetition (`*`,
+        /// `+` or `{n,}`) adds its nesting depth among repetitions, so `a+b+`
+        /// scores 2 and `(a+)+` scores 3. Patterns above the limit fail to
+        /// compile. None disables the check.
+        pub max_pattern_complexity: Option<usize>, default = None
+
+        /// When set to true, `regexp_extract.max_capture_length` counts the
+        /// grapheme clusters of a capture, the characters as displayed,
+        /// instead of its Unicode scalar values, so that truncating never
+        /// separates a character from its combining marks.
+        pub truncate_graphemes: bool, default = false
+
+        /// When set to true, `regexp_extract` collapses every run of
+        /// whitespace in a capture to a single space and trims the capture,
+        /// which normalizes human-entered fields. This applies before
+        /// `regexp_extract.max_capture_length`.
+        pub collapse_whitespace: bool, default = false
+
+        /// Maximum number of distinct pattern compile errors `regexp_extract`
+        /// collects from a batch whose patterns are a column before failing,
+        /// so that all the invalid patterns of a rules table are reported
+        /// together. The batch fails with the collected errors at its end.
+        /// The default of 1 fails on the first pattern that does not compile.
+        pub max_compile_errors: usize, default = 1
+
+        /// The regular expression syntax of `regexp_extract` patterns. Valid
+        /// values are: rust, posix. `rust` is the syntax of the Rust regex
+        /// crate, where `\d`, `\s` and `\w` match Unicode digits, spaces
+        /// and word characters. `posix` restricts them and the word
+        /// boundaries such as `\b` to ASCII, like the POSIX classes such as
+        /// `[[:digit:]]`, which are always ASCII. Everything else keeps the
+        /// Rust syntax, including Unicode classes such as `\p{L}`,
+        /// case-insensitive matching and leftmost-first alternation rather
+        /// than the leftmost-longest match of POSIX. A pattern that needs
+        /// backtracking cannot use `\d`, `\s`, `\w` or word boundaries with
+        /// `posix`.
+        pub syntax: RegexSyntax, default = RegexSyntax::Rust
+
+        /// Maximum number of matches `regexp_extract_all` returns across all
+        /// the rows of a batch, which bounds the memory of its output for
+        /// untrusted data that matches excessively. A batch above the limit
+        /// fails with the index of the row that exceeded it. None disables
+        /// the limit.
+        pub all_total_matches_limit: Option<usize>, default = None
+    }
+}
+
+/// How `regexp_extract` treats string values that are not valid UTF-8,
+/// see `regexp_extract.invalid_utf8`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidUtf8Handling {
+    /// Relies on Arrow's guarantee that string arrays hold valid UTF-8
+    #[default]
+    Trust,
+    /// Checks the values and fails on the first row that is invalid
+    Error,
+    /// Checks the values and replaces invalid sequences with U+FFFD
+    Lossy,
+}
+
+impl FromStr for InvalidUtf8Handling {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trust" | "" => Ok(Self::Trust),
+            "error" => Ok(Self::Error),
+            "lossy" => Ok(Self::Lossy),
+            other => Err(DataFusionError::Configuration(format!(
+                "Invalid UTF-8 handling: {other}. Expected one of: trust, error, lossy"
+            ))),
+        }
+    }
+}
+
+impl ConfigField for InvalidUtf8Handling {
+    fn visit<V: Visit>(&self, v: &mut V, key: &str, description: &'static str) {
+        v.some(key, self, description)
+    }
+
+    fn set(&mut self, _: &str, value: &str) -> Result<()> {
+        *self = InvalidUtf8Handling::from_str(value)?;
+        Ok(())
+    }
+}
+
+impl Display for InvalidUtf8Handling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            Self::Trust => "trust",
+            Self::Error => "error",
+            Self::Lossy => "lossy",
+        };
+        write!(f, "{str}")
+    }
+}
+
+/// The regular expression syntax `regexp_extract` compiles patterns with,
+/// see `regexp_extract.syntax`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RegexSyntax {
+    /// The syntax of the Rust `regex` crate, with Unicode aware `\d`, `\s`
+    /// and `\w`
+    #[default]
+    Rust,
+    /// Like `Rust`, but `\d`, `\s`, `\w` and the word boundaries such as
+    /// `\b` only consider ASCII characters, like the POSIX classes
+    /// `[[:digit:]]`, `[[:space:]]` and `[[:word:]]`
+    Posix,
+}
+
+impl FromStr for RegexSyntax {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rust" | "" => Ok(Self::Rust),
+            "posix" => Ok(Self::Posix),
+            other => Err(DataFusionError::Configuration(format!(
+                "Invalid regex syntax: {other}. Expected one of: rust, posix"
+            ))),
+        }
+    }
+}
+
+impl ConfigField for RegexSyntax {
+    fn visit<V: Visit>(&self, v: &mut V, key: &str, description: &'static str) {
+        v.some(key, self, description)
+    }
+
+    fn set(&mut self, _: &str, value: &str) -> Result<()> {
+        *self = RegexSyntax::from_str(value)?;
+        Ok(())
+    }
+}
+
+impl Display for RegexSyntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            Self::Rust => "rust",
+            Self::Posix => "posix",
+        };
+        write!(f, "{str}")
+    }
+}
+
+impl ConfigExtension for RegexpExtractConfig {
+    const PREFIX: &'static str = "regexp_extract";
+}
+
+impl ExtensionOptions for RegexpExtractConfig {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn cloned(&self) -> Box<dyn ExtensionOptions> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        ConfigField::set(self, key, value)
+    }
+
+    fn entries(&self) -> Vec<ConfigEntry> {
+        struct Visitor(Vec<ConfigEntry>);
+
+        impl Visit for Visitor {
+            fn some<V: Display>(
+                &mut self,
+                key: &str,
+                value: V,
+                description: &'static str,
+            ) {
+                self.0.push(ConfigEntry {
+                    key: key.to_string(),
+                    value: Some(value.to_string()),
+                    description,
+                })
+            }
+
+            fn none(&mut self, key: &str, description: &'static str) {
+                self.0.push(ConfigEntry {
+                    key: key.to_string(),
+                    value: None,
+                    description,
+                })
+            }
+        }
+
+        // unlike `extensions_options!`, the keys include the prefix, as they
+        // do for the options of `ConfigOptions`
+        let mut v = Visitor(vec![]);
+        self.visit(&mut v, Self::PREFIX, "");
+        v.0
+    }
+}
+
+impl RegexpExtractConfig {
+    /// Returns the options registered in `config`, or the defaults.
+    pub fn from_config(config: &ConfigOptions) -> Self {
+        config.extensions.get::<Self>().cloned().unwrap_or_default()
+    }
+}
@@ -0,0 +1,3406 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::appenders::collapse_whitespace;
+use super::compilers::{
+    anchored_literal_prefix, compile_ascii_bytes_pattern, pattern_complexity,
+    posix_pattern, repeated_class_ranges,
+};
+#[cfg(feature = "fancy_regex")]
+use super::fancy::{compile_fancy_pattern, fancy_pattern_complexity};
+use super::*;
+use arrow::array::{
+    DictionaryArray, FixedSizeBinaryArray, GenericStringArray, Int32Array,
+    LargeStringBuilder, StringViewBuilder,
+};
+use arrow::buffer::{Buffer, OffsetBuffer};
+use arrow::datatypes::Int32Type;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::DFSchema;
+use datafusion_expr::execution_props::ExecutionProps;
+use datafusion_expr::simplify::SimplifyContext;
+use datafusion_expr::ScalarFunctionArgs;
+use datafusion_expr::{cast, col, lit, try_cast};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn regexp_extract_with_scalar_values(args: &[ScalarValue]) -> Result<ColumnarValue> {
+    let args_values = args
+        .iter()
+        .map(|sv| ColumnarValue::Scalar(sv.clone()))
+        .collect();
+
+    let arg_fields = args
+        .iter()
+        .enumerate()
+        .map(|(idx, a)| Field::new(format!("arg_{idx}"), a.data_type(), true).into())
+        .collect::<Vec<_>>();
+
+    RegexpExtractFunc::new().invoke_with_args(ScalarFunctionArgs {
+        args: args_values,
+        arg_fields,
+        number_rows: 1,
+        return_field: Field::new("f", DataType::Utf8, true).into(),
+        config_options: Arc::new(ConfigOptions::default()),
+    })
+}
+
+/// Like [`regexp_extract_with_scalar_values`], but passes every argument
+/// as a single element array.
+fn regexp_extract_with_array_values(args: &[ScalarValue]) -> Result<ArrayRef> {
+    let args_values = args
+        .iter()
+        .map(|sv| sv.to_array().map(ColumnarValue::Array))
+        .collect::<Result<Vec<_>>>()?;
+
+    let arg_fields = args
+        .iter()
+        .enumerate()
+        .map(|(idx, a)| Field::new(format!("arg_{idx}"), a.data_type(), true).into())
+        .collect::<Vec<_>>();
+
+    let result = RegexpExtractFunc::new().invoke_with_args(ScalarFunctionArgs {
+        args: args_values,
+        arg_fields,
+        number_rows: 1,
+        return_field: Field::new("f", DataType::Utf8, true).into(),
+        config_options: Arc::new(ConfigOptions::default()),
+    })?;
+    match result {
+        ColumnarValue::Array(array) => Ok(array),
+        ColumnarValue::Scalar(scalar) => {
+            panic!("expected an array result for array arguments, got {scalar:?}")
+        }
+    }
+}
+
+#[test]
+fn test_regexp_extract_scalar_and_array_agree() {
+    let utf8 = |v: &str| ScalarValue::Utf8(Some(v.to_string()));
+    let cases = [
+        // matched
+        (
+            vec![
+                utf8("100-200"),
+                utf8(r"(\d+)-(\d+)"),
+                ScalarValue::Int64(Some(2)),
+            ],
+            Some("200"),
+        ),
+        // no match
+        (vec![utf8("abc"), utf8(r"(\d+)")], Some("")),
+        // the group did not participate in the match
+        (
+            vec![utf8("abc"), utf8("(x)?(a)"), ScalarValue::Int64(Some(1))],
+            Some(""),
+        ),
+        (vec![ScalarValue::Utf8(None), utf8(r"(\d+)")], None),
+        (vec![utf8("abc"), ScalarValue::Utf8(None)], None),
+        (
+            vec![utf8("abc"), utf8("(b)"), ScalarValue::Int64(None)],
+            None,
+        ),
+        (
+            vec![
+                utf8("ABC"),
+                utf8("(b)"),
+                ScalarValue::Int64(Some(1)),
+                utf8("i"),
+            ],
+            Some("B"),
+        ),
+    ];
+
+    for (args, expected) in cases {
+        let expected = ScalarValue::Utf8(expected.map(str::to_string));
+
+        let ColumnarValue::Scalar(scalar) =
+            regexp_extract_with_scalar_values(&args).unwrap()
+        else {
+            panic!("expected a scalar result for scalar arguments {args:?}");
+        };
+        assert_eq!(scalar, expected, "scalar arguments {args:?}");
+
+        let array = regexp_extract_with_array_values(&args).unwrap();
+        assert_eq!(array.data_type(), &DataType::Utf8);
+        assert_eq!(
+            ScalarValue::try_from_array(&array, 0).unwrap(),
+            expected,
+            "array arguments {args:?}"
+        );
+    }
+}
+
+#[test]
+fn test_regexp_extract_scalar_and_array_paths_differential() {
+    let utf8 = |v: Option<&str>| ScalarValue::Utf8(v.map(str::to_string));
+    let values = [
+        None,
+        Some(""),
+        Some("abc"),
+        Some("order 42 shipped"),
+        Some("日本語 123 café"),
+        Some("ÄÖÜ-äöü"),
+    ];
+    let patterns = [
+        None,
+        Some(r"(\d+)"),
+        Some(r"(\w+)\s(\d+)"),
+        Some("(é)"),
+        Some("(?i)(ä+)"),
+        Some("(x)?(.)"),
+    ];
+    let group_indices = [None, Some(0), Some(1), Some(2)];
+
+    for value in values {
+        for pattern in patterns {
+            for group_index in group_indices {
+                let args = [utf8(value), utf8(pattern), ScalarValue::Int64(group_index)];
+                let scalar = regexp_extract_with_scalar_values(&args).map(|result| {
+                    let ColumnarValue::Scalar(scalar) = result else {
+                        panic!("expected a scalar result for scalar arguments {args:?}");
+                    };
+                    scalar
+                });
+                let array = regexp_extract_with_array_values(&args)
+                    .and_then(|array| ScalarValue::try_from_array(&array, 0));
+
+                match (scalar, array) {
+                    (Ok(scalar), Ok(array)) => {
+                        assert_eq!(scalar, array, "arguments {args:?}")
+                    }
+                    // an invalid group index fails both paths alike
+                    (Err(scalar), Err(array)) => assert_eq!(
+                        scalar.strip_backtrace(),
+                        array.strip_backtrace(),
+                        "arguments {args:?}"
+                    ),
+                    (scalar, array) => panic!(
+                        "arguments {args:?} disagree: scalar {scalar:?}, array {array:?}"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+fn regexp_extract_with_columnar_values(
+    args: Vec<ColumnarValue>,
+    number_rows: usize,
+) -> Result<ColumnarValue> {
+    let arg_fields = args
+        .iter()
+        .enumerate()
+        .map(|(idx, a)| Field::new(format!("arg_{idx}"), a.data_type(), true).into())
+        .collect::<Vec<_>>();
+
+    RegexpExtractFunc::new().invoke_with_args(ScalarFunctionArgs {
+        args,
+        arg_fields,
+        number_rows,
+        return_field: Field::new("f", DataType::Utf8, true).into(),
+        config_options: Arc::new(ConfigOptions::default()),
+    })
+}
+
+#[test]
+fn test_regexp_extract_scalar_array_matrix() {
+    // every pattern has two groups, so that any group index is valid for
+    // any pattern
+    let columns: [Vec<ScalarValue>; 3] = [
+        vec![
+            ScalarValue::from("100-200"),
+            ScalarValue::from("abc-de"),
+            ScalarValue::Utf8(None),
+            ScalarValue::from("7"),
+        ],
+        vec![
+            ScalarValue::from(r"(\d+)-(\d+)"),
+            ScalarValue::from(r"(\w+)-(\w+)"),
+            ScalarValue::from(r"(x)?(\w)"),
+            ScalarValue::Utf8(None),
+        ],
+        vec![
+            ScalarValue::Int64(Some(2)),
+            ScalarValue::Int64(Some(1)),
+            ScalarValue::Int64(Some(0)),
+            ScalarValue::Int64(None),
+        ],
+    ];
+    let number_rows = columns[0].len();
+    // a scalar is either the value of the first row or a NULL
+    let scalars = |column: &[ScalarValue]| {
+        [
+            column[0].clone(),
+            ScalarValue::try_from(column[0].data_type()).unwrap(),
+        ]
+    };
+
+    for combination in 0..8 {
+        let is_array = |arg: usize| combination & (1 << arg) != 0;
+        let scalar_choices = (0..3)
+            .map(|arg| {
+                if is_array(arg) {
+                    vec![None]
+                } else {
+                    scalars(&columns[arg]).into_iter().map(Some).collect()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for value in &scalar_choices[0] {
+            for pattern in &scalar_choices[1] {
+                for group_index in &scalar_choices[2] {
+                    let choices = [value, pattern, group_index];
+                    let args = (0..3)
+                        .map(|arg| match choices[arg] {
+                            Some(scalar) => Ok(ColumnarValue::Scalar(scalar.clone())),
+                            None => ScalarValue::iter_to_array(columns[arg].clone())
+                                .map(ColumnarValue::Array),
+                        })
+                        .collect::<Result<Vec<_>>>()
+                        .unwrap();
+                    let description = format!("{args:?}");
+
+                    // each row must agree with extracting from the scalars
+                    // of that row
+                    let expected = (0..number_rows)
+                        .map(|row| {
+                            let row_args = (0..3)
+                                .map(|arg| match choices[arg] {
+                                    Some(scalar) => scalar.clone(),
+                                    None => columns[arg][row].clone(),
+                                })
+                                .collect::<Vec<_>>();
+                            match regexp_extract_with_scalar_values(&row_args)
+                                .unwrap()
+                            {
+                                ColumnarValue::Scalar(scalar) => scalar,
+                                ColumnarValue::Array(_) => panic!(
+                                    "expected a scalar result for scalar arguments {row_args:?}"
+                                ),
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    let result =
+                        regexp_extract_with_columnar_values(args, number_rows).unwrap();
+                    match result {
+                        ColumnarValue::Scalar(scalar) => {
+                            assert_eq!(combination, 0, "{description}");
+                            assert_eq!(scalar, expected[0], "{description}");
+                        }
+                        ColumnarValue::Array(array) => {
+                            assert_ne!(combination, 0, "{description}");
+                            assert_eq!(array.len(), number_rows, "{description}");
+                            assert_eq!(
+                                &array,
+                                &ScalarValue::iter_to_array(expected).unwrap(),
+                                "{description}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // a scalar string is matched against every pattern of the array
+    let result = regexp_extract_with_columnar_values(
+        vec![
+            ColumnarValue::Scalar(ScalarValue::from("ab-12")),
+            ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                Some(r"(\d+)"),
+                Some(r"(\w+)-"),
+                None,
+            ]))),
+        ],
+        3,
+    )
+    .unwrap()
+    .into_array(3)
+    .unwrap();
+    let expected = StringArray::from(vec![Some("12"), Some("ab"), None]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // a scalar group index applies to every string of the array
+    let result = regexp_extract_with_columnar_values(
+        vec![
+            ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                Some("1-2"),
+                None,
+                Some("3-4"),
+                Some("5"),
+            ]))),
+            ColumnarValue::Scalar(ScalarValue::from(r"(\d)-(\d)")),
+            ColumnarValue::Scalar(ScalarValue::Int64(Some(2))),
+        ],
+        4,
+    )
+    .unwrap()
+    .into_array(4)
+    .unwrap();
+    let expected = StringArray::from(vec![Some("2"), None, Some("4"), Some("")]);
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_scalar_pattern() {
+    let values = StringArray::from(vec![Some("100-200"), Some("foo"), None, Some("1-2")]);
+    let patterns = StringArray::from(vec![r"(\d+)-(\d+)"]);
+
+    let result =
+        regexp_extract(&[Arc::new(values.clone()), Arc::new(patterns.clone())]).unwrap();
+    let expected = StringArray::from(vec![Some("100"), Some(""), None, Some("1")]);
+    assert_eq!(result.as_ref(), &expected);
+
+    let group_indices = Int64Array::from(vec![2, 2, 2, 0]);
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(group_indices),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec![Some("200"), Some(""), None, Some("1-2")]);
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_array_pattern() {
+    let values =
+        LargeStringArray::from(vec![Some("abc"), Some("Köln"), Some("abc"), Some("abc")]);
+    let patterns = LargeStringArray::from(vec![
+        Some("(b)(c)"),
+        Some("[a-zA-Z](ö)[a-zA-Z]{2}"),
+        Some("(x)?abc"),
+        None,
+    ]);
+
+    let result = regexp_extract(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+    let expected = StringArray::from(vec![Some("b"), Some("ö"), Some(""), None]);
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_utf8view() {
+    let values = StringViewArray::from(vec!["key=value", "no match here"]);
+    let patterns = StringViewArray::from(vec![r"(\w+)=(\w+)"]);
+    let group_indices = Int64Array::from(vec![2, 2]);
+
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(group_indices),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec!["value", ""]);
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_scalar_args() {
+    let result = regexp_extract_with_scalar_values(&[
+        ScalarValue::Utf8(Some("100-200".to_string())),
+        ScalarValue::Utf8(Some(r"(\d+)-(\d+)".to_string())),
+        ScalarValue::Int64(Some(2)),
+    ])
+    .unwrap();
+    match result {
+        ColumnarValue::Scalar(ScalarValue::Utf8(v)) => {
+            assert_eq!(v.as_deref(), Some("200"));
+        }
+        other => panic!("Unexpected result {other:?}"),
+    }
+}
+
+#[test]
+fn test_regexp_extract_scalar_null_pattern() {
+    for values in [
+        ScalarValue::Utf8(Some("100-200".to_string())),
+        ScalarValue::LargeUtf8(Some("100-200".to_string())),
+        ScalarValue::Utf8View(Some("100-200".to_string())),
+    ] {
+        let pattern = ScalarValue::try_new_null(&values.data_type()).unwrap();
+        let result = regexp_extract_with_scalar_values(&[
+            values,
+            pattern,
+            ScalarValue::Int64(Some(1)),
+        ])
+        .unwrap();
+        match result {
+            ColumnarValue::Scalar(ScalarValue::Utf8(None)) => {}
+            other => panic!("expected a NULL Utf8 scalar, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_regexp_extract_flags() {
+    let values = StringArray::from(vec!["ABC", "abc", "ABC", "ABC"]);
+    let patterns = StringArray::from(vec!["(b)"]);
+    let group_indices = Int64Array::from(vec![1, 1, 1, 1]);
+    let flags = StringArray::from(vec![Some("i"), Some("i"), Some(""), None]);
+
+    let result = regexp_extract(&[
+        Arc::new(values.clone()),
+        Arc::new(patterns.clone()),
+        Arc::new(group_indices.clone()),
+        Arc::new(flags),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec!["B", "b", "", ""]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // scalar flags are applied to every row
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(group_indices),
+        Arc::new(StringArray::from(vec!["i"])),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec!["B", "b", "B", "B"]);
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_appenders() {
+    let values = StringArray::from(vec![Some("100-200"), Some("abc"), None]);
+    let patterns = StringArray::from(vec![r"(\d+)-(\d+)"]);
+    let group_indices = Int64Array::from(vec![2, 2, 2]);
+
+    let large = concrete_regexp_extract(
+        &values,
+        &patterns,
+        Some(&group_indices),
+        None,
+        None,
+        &DefaultPatternCompiler,
+        DEFAULT_PATTERN_CACHE_SIZE,
+        FAIL_FAST,
+        None,
+        LargeStringBuilder::new(),
+    )
+    .unwrap()
+    .finish();
+    let expected = LargeStringArray::from(vec![Some("200"), Some(""), None]);
+    assert_eq!(large.as_ref(), &expected);
+
+    let view = concrete_regexp_extract(
+        &values,
+        &patterns,
+        Some(&group_indices),
+        None,
+        None,
+        &DefaultPatternCompiler,
+        DEFAULT_PATTERN_CACHE_SIZE,
+        FAIL_FAST,
+        None,
+        StringViewBuilder::new(),
+    )
+    .unwrap()
+    .finish();
+    let expected = StringViewArray::from(vec![Some("200"), Some(""), None]);
+    assert_eq!(view.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_non_capturing_groups() {
+    // `(?:...)` and flag groups such as `(?i:...)` do not capture, so they
+    // are skipped when numbering the capture groups
+    let values = StringArray::from(vec!["abcd", "abcd", "abcd", "xABcd-9"]);
+    let patterns = StringArray::from(vec![
+        "(?:ab)(cd)",
+        "(?:ab)(cd)",
+        "(a)(?:b)(c)",
+        r"(?i:ab)(cd)(?:-)(\d)",
+    ]);
+    let group_indices = Int64Array::from(vec![0, 1, 2, 2]);
+
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(group_indices),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec!["abcd", "cd", "c", "9"]);
+    assert_eq!(result.as_ref(), &expected);
+
+    let err = regexp_extract(&[
+        Arc::new(StringArray::from(vec!["abcd"])),
+        Arc::new(StringArray::from(vec!["(?:ab)(cd)"])),
+        Arc::new(Int64Array::from(vec![2])),
+    ])
+    .expect_err("a non-capturing group is not a capture group");
+    assert_eq!(
+        err.strip_backtrace(),
+        "Execution error: Regex group index 2 is out of range, the pattern '(?:ab)(cd)' has 1 capture groups"
+    );
+}
+
+#[test]
+fn test_regexp_extract_occurrence() {
+    let values = Arc::new(StringArray::from(vec![
+        Some("a=1, b=2, c=3"),
+        Some("a=1, b=2, c=3"),
+        Some("a=1, b=2, c=3"),
+        Some("a=1, b=2, c=3"),
+        Some("a=1"),
+    ])) as ArrayRef;
+    let patterns = Arc::new(StringArray::from(vec![r"(\w)=(\d)"])) as ArrayRef;
+    let group_indices = Arc::new(Int64Array::from(vec![1, 2, 2, 0, 1])) as ArrayRef;
+    // the third row asks for a NULL occurrence, the last one for an
+    // occurrence after the last match
+    let occurrences = Arc::new(Int64Array::from(vec![
+        Some(2),
+        Some(3),
+        None,
+        Some(2),
+        Some(2),
+    ])) as ArrayRef;
+
+    let result = regexp_extract(&[
+        Arc::clone(&values),
+        Arc::clone(&patterns),
+        group_indices,
+        occurrences,
+    ])
+    .unwrap();
+    let expected =
+        StringArray::from(vec![Some("b"), Some("3"), None, Some("b=2"), Some("")]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // the flags follow the occurrence
+    let result = regexp_extract(&[
+        Arc::new(StringArray::from(vec!["A=1, B=2"])),
+        Arc::new(StringArray::from(vec![r"([a-z])=(\d)"])),
+        Arc::new(Int64Array::from(vec![1])),
+        Arc::new(Int64Array::from(vec![2])),
+        Arc::new(StringArray::from(vec!["i"])),
+    ])
+    .unwrap();
+    assert_eq!(result.as_ref(), &StringArray::from(vec!["B"]));
+
+    let err = regexp_extract(&[
+        values,
+        patterns,
+        Arc::new(Int64Array::from(vec![1; 5])),
+        Arc::new(Int64Array::from(vec![0; 5])),
+    ])
+    .expect_err("occurrence 0 should fail");
+    assert_eq!(
+        err.strip_backtrace(),
+        "Execution error: regexp_extract occurrence must be greater than 0, got 0"
+    );
+}
+
+#[test]
+fn test_regexp_extract_options() -> Result<()> {
+    let utf8 = |v: &str| ScalarValue::Utf8(Some(v.to_string()));
+    let case_insensitive = RegexpExtractOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    let multiline_dotall = RegexpExtractOptions {
+        multiline: true,
+        dotall: true,
+        ..Default::default()
+    };
+    let null_options = ScalarValue::try_new_null(&RegexpExtractOptions::data_type())?;
+
+    for (value, pattern, options, expected) in [
+        ("ABC", "(b)", case_insensitive.to_scalar(), "B"),
+        (
+            "ABC",
+            "(b)",
+            RegexpExtractOptions::default().to_scalar(),
+            "",
+        ),
+        ("x\na\nb", "^(a.b)$", multiline_dotall.to_scalar(), "a\nb"),
+        // NULL options are the same as no options
+        ("ABC", "(b)", null_options, ""),
+    ] {
+        let result = regexp_extract_with_scalar_values(&[
+            utf8(value),
+            utf8(pattern),
+            ScalarValue::Int64(Some(1)),
+            options,
+        ])?;
+        match result {
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some(result))) => {
+                assert_eq!(result, expected, "options for {value:?} and {pattern:?}")
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    let err = regexp_extract_with_array_values(&[
+        utf8("ABC"),
+        utf8("(b)"),
+        ScalarValue::Int64(Some(1)),
+        case_insensitive.to_scalar(),
+    ])
+    .expect_err("options column should fail");
+    assert_eq!(
+        err.strip_backtrace(),
+        "Execution error: regexp_extract options must be a constant"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_dotall_flag() {
+    let values = Arc::new(StringArray::from(vec!["xa\nbx", "xa-bx"])) as ArrayRef;
+    let patterns = Arc::new(StringArray::from(vec!["(a.b)"])) as ArrayRef;
+    let group_indices = Arc::new(Int64Array::from(vec![1, 1])) as ArrayRef;
+
+    for (flags, expected) in [
+        ("", vec!["", "a-b"]),
+        ("s", vec!["a\nb", "a-b"]),
+        ("is", vec!["a\nb", "a-b"]),
+    ] {
+        let result = regexp_extract(&[
+            Arc::clone(&values),
+            Arc::clone(&patterns),
+            Arc::clone(&group_indices),
+            Arc::new(StringArray::from(vec![flags])),
+        ])
+        .unwrap();
+        assert_eq!(
+            result.as_ref(),
+            &StringArray::from(expected),
+            "flags '{flags}'"
+        );
+    }
+}
+
+#[test]
+fn test_regexp_extract_swap_greed_flag() {
+    let values = Arc::new(StringArray::from(vec!["xa1b2b3bx", "ab"])) as ArrayRef;
+    let group_indices = Arc::new(Int64Array::from(vec![0, 0])) as ArrayRef;
+
+    // the U flag makes `.*` lazy and `.*?` greedy, for the scalar pattern
+    // and for a pattern array alike
+    for (pattern, flags, expected) in [
+        ("a.*b", "", vec!["a1b2b3b", "ab"]),
+        ("a.*b", "U", vec!["a1b", "ab"]),
+        ("a.*?b", "", vec!["a1b", "ab"]),
+        ("a.*?b", "U", vec!["a1b2b3b", "ab"]),
+    ] {
+        for patterns in [vec![pattern], vec![pattern; 2]] {
+            let result = regexp_extract(&[
+                Arc::clone(&values),
+                Arc::new(StringArray::from(patterns)),
+                Arc::clone(&group_indices),
+                Arc::new(StringArray::from(vec![flags])),
+            ])
+            .unwrap();
+            assert_eq!(
+                result.as_ref(),
+                &StringArray::from(expected.clone()),
+                "pattern '{pattern}' flags '{flags}'"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_regexp_extract_ascii_digits() {
+    // U+0663 is the ARABIC-INDIC DIGIT THREE
+    let values = StringArray::from(vec!["id: \u{0663}\u{0664}", "id: 34"]);
+    let patterns = StringArray::from(vec![r"(\d+)"]);
+    let group_indices = Int64Array::from(vec![1, 1]);
+
+    let result = regexp_extract(&[
+        Arc::new(values.clone()),
+        Arc::new(patterns.clone()),
+        Arc::new(group_indices.clone()),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec!["\u{0663}\u{0664}", "34"]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // disabling Unicode mode restricts \d to ASCII 0-9, both for the whole
+    // pattern and for a single segment
+    for (patterns, flags) in [
+        (patterns, Some("-u")),
+        (StringArray::from(vec![r"((?-u:\d)+)"]), None),
+    ] {
+        let mut args: Vec<ArrayRef> = vec![
+            Arc::new(values.clone()),
+            Arc::new(patterns),
+            Arc::new(group_indices.clone()),
+        ];
+        if let Some(flags) = flags {
+            args.push(Arc::new(StringArray::from(vec![flags])));
+        }
+        let result = regexp_extract(&args).unwrap();
+        let expected = StringArray::from(vec!["", "34"]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
+
+#[test]
+fn test_regexp_extract_dictionary_pattern() {
+    let values = StringArray::from(vec![
+        Some("id=42"),
+        Some("name=bob"),
+        Some("id=7"),
+        None,
+        Some("name=alice"),
+        Some("id=1"),
+    ]);
+    let patterns: DictionaryArray<Int32Type> = vec![
+        Some(r"id=(\d+)"),
+        Some(r"name=(\w+)"),
+        Some(r"id=(\d+)"),
+        Some(r"id=(\d+)"),
+        Some(r"id=(\d+)"),
+        None,
+    ]
+    .into_iter()
+    .collect();
+
+    let result = regexp_extract(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+    let expected = StringArray::from(vec![
+        Some("42"),
+        Some("bob"),
+        Some("7"),
+        None,
+        Some(""),
+        None,
+    ]);
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_match_mask() -> Result<()> {
+    let values = Arc::new(StringArray::from(vec![
+        Some("a=1"),
+        Some("a="),
+        Some("b=2"),
+        None,
+    ])) as ArrayRef;
+    let patterns = Arc::new(StringArray::from(vec![r"a=(\d*)"])) as ArrayRef;
+
+    // an empty capture group and a value without a match both extract
+    // the empty string, only the mask tells them apart
+    let (result, mask) =
+        regexp_extract_with_match_mask(&[Arc::clone(&values), Arc::clone(&patterns)])?;
+    let expected = StringArray::from(vec![Some("1"), Some(""), Some(""), None]);
+    assert_eq!(result.as_ref(), &expected);
+    assert_eq!(
+        mask,
+        BooleanArray::from(vec![Some(true), Some(true), Some(false), None])
+    );
+
+    // the default output is unchanged
+    let func = RegexpExtractFunc::new();
+    let args = || ScalarFunctionArgs {
+        args: vec![
+            ColumnarValue::Array(Arc::clone(&values)),
+            ColumnarValue::Scalar(ScalarValue::from(r"a=(\d*)")),
+        ],
+        arg_fields: vec![
+            Field::new("arg_0", DataType::Utf8, true).into(),
+            Field::new("arg_1", DataType::Utf8, true).into(),
+        ],
+        number_rows: 4,
+        return_field: Field::new("f", DataType::Utf8, true).into(),
+        config_options: Arc::new(ConfigOptions::default()),
+    };
+    let (result, mask) = func.invoke_with_match_mask(args())?;
+    let ColumnarValue::Array(mask) = mask else {
+        panic!("expected an array mask, got {mask:?}");
+    };
+    assert_eq!(
+        mask.as_boolean(),
+        &BooleanArray::from(vec![Some(true), Some(true), Some(false), None])
+    );
+    let result = result.into_array(4)?;
+    assert_eq!(result.as_ref(), &expected);
+    assert_eq!(
+        func.invoke_with_args(args())?.into_array(4)?.as_ref(),
+        &expected
+    );
+
+    // scalar inputs keep both outputs scalar
+    let scalar_args = ScalarFunctionArgs {
+        args: vec![
+            ColumnarValue::Scalar(ScalarValue::from("b=2")),
+            ColumnarValue::Scalar(ScalarValue::from(r"a=(\d*)")),
+        ],
+        ..args()
+    };
+    let (result, mask) = func.invoke_with_match_mask(scalar_args)?;
+    assert!(
+        matches!(result, ColumnarValue::Scalar(ScalarValue::Utf8(Some(ref v))) if v.is_empty())
+    );
+    assert!(matches!(
+        mask,
+        ColumnarValue::Scalar(ScalarValue::Boolean(Some(false)))
+    ));
+    Ok(())
+}
+
+fn decode_runs(result: &ArrayRef) -> (Vec<i32>, Vec<Option<&str>>) {
+    let runs = result.as_run::<Int32Type>();
+    let values = runs.values().as_string::<i32>().iter().collect();
+    (runs.run_ends().values().to_vec(), values)
+}
+
+#[test]
+fn test_regexp_extract_run_end_encoded() -> Result<()> {
+    let run_ends = Int32Array::from(vec![3, 5, 6, 8]);
+    let run_values = StringArray::from(vec![Some("a1"), Some("b2"), None, Some("a3")]);
+    let values = Arc::new(RunArray::try_new(&run_ends, &run_values)?) as ArrayRef;
+    let patterns = Arc::new(StringArray::from(vec![r"a(\d)"])) as ArrayRef;
+
+    // the output keeps the runs of the input
+    let result = regexp_extract(&[Arc::clone(&values), Arc::clone(&patterns)])?;
+    let (ends, extracted) = decode_runs(&result);
+    assert_eq!(ends, vec![3, 5, 6, 8]);
+    assert_eq!(extracted, vec![Some("1"), Some(""), None, Some("3")]);
+
+    // a capture group that is constant within every run still extracts
+    // from each run value once
+    let group_indices = Arc::new(Int64Array::from(vec![
+        Some(1),
+        Some(1),
+        Some(1),
+        Some(0),
+        Some(0),
+        None,
+        Some(0),
+        Some(0),
+    ])) as ArrayRef;
+    let result = regexp_extract(&[
+        Arc::clone(&values),
+        Arc::clone(&patterns),
+        Arc::clone(&group_indices),
+    ])?;
+    let (ends, extracted) = decode_runs(&result);
+    assert_eq!(ends, vec![3, 5, 6, 8]);
+    assert_eq!(extracted, vec![Some("1"), Some(""), None, Some("a3")]);
+
+    // a slice starts and ends within a run
+    let result = regexp_extract(&[
+        values.slice(1, 6),
+        Arc::clone(&patterns),
+        group_indices.slice(1, 6),
+    ])?;
+    let (ends, extracted) = decode_runs(&result);
+    assert_eq!(ends, vec![2, 4, 5, 6]);
+    assert_eq!(extracted, vec![Some("1"), Some(""), None, Some("a3")]);
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_run_end_encoded_varying_within_run() -> Result<()> {
+    let run_ends = Int32Array::from(vec![3, 4]);
+    let run_values = StringArray::from(vec!["a1", "b2"]);
+    let values = Arc::new(RunArray::try_new(&run_ends, &run_values)?) as ArrayRef;
+    let patterns = Arc::new(StringArray::from(vec![r"(\w)(\d)"])) as ArrayRef;
+    // the capture group changes within the first run
+    let group_indices = Arc::new(Int64Array::from(vec![1, 1, 2, 2])) as ArrayRef;
+
+    let result = regexp_extract(&[values, patterns, group_indices])?;
+    let (ends, extracted) = decode_runs(&result);
+    assert_eq!(ends, vec![2, 3, 4]);
+    assert_eq!(extracted, vec![Some("a"), Some("1"), Some("2")]);
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_null_group_index() {
+    let values = StringArray::from(vec!["100-200", "100-200", "100-200", "abc"]);
+    let group_indices = Int64Array::from(vec![Some(2), None, Some(0), None]);
+
+    // scalar pattern
+    let patterns = StringArray::from(vec![r"(\d+)-(\d+)"]);
+    let result = regexp_extract(&[
+        Arc::new(values.clone()),
+        Arc::new(patterns),
+        Arc::new(group_indices.clone()),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec![Some("200"), None, Some("100-200"), None]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // array pattern
+    let patterns = StringArray::from(vec![r"(\d+)-(\d+)"; 4]);
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(group_indices),
+    ])
+    .unwrap();
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_mostly_zero_group_indices() {
+    let regex = Regex::new(r"([a-z]+)(-[0-9]+)?").unwrap();
+    let values = ["ab-12", "cd", "ef-3", "", "gh-45", "ij"]
+        .into_iter()
+        .cycle()
+        .take(60)
+        .collect::<Vec<_>>();
+    // every seventh row asks for a capture group, alternating between a
+    // group that always participates and one that may not
+    let group_indices = (0..values.len())
+        .map(|i| match i % 7 {
+            0 => 1 + (i / 7 % 2) as i64,
+            _ => 0,
+        })
+        .collect::<Vec<_>>();
+
+    let expected = values
+        .iter()
+        .zip(&group_indices)
+        .map(|(value, &group_index)| match group_index {
+            0 => regex.find(value).map(|m| m.as_str()),
+            _ => regex.captures(value).map(|captures| {
+                captures
+                    .get(group_index as usize)
+                    .map_or("", |m| m.as_str())
+            }),
+        })
+        .map(|m| m.or(Some("")))
+        .collect::<StringArray>();
+
+    let result = regexp_extract(&[
+        Arc::new(StringArray::from(values)),
+        Arc::new(StringArray::from(vec![regex.as_str()])),
+        Arc::new(Int64Array::from(group_indices)),
+    ])
+    .unwrap();
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_max_compile_errors() {
+    let invoke = |max_compile_errors| {
+        let mut config_options = ConfigOptions::default();
+        config_options.extensions.insert(RegexpExtractConfig {
+            max_compile_errors,
+            ..Default::default()
+        });
+        RegexpExtractFunc::new().invoke_with_args(ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                    "a1", "b2", "c3", "d4", "e5",
+                ]))),
+                // a rules table with three distinct invalid patterns, one
+                // of them twice
+                ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                    r"a(\d", r"b(\d)", r"[z-a]", r"a(\d", r"e\",
+                ]))),
+            ],
+            arg_fields: vec![
+                Field::new("arg_0", DataType::Utf8, true).into(),
+                Field::new("arg_1", DataType::Utf8, true).into(),
+            ],
+            number_rows: 5,
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(config_options),
+        })
+    };
+    let errors = |err: DataFusionError| {
+        let message = err.strip_backtrace();
+        let patterns = ["a(\\d", "[z-a]", "e\\"]
+            .into_iter()
+            .filter(|pattern| {
+                message.contains(&format!("unable to compile pattern '{pattern}'"))
+            })
+            .collect::<Vec<_>>();
+        (message, patterns)
+    };
+
+    // by default the first invalid pattern fails the batch
+    let (message, patterns) = errors(invoke(1).unwrap_err());
+    assert!(
+        message.starts_with("Execution error: unable to compile pattern 'a(\\d'"),
+        "unexpected error: {message}"
+    );
+    assert_eq!(patterns, ["a(\\d"]);
+
+    // all distinct errors are reported together at the end of the batch
+    let (message, patterns) = errors(invoke(10).unwrap_err());
+    assert!(
+        message.starts_with(
+            "Execution error: regexp_extract failed to compile 3 patterns:\n"
+        ),
+        "unexpected error: {message}"
+    );
+    assert_eq!(patterns, ["a(\\d", "[z-a]", "e\\"]);
+
+    // or as many as the limit allows
+    let (message, patterns) = errors(invoke(2).unwrap_err());
+    assert!(
+        message.starts_with(
+            "Execution error: regexp_extract failed to compile 2 patterns (stopped at the limit"
+        ),
+        "unexpected error: {message}"
+    );
+    assert_eq!(patterns, ["a(\\d", "[z-a]"]);
+}
+
+#[test]
+fn test_regexp_extract_config() {
+    // without the extension the functions use the defaults
+    let mut config = ConfigOptions::default();
+    assert_eq!(
+        RegexpExtractConfig::from_config(&config),
+        RegexpExtractConfig::default()
+    );
+
+    config.extensions.insert(RegexpExtractConfig::default());
+    config
+        .set("regexp_extract.null_group_index_is_error", "true")
+        .unwrap();
+    assert!(RegexpExtractConfig::from_config(&config).null_group_index_is_error);
+    let entry = config
+        .entries()
+        .into_iter()
+        .find(|entry| entry.key == "regexp_extract.null_group_index_is_error")
+        .expect("the options are listed with their prefix");
+    assert_eq!(entry.value.as_deref(), Some("true"));
+
+    let err = config.set("regexp_extract.nope", "1").unwrap_err();
+    assert_eq!(
+        err.strip_backtrace(),
+        "Invalid or Unsupported Configuration: Config value \"nope\" not found on RegexpExtractConfig"
+    );
+}
+
+#[test]
+fn test_regexp_extract_null_group_index_is_error() {
+    let invoke = |null_group_index_is_error| {
+        let mut config_options = ConfigOptions::default();
+        config_options.extensions.insert(RegexpExtractConfig {
+            null_group_index_is_error,
+            ..Default::default()
+        });
+        RegexpExtractFunc::new().invoke_with_args(ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                    "100-200", "100-200",
+                ]))),
+                ColumnarValue::Scalar(ScalarValue::from(r"(\d+)-(\d+)")),
+                ColumnarValue::Array(Arc::new(Int64Array::from(vec![Some(2), None]))),
+            ],
+            arg_fields: vec![
+                Field::new("arg_0", DataType::Utf8, true).into(),
+                Field::new("arg_1", DataType::Utf8, true).into(),
+                Field::new("arg_2", DataType::Int64, true).into(),
+            ],
+            number_rows: 2,
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(config_options),
+        })
+    };
+
+    // by default a NULL group index yields NULL
+    let ColumnarValue::Array(result) = invoke(false).unwrap() else {
+        panic!("expected an array result");
+    };
+    let expected = StringArray::from(vec![Some("200"), None]);
+    assert_eq!(result.as_ref(), &expected);
+
+    let err = invoke(true).expect_err("a NULL group index should fail");
+    assert!(
+        err.strip_backtrace()
+            .starts_with("Execution error: regexp_extract group index must not be NULL"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_pattern_complexity() {
+    assert_eq!(pattern_complexity(r"\d{3}-\d{2}", None), Some(0));
+    assert_eq!(pattern_complexity(r"(\d+)-(\d+)", None), Some(2));
+    assert_eq!(pattern_complexity("(a+)+", None), Some(3));
+    assert_eq!(pattern_complexity("((a+)+)+", None), Some(6));
+    assert_eq!(pattern_complexity("(a{2,})*", Some("i")), Some(3));
+    assert_eq!(pattern_complexity("(", None), None);
+}
+
+#[test]
+fn test_regexp_extract_max_pattern_complexity() {
+    let invoke = |pattern: &str, max_complexity| {
+        let mut config_options = ConfigOptions::default();
+        config_options.extensions.insert(RegexpExtractConfig {
+            max_pattern_complexity: max_complexity,
+            ..Default::default()
+        });
+        RegexpExtractFunc::new().invoke_with_args(ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(vec!["aaa"]))),
+                ColumnarValue::Scalar(ScalarValue::from(pattern)),
+                ColumnarValue::Scalar(ScalarValue::Int64(Some(1))),
+            ],
+            arg_fields: vec![
+                Field::new("arg_0", DataType::Utf8, true).into(),
+                Field::new("arg_1", DataType::Utf8, true).into(),
+                Field::new("arg_2", DataType::Int64, true).into(),
+            ],
+            number_rows: 1,
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(config_options),
+        })
+    };
+    let expected = StringArray::from(vec!["aaa"]);
+
+    // without a limit any pattern compiles
+    let ColumnarValue::Array(result) = invoke("((a+)+)+", None).unwrap() else {
+        panic!("expected an array result");
+    };
+    assert_eq!(result.as_ref(), &expected);
+
+    let ColumnarValue::Array(result) = invoke("(a+)", Some(4)).unwrap() else {
+        panic!("expected an array result");
+    };
+    assert_eq!(result.as_ref(), &expected);
+
+    let err = invoke("((a+)+)+", Some(4)).expect_err("the pattern is too complex");
+    assert_eq!(
+        err.strip_backtrace(),
+        "Execution error: regexp_extract pattern '((a+)+)+' has a complexity of 6, above the limit of 4 set by regexp_extract.max_pattern_complexity"
+    );
+
+    // invalid patterns still report the compile error
+    let err = invoke("(", Some(4)).expect_err("the pattern is invalid");
+    assert!(
+        err.strip_backtrace()
+            .starts_with("Execution error: unable to compile pattern '('"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_posix_pattern() {
+    assert_eq!(
+        posix_pattern(r"(\d+)-(\w+)", None),
+        "([[:digit:]]+)-([[:word:]]+)"
+    );
+    assert_eq!(
+        posix_pattern(r"[\d.]\S[^\W_]", None),
+        "[[:digit:].][[:^space:]][^[:^word:]_]"
+    );
+    assert_eq!(posix_pattern(r"\bx\B", None), r"(?-u:\b)x(?-u:\B)");
+    // escaped backslashes, other escapes and POSIX classes are kept
+    assert_eq!(posix_pattern(r"\\d\p{L}[\\w]", None), r"\\d\p{L}[\\w]");
+    assert_eq!(posix_pattern("[[:digit:]]+", None), "[[:digit:]]+");
+    // the comments of the x flag are not rewritten, nor parsed as a pattern
+    assert_eq!(
+        posix_pattern("\\d+ # one \\d or more (\n", Some("x")),
+        "[[:digit:]]+"
+    );
+    // a pattern that does not parse is left to the compiler to report
+    assert_eq!(posix_pattern(r"(\d", None), r"(\d");
+}
+
+#[test]
+fn test_regexp_extract_syntax() {
+    let invoke = |pattern: &str, syntax| {
+        let mut config_options = ConfigOptions::default();
+        config_options.extensions.insert(RegexpExtractConfig {
+            syntax,
+            ..Default::default()
+        });
+        let result = RegexpExtractFunc::new()
+            .invoke_with_args(ScalarFunctionArgs {
+                args: vec![
+                    ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                        "no ٣٤ 12",
+                        "x\u{a0}y z",
+                    ]))),
+                    ColumnarValue::Scalar(ScalarValue::from(pattern)),
+                    ColumnarValue::Scalar(ScalarValue::Int64(Some(0))),
+                ],
+                arg_fields: vec![
+                    Field::new("arg_0", DataType::Utf8, true).into(),
+                    Field::new("arg_1", DataType::Utf8, true).into(),
+                    Field::new("arg_2", DataType::Int64, true).into(),
+                ],
+                number_rows: 2,
+                return_field: Field::new("f", DataType::Utf8, true).into(),
+                config_options: Arc::new(config_options),
+            })
+            .unwrap();
+        let ColumnarValue::Array(result) = result else {
+            panic!("expected an array result");
+        };
+        result.as_string::<i32>().clone()
+    };
+
+    // POSIX classes only match ASCII characters in either syntax
+    for syntax in [RegexSyntax::Rust, RegexSyntax::Posix] {
+        assert_eq!(
+            invoke("[[:digit:]]+", syntax),
+            StringArray::from(vec!["12", ""])
+        );
+    }
+    // `\d` and `\s` match Unicode digits and spaces only in the Rust syntax
+    assert_eq!(
+        invoke(r"\d+", RegexSyntax::Rust),
+        StringArray::from(vec!["٣٤", ""])
+    );
+    assert_eq!(
+        invoke(r"\d+", RegexSyntax::Posix),
+        StringArray::from(vec!["12", ""])
+    );
+    assert_eq!(
+        invoke(r"\w\s\w", RegexSyntax::Rust),
+        StringArray::from(vec!["o ٣", "x\u{a0}y"])
+    );
+    assert_eq!(
+        invoke(r"\w\s\w", RegexSyntax::Posix),
+        StringArray::from(vec!["", "y z"])
+    );
+    // word boundaries only consider ASCII word characters in the POSIX syntax
+    assert_eq!(
+        invoke(r"\b\d+\b", RegexSyntax::Posix),
+        StringArray::from(vec!["12", ""])
+    );
+}
+
+#[test]
+fn test_regexp_extract_max_capture_length() {
+    let invoke = |max_capture_length| {
+        let mut config_options = ConfigOptions::default();
+        config_options.extensions.insert(RegexpExtractConfig {
+            max_capture_length,
+            ..Default::default()
+        });
+        let func = RegexpExtractFunc::new();
+        func.invoke_with_args(ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                    Some("id=abcdef;"),
+                    Some("id=ab;"),
+                    Some("id=äöüß€x;"),
+                    Some("id=;"),
+                    Some("none"),
+                    None,
+                ]))),
+                ColumnarValue::Scalar(ScalarValue::from(r"id=([^;]*);")),
+            ],
+            arg_fields: vec![
+                Field::new("arg_0", DataType::Utf8, true).into(),
+                Field::new("arg_1", DataType::Utf8, true).into(),
+            ],
+            number_rows: 6,
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(config_options),
+        })
+        .map(|result| match result {
+            ColumnarValue::Array(result) => result,
+            ColumnarValue::Scalar(_) => panic!("expected an array result"),
+        })
+    };
+
+    // the captures are cut after 3 characters, not bytes, so the 2 byte
+    // characters are kept whole
+    let truncated = StringArray::from(vec![
+        Some("abc"),
+        Some("ab"),
+        Some("äöü"),
+        Some(""),
+        Some(""),
+        None,
+    ]);
+    assert_eq!(invoke(Some(3)).unwrap().as_ref(), &truncated);
+
+    // the 3 byte `€` is the last character within a limit of 5
+    let result = invoke(Some(5)).unwrap();
+    assert_eq!(result.as_string::<i32>().value(2), "äöüß€");
+
+    let result = invoke(None).unwrap();
+    assert_eq!(result.as_string::<i32>().value(0), "abcdef");
+    assert_eq!(result.as_string::<i32>().value(2), "äöüß€x");
+    let result = invoke(Some(0)).unwrap();
+    assert_eq!(result.as_string::<i32>().value(0), "");
+}
+
+#[test]
+fn test_collapse_whitespace() {
+    let mut collapsed = String::new();
+    for (value, expected) in [
+        ("  John \t Smith\n", "John Smith"),
+        ("a\n\nb\r\nc", "a b c"),
+        ("tab\there", "tab here"),
+        ("already clean", "already clean"),
+        ("\u{a0}non\u{2003}breaking\u{a0}", "non breaking"),
+        (" \t\n ", ""),
+        ("", ""),
+    ] {
+        collapse_whitespace(value, &mut collapsed);
+        assert_eq!(collapsed, expected, "collapsing {value:?}");
+    }
+}
+
+#[test]
+fn test_regexp_extract_collapse_whitespace() {
+    let invoke = |collapse_whitespace| {
+        let mut config_options = ConfigOptions::default();
+        config_options.extensions.insert(RegexpExtractConfig {
+            collapse_whitespace,
+            max_capture_length: Some(8),
+            ..Default::default()
+        });
+        let values = StringArray::from(vec![
+            Some("name=  Ada \t  Lovelace ;"),
+            Some("name=\n\tGrace\n\nHopper;"),
+            Some("no name"),
+            None,
+        ]);
+        let args = vec![
+            ColumnarValue::Array(Arc::new(values)),
+            ColumnarValue::Scalar(ScalarValue::from("name=([^;]*);")),
+        ];
+        let arg_fields = args
+            .iter()
+            .enumerate()
+            .map(|(idx, a)| Field::new(format!("arg_{idx}"), a.data_type(), true).into())
+            .collect();
+        RegexpExtractFunc::new()
+            .invoke_with_args(ScalarFunctionArgs {
+                args,
+                arg_fields,
+                number_rows: 4,
+                return_field: Field::new("f", DataType::Utf8, true).into(),
+                config_options: Arc::new(config_options),
+            })
+            .and_then(|result| result.into_array(4))
+            .unwrap()
+    };
+
+    // the captures are collapsed before they are truncated
+    let expected =
+        StringArray::from(vec![Some("Ada Love"), Some("Grace Ho"), Some(""), None]);
+    assert_eq!(invoke(true).as_ref(), &expected);
+    let expected =
+        StringArray::from(vec![Some("  Ada \t "), Some("\n\tGrace\n"), Some(""), None]);
+    assert_eq!(invoke(false).as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_truncate_graphemes() {
+    let invoke = |truncate_graphemes| {
+        let mut config_options = ConfigOptions::default();
+        config_options.extensions.insert(RegexpExtractConfig {
+            max_capture_length: Some(2),
+            truncate_graphemes,
+            ..Default::default()
+        });
+        let func = RegexpExtractFunc::new();
+        let result = func
+            .invoke_with_args(ScalarFunctionArgs {
+                args: vec![
+                    ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                        // `e` followed by a combining acute accent
+                        "id=ne\u{301}e;",
+                        "id=e\u{301}\u{323}x;",
+                        "id=abc;",
+                    ]))),
+                    ColumnarValue::Scalar(ScalarValue::from(r"id=([^;]*);")),
+                ],
+                arg_fields: vec![
+                    Field::new("arg_0", DataType::Utf8, true).into(),
+                    Field::new("arg_1", DataType::Utf8, true).into(),
+                ],
+                number_rows: 3,
+                return_field: Field::new("f", DataType::Utf8, true).into(),
+                config_options: Arc::new(config_options),
+            })
+            .unwrap();
+        let ColumnarValue::Array(result) = result else {
+            panic!("expected an array result");
+        };
+        result
+    };
+
+    // by default the limit counts characters, which separates the
+    // accents from the `e` they belong to
+    let expected = StringArray::from(vec!["ne", "e\u{301}", "ab"]);
+    assert_eq!(invoke(false).as_ref(), &expected);
+
+    // counting grapheme clusters keeps each `e` with all its marks
+    let expected = StringArray::from(vec!["ne\u{301}", "e\u{301}\u{323}x", "ab"]);
+    assert_eq!(invoke(true).as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_invalid_utf8() {
+    // corrupt data that bypassed Arrow's validation, with an invalid byte
+    // in the second row
+    let corrupt = || -> ArrayRef {
+        let offsets = OffsetBuffer::new(vec![0, 4, 9, 13].into());
+        let values = Buffer::from(b"id=1id=\xff2id=3".to_vec());
+        Arc::new(unsafe { StringArray::new_unchecked(offsets, values, None) })
+    };
+    let invoke = |values: ArrayRef, handling| {
+        let mut config_options = ConfigOptions::default();
+        config_options.extensions.insert(RegexpExtractConfig {
+            invalid_utf8: handling,
+            ..Default::default()
+        });
+        RegexpExtractFunc::new()
+            .invoke_with_args(ScalarFunctionArgs {
+                args: vec![
+                    ColumnarValue::Array(values),
+                    ColumnarValue::Scalar(ScalarValue::from("id=(.+)")),
+                ],
+                arg_fields: vec![
+                    Field::new("arg_0", DataType::Utf8, true).into(),
+                    Field::new("arg_1", DataType::Utf8, true).into(),
+                ],
+                number_rows: 3,
+                return_field: Field::new("f", DataType::Utf8, true).into(),
+                config_options: Arc::new(config_options),
+            })
+            .map(|result| match result {
+                ColumnarValue::Array(result) => result,
+                ColumnarValue::Scalar(_) => panic!("expected an array result"),
+            })
+    };
+
+    let err = invoke(corrupt(), InvalidUtf8Handling::Error)
+        .expect_err("invalid UTF-8 should fail");
+    assert!(
+        err.strip_backtrace().starts_with(
+            "Execution error: regexp_extract input row 1 is not valid UTF-8"
+        ),
+        "unexpected error: {err}"
+    );
+
+    // the invalid byte is replaced, so the capture is valid UTF-8
+    let result = invoke(corrupt(), InvalidUtf8Handling::Lossy).unwrap();
+    let expected = StringArray::from(vec!["1", "\u{FFFD}2", "3"]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // valid values are passed through by both checking modes
+    let valid = Arc::new(StringArray::from(vec!["id=1", "id=ü"])) as ArrayRef;
+    for handling in [InvalidUtf8Handling::Error, InvalidUtf8Handling::Lossy] {
+        let result = invoke(Arc::clone(&valid), handling).unwrap();
+        assert_eq!(result.as_ref(), &StringArray::from(vec!["1", "ü"]));
+    }
+}
+
+/// (value, pattern, group index, expected)
+type ParityCase = (
+    Option<&'static str>,
+    Option<&'static str>,
+    Option<i64>,
+    Option<&'static str>,
+);
+
+const PARITY_CASES: [ParityCase; 9] = [
+    (Some("100-200"), Some(r"(\d+)-(\d+)"), Some(2), Some("200")),
+    (
+        Some("100-200"),
+        Some(r"(\d+)-(\d+)"),
+        Some(0),
+        Some("100-200"),
+    ),
+    (
+        Some("Köln"),
+        Some("[a-zA-Z](ö)[a-zA-Z]{2}"),
+        Some(1),
+        Some("ö"),
+    ),
+    (Some("Москва"), Some(r"(\p{L}{2})$"), Some(1), Some("ва")),
+    (Some("abc"), Some("(x)?abc"), Some(1), Some("")),
+    (Some("abc"), Some("(d)"), Some(1), Some("")),
+    (None, Some("(a)"), Some(1), None),
+    (Some("abc"), None, Some(1), None),
+    (Some("abc"), Some("(a)"), None, None),
+];
+
+#[test]
+fn test_regexp_extract_string_type_parity() {
+    test_regexp_extract_parity_array::<GenericStringArray<i32>>();
+    test_regexp_extract_parity_array::<GenericStringArray<i64>>();
+    test_regexp_extract_parity_array::<StringViewArray>();
+
+    test_regexp_extract_parity_scalar(|v| ScalarValue::Utf8(v.map(str::to_string)));
+    test_regexp_extract_parity_scalar(|v| ScalarValue::LargeUtf8(v.map(str::to_string)));
+    test_regexp_extract_parity_scalar(|v| ScalarValue::Utf8View(v.map(str::to_string)));
+}
+
+fn test_regexp_extract_parity_array<A>()
+where
+    A: From<Vec<Option<&'static str>>> + Array + 'static,
+{
+    let values = A::from(PARITY_CASES.iter().map(|c| c.0).collect());
+    let patterns = A::from(PARITY_CASES.iter().map(|c| c.1).collect());
+    let group_indices = Int64Array::from_iter(PARITY_CASES.iter().map(|c| c.2));
+    let expected = StringArray::from_iter(PARITY_CASES.iter().map(|c| c.3));
+
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(group_indices),
+    ])
+    .unwrap();
+    assert_eq!(result.as_ref(), &expected, "{}", result.data_type());
+}
+
+fn test_regexp_extract_parity_scalar(to_scalar: impl Fn(Option<&str>) -> ScalarValue) {
+    for (value, pattern, group_index, expected) in PARITY_CASES {
+        let result = regexp_extract_with_scalar_values(&[
+            to_scalar(value),
+            to_scalar(pattern),
+            ScalarValue::Int64(group_index),
+        ])
+        .unwrap();
+        match result {
+            ColumnarValue::Scalar(ScalarValue::Utf8(v)) => {
+                assert_eq!(v.as_deref(), expected, "{value:?} {pattern:?}");
+            }
+            other => panic!("Unexpected result {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_regexp_extract_group_zero() {
+    // group 0 is always the entire match, whatever the shape of the pattern
+    let cases = [
+        // alternation, the first alternative that matches wins
+        ("foobar", "bar|foo", "foo"),
+        ("foobar", "(x)|(b)ar", "bar"),
+        ("foobar", "o+|b+", "oo"),
+        // anchored patterns
+        ("foobar", "^fo+", "foo"),
+        ("foobar", "b.r$", "bar"),
+        ("foobar", "^foobar$", "foobar"),
+        ("foobar", "^bar", ""),
+        // non-capturing groups and groups that did not participate
+        ("foobar", "(?:fo)+o(x)?", "foo"),
+        ("foobar", "(?<first>f)(o)(?:x|o)(b)?", "foob"),
+        // empty matches
+        ("foobar", "x*", ""),
+        ("", "^$", ""),
+        // the match spans multibyte characters
+        ("Köln Düsseldorf", r"\w+ö\w+|D\w+", "Köln"),
+    ];
+
+    for (value, pattern, expected) in cases {
+        let result = regexp_extract(&[
+            Arc::new(StringArray::from(vec![value])),
+            Arc::new(StringArray::from(vec![pattern])),
+            Arc::new(Int64Array::from(vec![0])),
+        ])
+        .unwrap();
+        assert_eq!(
+            result.as_string::<i32>().value(0),
+            expected,
+            "group 0 of '{pattern}' in '{value}'"
+        );
+    }
+}
+
+#[test]
+fn test_regexp_extract_invalid_pattern() {
+    for (pattern, detail) in [
+        ("(abc", "unclosed group"),
+        ("[a-", "unclosed character class"),
+        (r"\p{Foo}", "Unicode property not found"),
+    ] {
+        let err = regexp_extract(&[
+            Arc::new(StringArray::from(vec!["abc"])),
+            Arc::new(StringArray::from(vec![pattern])),
+        ])
+        .expect_err("invalid pattern should fail");
+        let message = err.strip_backtrace();
+        assert!(
+            message.starts_with(&format!(
+                "Execution error: unable to compile pattern '{pattern}': regex parse error"
+            )),
+            "unexpected error: {message}"
+        );
+        assert!(message.contains(detail), "unexpected error: {message}");
+    }
+}
+
+#[test]
+#[cfg(not(feature = "fancy_regex"))]
+fn test_regexp_extract_backreference_unsupported() {
+    // the regex crate guarantees linear time matching, which rules out
+    // backreferences such as repeated word detection
+    let err = regexp_extract(&[
+        Arc::new(StringArray::from(vec!["hello hello"])),
+        Arc::new(StringArray::from(vec![r"(\w+)\s+\1"])),
+    ])
+    .expect_err("backreferences should be rejected");
+    assert!(
+        err.strip_backtrace()
+            .contains("backreferences are not supported"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+#[cfg(feature = "fancy_regex")]
+fn test_regexp_extract_fancy_backreference() {
+    let values = StringArray::from(vec![
+        Some("it is is here"),
+        Some("no repeats"),
+        None,
+        Some("the the end"),
+    ]);
+    let expected = StringArray::from(vec![Some("is"), Some(""), None, Some("the")]);
+
+    // a scalar pattern
+    let result = regexp_extract(&[
+        Arc::new(values.clone()),
+        Arc::new(StringArray::from(vec![r"\b(\w+)\s+\1\b"])),
+    ])
+    .unwrap();
+    assert_eq!(result.as_ref(), &expected);
+
+    // a pattern per row, mixed with patterns the regex crate supports
+    let patterns = StringArray::from(vec![
+        r"\b(\w+)\s+\1\b",
+        r"(\w+) (\w+)",
+        r"\b(\w+)\s+\1\b",
+        r"(\w+)(?= end)",
+    ]);
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(Int64Array::from(vec![1, 2, 1, 1])),
+    ])
+    .unwrap();
+    let expected =
+        StringArray::from(vec![Some("is"), Some("repeats"), None, Some("the")]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // group indices are validated like with the regex crate
+    let err = regexp_extract(&[
+        Arc::new(StringArray::from(vec!["a a"])),
+        Arc::new(StringArray::from(vec![r"(\w+)\s+\1"])),
+        Arc::new(Int64Array::from(vec![2])),
+    ])
+    .unwrap_err();
+    assert!(
+        err.strip_backtrace()
+            .contains("group index 2 is out of range"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+#[cfg(feature = "fancy_regex")]
+fn test_compile_fancy_pattern() {
+    // only patterns the regex crate rejects for needing backtracking
+    // fall back, so the others keep their matching semantics
+    assert!(compile_fancy_pattern(r"(\w+)-(\d+)", None).is_none());
+    assert!(compile_fancy_pattern("(abc", None).is_none());
+    assert!(compile_fancy_pattern(r"(\w+)\s+\1", Some("g")).is_none());
+    let regex = compile_fancy_pattern(r"(\w+)\s+\1", Some("i")).unwrap();
+    assert_eq!(
+        find_fancy_occurrence_group(&regex, "Go go now", 1, 1).unwrap(),
+        Some("Go")
+    );
+}
+
+#[test]
+#[cfg(feature = "fancy_regex")]
+fn test_fancy_pattern_complexity() {
+    assert_eq!(fancy_pattern_complexity(r"(\w+)\s+\1", None), Some(2));
+    assert_eq!(fancy_pattern_complexity(r"((a+)+)\1", None), Some(3));
+    assert_eq!(fancy_pattern_complexity(r"(?=a{2,3})a", None), Some(0));
+    assert_eq!(fancy_pattern_complexity("(", None), None);
+}
+
+#[test]
+#[cfg(feature = "fancy_regex")]
+fn test_regexp_extract_fancy_pattern_compiler() {
+    let invoke = |func: &RegexpExtractFunc, patterns: &ColumnarValue, config: &Arc<_>| {
+        func.invoke_with_args(ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(vec!["٣٣ 11", "aa ٣٣"]))),
+                patterns.clone(),
+                ColumnarValue::Scalar(ScalarValue::Int64(Some(0))),
+            ],
+            arg_fields: vec![
+                Field::new("arg_0", DataType::Utf8, true).into(),
+                Field::new("arg_1", DataType::Utf8, true).into(),
+                Field::new("arg_2", DataType::Int64, true).into(),
+            ],
+            number_rows: 2,
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::clone(config),
+        })
+        .map(|result| result.to_array(2).unwrap())
+    };
+    let func = RegexpExtractFunc::new();
+    let pattern = r"(\d+)\1";
+    let per_row = ColumnarValue::Array(Arc::new(StringArray::from(vec![pattern; 2])));
+    let scalar = ColumnarValue::Scalar(ScalarValue::from(pattern));
+
+    // the pattern is compiled with fancy-regex once for the session, not for
+    // every row
+    let mut config = ConfigOptions::default();
+    config.extensions.insert(SharedRegexes::default());
+    let config = Arc::new(config);
+    let expected = StringArray::from(vec!["٣٣", "٣٣"]);
+    for patterns in [&per_row, &scalar] {
+        let result = invoke(&func, patterns, &config).unwrap();
+        assert_eq!(result.as_ref(), &expected);
+    }
+    let shared = SharedRegexes::from_config(&config).unwrap();
+    assert_eq!(shared.compilations(), 1);
+
+    // the POSIX syntax cannot rewrite the classes of a pattern that needs
+    // backtracking, which fails instead of matching Unicode digits
+    let mut posix = ConfigOptions::default();
+    posix.extensions.insert(RegexpExtractConfig {
+        syntax: RegexSyntax::Posix,
+        ..Default::default()
+    });
+    let posix = Arc::new(posix);
+    for patterns in [&per_row, &scalar] {
+        let err = invoke(&func, patterns, &posix).expect_err("the pattern uses \\d");
+        assert!(
+            err.strip_backtrace()
+                .contains("which the posix syntax does not support with \\d"),
+            "unexpected error: {err}"
+        );
+    }
+    let ascii = ColumnarValue::Scalar(ScalarValue::from(r"([[:digit:]]+)\1"));
+    let result = invoke(&func, &ascii, &posix).unwrap();
+    assert_eq!(result.as_ref(), &StringArray::from(vec!["11", ""]));
+
+    // the complexity limit rejects the pattern
+    let mut limited = ConfigOptions::default();
+    limited.extensions.insert(RegexpExtractConfig {
+        max_pattern_complexity: Some(0),
+        ..Default::default()
+    });
+    let limited = Arc::new(limited);
+    for patterns in [&per_row, &scalar] {
+        let err =
+            invoke(&func, patterns, &limited).expect_err("the pattern is too complex");
+        assert!(
+            err.strip_backtrace()
+                .contains("has a complexity of 1, above the limit of 0"),
+            "unexpected error: {err}"
+        );
+    }
+
+    // a custom compiler only falls back to fancy-regex if it implements it
+    let func =
+        RegexpExtractFunc::new().with_pattern_compiler(Arc::new(OctalPatternCompiler));
+    let look_ahead = ColumnarValue::Scalar(ScalarValue::from(r"\d+(?= )"));
+    let err = invoke(&func, &look_ahead, &config).expect_err("look-arounds are rejected");
+    assert!(
+        err.strip_backtrace().contains(
+            "look-around, including look-ahead and look-behind, is not supported"
+        ),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_regexp_extract_leftmost_first_alternation() {
+    let values = StringArray::from(vec!["ab", "ab", "abc", "abc"]);
+    // the first alternative that matches wins, even if a later one would
+    // match a longer string as POSIX requires
+    let patterns =
+        StringArray::from(vec!["(a|ab)", "(ab|a)", "(a|ab)(c)?", "(ab|a)(c)?"]);
+    let group_indices = Int64Array::from(vec![1, 1, 0, 0]);
+
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(group_indices),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec!["a", "ab", "a", "abc"]);
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_anchored_literal_prefix() {
+    let prefix = |pattern, flags| anchored_literal_prefix(pattern, flags);
+    assert_eq!(prefix(r"^GET (\S+)", None).as_deref(), Some("GET "));
+    assert_eq!(prefix(r"^(ab)(c)d?", None).as_deref(), Some("abc"));
+    assert_eq!(prefix(r"\Aké(\d)", None).as_deref(), Some("ké"));
+    assert_eq!(prefix(r"^ab", Some("")).as_deref(), Some("ab"));
+    // unanchored patterns, patterns without a literal prefix, and flags
+    // that make the prefix inexact have no prefix
+    assert_eq!(prefix(r"GET (\S+)", None), None);
+    assert_eq!(prefix(r"^a|^b", None), None);
+    assert_eq!(prefix(r"^(a|b)c", None), None);
+    assert_eq!(prefix(r"^a*b", None), None);
+    assert_eq!(prefix(r"^ab", Some("i")), None);
+    assert_eq!(prefix(r"^ab", Some("m")), None);
+    assert_eq!(prefix(r"^(ab", None), None);
+}
+
+#[test]
+fn test_regexp_extract_anchored_prefix() {
+    let values = StringArray::from(vec![
+        Some("GET /index"),
+        Some("POST /form"),
+        Some("GET"),
+        Some("xGET /a"),
+        None,
+    ]);
+    let patterns = StringArray::from(vec![r"^GET (\S+)"]);
+    let group_indices = Int64Array::from(vec![1; 5]);
+
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(group_indices),
+    ])
+    .unwrap();
+    let expected =
+        StringArray::from(vec![Some("/index"), Some(""), Some(""), Some(""), None]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // the values without the prefix still validate the group index
+    let err = regexp_extract(&[
+        Arc::new(StringArray::from(vec!["POST /form"])),
+        Arc::new(StringArray::from(vec![r"^GET (\S+)"])),
+        Arc::new(Int64Array::from(vec![2])),
+    ])
+    .expect_err("out of range group index should fail");
+    assert!(
+        err.strip_backtrace()
+            .starts_with("Execution error: Regex group index 2 is out of range"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_repeated_class_ranges() {
+    let is_class = |pattern, flags| repeated_class_ranges(pattern, flags).is_some();
+    assert_eq!(repeated_class_ranges("[0-9]+", None), Some(vec!['0'..='9']));
+    assert!(is_class(r"\w+", None));
+    assert!(is_class(r"(\d+)", None));
+    assert!(is_class("[a-z]+", Some("i")));
+    // anything besides a single greedy `+` of a Unicode class goes through
+    // the regex engine
+    assert!(!is_class("[0-9]*", None));
+    assert!(!is_class("[0-9]+?", None));
+    assert!(!is_class("[0-9]{2,}", None));
+    assert!(!is_class("[0-9]+x", None));
+    assert!(!is_class(r"((\d+))", None));
+    assert!(!is_class("a+", None));
+    assert!(!is_class(r"\w+", Some("-u")));
+    assert!(!is_class("[0-9+", None));
+}
+
+#[test]
+fn test_class_tokenizer_matches_regex() {
+    let alphabet = ['1', '٣', 'a', 'É', '_', ' ', '-', '🦀'];
+    // every value of up to 5 characters of the alphabet
+    let mut values = vec![String::new()];
+    let mut last = values.clone();
+    for _ in 0..5 {
+        last = last
+            .iter()
+            .flat_map(|value| alphabet.iter().map(move |c| format!("{value}{c}")))
+            .collect();
+        values.extend(last.iter().cloned());
+    }
+
+    for (pattern, flags) in [
+        ("[0-9]+", None),
+        (r"\d+", None),
+        (r"\w+", None),
+        (r"(\S+)", None),
+        ("[a-zé]+", Some("i")),
+        (r"[^\s\d]+", None),
+    ] {
+        let regex = compile_pattern(pattern, flags).unwrap();
+        let tokenizer =
+            ClassTokenizer::new(repeated_class_ranges(pattern, flags).unwrap());
+        for value in &values {
+            for occurrence in 1..=3 {
+                assert_eq!(
+                    tokenizer.find(value, occurrence),
+                    regex
+                        .find_iter(value)
+                        .nth(occurrence - 1)
+                        .map(|m| m.as_str()),
+                    "pattern {pattern}, value {value:?}, occurrence {occurrence}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_compile_ascii_bytes_pattern() {
+    for (pattern, flags, is_ascii) in [
+        ("[a-z]+=([0-9]+)", None, true),
+        ("^GET /([a-z/]*)", None, true),
+        ("^id=[0-9]+$", Some("m"), true),
+        (r"(?-u:\w+)(?-u:\b)", None, true),
+        ("id|name", None, true),
+        // unicode classes and word boundaries
+        (r"\d+", None, false),
+        (r"\S+", None, false),
+        (".+", None, false),
+        (r"\bid", None, false),
+        // `k` also matches the Kelvin sign when case insensitive
+        ("k+", Some("i"), false),
+        // an empty match may split a multibyte character
+        ("[a-z]*", None, false),
+        ("", None, false),
+        // not a pattern
+        ("(", None, false),
+    ] {
+        assert_eq!(
+            compile_ascii_bytes_pattern(pattern, flags).is_some(),
+            is_ascii,
+            "pattern {pattern}, flags {flags:?}"
+        );
+    }
+}
+
+#[test]
+fn test_regexp_extract_ascii_bytes_matches_regex() {
+    // the Kelvin sign is a case variant of `k` when case insensitive
+    let alphabet = ['a', 'K', '1', '=', ' ', 'é', '\u{212a}', '🦀'];
+    // every value of up to 4 characters of the alphabet
+    let mut values = vec![String::new()];
+    let mut last = values.clone();
+    for _ in 0..4 {
+        last = last
+            .iter()
+            .flat_map(|value| alphabet.iter().map(move |c| format!("{value}{c}")))
+            .collect();
+        values.extend(last.iter().cloned());
+    }
+    let utf8 = Arc::new(StringArray::from(values.clone())) as ArrayRef;
+    let large_utf8 = Arc::new(LargeStringArray::from(values.clone())) as ArrayRef;
+
+    for (pattern, flags) in [
+        ("[a-z]+", None),
+        ("([a-z]+)=([0-9]*)", None),
+        (r"^(a|K)(?-u:\b)", None),
+        ("[ab]+", Some("i")),
+        ("(1)|(=+)", None),
+        ("^a$", Some("m")),
+    ] {
+        let regex = compile_pattern(pattern, flags).unwrap();
+        assert!(
+            compile_ascii_bytes_pattern(pattern, flags).is_some(),
+            "pattern {pattern}"
+        );
+        for group_index in 0..regex.captures_len() as i64 {
+            for occurrence in 1..=3 {
+                let expected = values
+                    .iter()
+                    .map(|value| {
+                        find_occurrence_group(&regex, value, group_index, occurrence)
+                            .unwrap()
+                            .or(Some(""))
+                    })
+                    .collect::<StringArray>();
+                let args = |values: &ArrayRef| {
+                    let scalar = |value: ScalarValue| value.to_array_of_size(1).unwrap();
+                    vec![
+                        Arc::clone(values),
+                        arrow::compute::cast(
+                            &scalar(ScalarValue::from(pattern)),
+                            values.data_type(),
+                        )
+                        .unwrap(),
+                        Arc::new(Int64Array::from(vec![group_index; values.len()])),
+                        Arc::new(Int64Array::from(vec![occurrence; values.len()])),
+                        arrow::compute::cast(
+                            &scalar(ScalarValue::from(flags.unwrap_or(""))),
+                            values.data_type(),
+                        )
+                        .unwrap(),
+                    ]
+                };
+                let result = regexp_extract(&args(&utf8)).unwrap();
+                assert_eq!(
+                    result.as_ref(),
+                    &expected,
+                    "pattern {pattern}, group {group_index}, occurrence {occurrence}"
+                );
+                // the output is `Utf8` for every string type
+                let result = regexp_extract(&args(&large_utf8)).unwrap();
+                assert_eq!(
+                    result.as_ref(),
+                    &expected,
+                    "pattern {pattern}, group {group_index}, occurrence {occurrence}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_regexp_extract_repeated_class() {
+    let values = StringArray::from(vec![
+        Some("id 42 and 7"),
+        Some("none"),
+        Some("٣٤ arabic"),
+        None,
+    ]);
+    let result = regexp_extract(&[
+        Arc::new(values.clone()),
+        Arc::new(StringArray::from(vec![r"(\d+)"])),
+        Arc::new(Int64Array::from(vec![1; 4])),
+        Arc::new(Int64Array::from(vec![2, 1, 1, 1])),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec![Some("7"), Some(""), Some("٣٤"), None]);
+    assert_eq!(result.as_ref(), &expected);
+
+    // the group index is still validated against the regex
+    let err = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(StringArray::from(vec![r"\d+"])),
+        Arc::new(Int64Array::from(vec![1; 4])),
+    ])
+    .expect_err("out of range group index should fail");
+    assert!(
+        err.strip_backtrace()
+            .starts_with("Execution error: Regex group index 1 is out of range"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_find_occurrence_group_entire_match() {
+    let regex = Regex::new(r"(\w)(\d)?(?<rest>[a-z]*)").unwrap();
+    let value = "a1bc d ef9 g";
+
+    // the entire matches found without the capture groups are the same
+    // as the group 0 of the captures
+    for occurrence in 1..=6 {
+        let expected = regex
+            .captures_iter(value)
+            .nth(occurrence - 1)
+            .map(|captures| captures.get(0).unwrap().as_str());
+        assert_eq!(
+            find_occurrence_group(&regex, value, 0, occurrence as i64).unwrap(),
+            expected,
+            "occurrence {occurrence}"
+        );
+    }
+    assert_eq!(
+        find_occurrence_group(&regex, value, 0, 3).unwrap(),
+        Some("ef")
+    );
+    assert_eq!(
+        find_occurrence_group(&regex, value, 0, 4).unwrap(),
+        Some("9")
+    );
+    assert_eq!(find_occurrence_group(&regex, value, 0, 6).unwrap(), None);
+    // the group index is still validated
+    assert!(find_occurrence_group(&regex, value, 4, 2).is_err());
+}
+
+#[test]
+fn test_regexp_extract_unicode_classes() {
+    let values = Arc::new(StringArray::from(vec![
+        "Köln ٣٤ Αθήνα",
+        "Zürich 42",
+        "Ελλάδα",
+        "1234",
+    ])) as ArrayRef;
+    let group_indices = Arc::new(Int64Array::from(vec![1; 4])) as ArrayRef;
+
+    // the Unicode property classes are compiled in with the default
+    // features of the regex crate
+    for (pattern, expected) in [
+        (r"(\p{L}+)", vec!["Köln", "Zürich", "Ελλάδα", ""]),
+        (r"(\p{Nd}+)", vec!["٣٤", "42", "", "1234"]),
+        (r"(\p{Greek}+)", vec!["Αθήνα", "", "Ελλάδα", ""]),
+        (r"(\P{L}+)$", vec!["", " 42", "", "1234"]),
+        (r"(\p{Lu}\p{Ll}+)", vec!["Köln", "Zürich", "Ελλάδα", ""]),
+    ] {
+        let result = regexp_extract(&[
+            Arc::clone(&values),
+            Arc::new(StringArray::from(vec![pattern])),
+            Arc::clone(&group_indices),
+        ])
+        .unwrap();
+        assert_eq!(
+            result.as_ref(),
+            &StringArray::from(expected),
+            "pattern {pattern}"
+        );
+    }
+}
+
+#[test]
+fn test_regexp_extract_nul_bytes() {
+    let values = Arc::new(StringArray::from(vec![
+        Some("key\0value\0rest"),
+        Some("\0\0"),
+        Some("no nul"),
+        None,
+    ])) as ArrayRef;
+    let group_indices = Arc::new(Int64Array::from(vec![1; 4])) as ArrayRef;
+
+    // NUL is an ordinary character for the text engine, whether it is
+    // escaped, a literal character of the pattern, or part of the anchored
+    // literal prefix
+    for pattern in [r"\x00([^\x00]*)\x00", "\0([^\0]*)\0", r"^key\x00(.*)"] {
+        let result = regexp_extract(&[
+            Arc::clone(&values),
+            Arc::new(StringArray::from(vec![pattern])),
+            Arc::clone(&group_indices),
+        ])
+        .unwrap();
+
+        let expected = if pattern.starts_with('^') {
+            StringArray::from(vec![Some("value\0rest"), Some(""), Some(""), None])
+        } else {
+            StringArray::from(vec![Some("value"), Some(""), Some(""), None])
+        };
+        assert_eq!(result.as_ref(), &expected, "pattern {pattern:?}");
+    }
+    assert_eq!(
+        anchored_literal_prefix(r"^key\x00(.*)", None).as_deref(),
+        Some("key\0")
+    );
+}
+
+#[test]
+fn test_regexp_extract_view() -> Result<()> {
+    let values = StringArray::from(vec![
+        Some("id=a-very-long-identifier;"),
+        Some("id=short;"),
+        Some("no id"),
+        None,
+        Some("id=another-long-identifier;"),
+    ]);
+    let patterns = Arc::new(StringArray::from(vec![r"id=([^;]+)"])) as ArrayRef;
+    let input_buffer = values.values().as_ptr_range();
+
+    let result = regexp_extract_view(&[Arc::new(values), Arc::clone(&patterns)])?;
+    let result = result.as_string_view();
+    let expected = StringViewArray::from(vec![
+        Some("a-very-long-identifier"),
+        Some("short"),
+        Some(""),
+        None,
+        Some("another-long-identifier"),
+    ]);
+    assert_eq!(result, &expected);
+
+    // the long strings are views into the buffer of the values
+    for i in [0, 4] {
+        assert!(input_buffer.contains(&result.value(i).as_ptr()));
+    }
+    assert_eq!(result.data_buffers().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_view_string_view_values() -> Result<()> {
+    let values = StringViewArray::from(vec![
+        "prefix:a-string-longer-than-inline",
+        "prefix:inline",
+        "prefix:another-string-longer-than-inline",
+    ])
+    .slice(1, 2);
+    let patterns = Arc::new(StringViewArray::from(vec![r"prefix:(.+)"])) as ArrayRef;
+    let input_buffers = values
+        .data_buffers()
+        .iter()
+        .map(|buffer| buffer.as_ptr_range())
+        .collect::<Vec<_>>();
+
+    let result = regexp_extract_view(&[Arc::new(values), patterns])?;
+    let result = result.as_string_view();
+    let expected =
+        StringViewArray::from(vec!["inline", "another-string-longer-than-inline"]);
+    assert_eq!(result, &expected);
+    assert!(input_buffers
+        .iter()
+        .any(|buffer| buffer.contains(&result.value(1).as_ptr())));
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_view_dictionary_values() -> Result<()> {
+    let values: DictionaryArray<Int32Type> = vec![
+        Some("id=a-very-long-identifier;"),
+        Some("id=short;"),
+        None,
+        Some("id=a-very-long-identifier;"),
+        Some("no id"),
+    ]
+    .into_iter()
+    .collect();
+    let dictionary_buffer = values.values().as_string::<i32>().values().as_ptr_range();
+    let patterns = Arc::new(StringArray::from(vec![r"id=([^;]+)"])) as ArrayRef;
+
+    let result = regexp_extract_view(&[Arc::new(values), patterns])?;
+    let result = result.as_string_view();
+    let expected = StringViewArray::from(vec![
+        Some("a-very-long-identifier"),
+        Some("short"),
+        None,
+        Some("a-very-long-identifier"),
+        Some(""),
+    ]);
+    assert_eq!(result, &expected);
+
+    // the long strings are views into the buffer of the dictionary values,
+    // which is the only data buffer of the output
+    for i in [0, 3] {
+        assert!(dictionary_buffer.contains(&result.value(i).as_ptr()));
+    }
+    assert_eq!(result.data_buffers().len(), 1);
+    assert_eq!(
+        result.data_buffers()[0].as_ptr_range().start,
+        dictionary_buffer.start
+    );
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_large_value() {
+    // a match straddling the middle and one at the end of an 8 MiB value
+    let half = 4 * 1024 * 1024;
+    let mut value = "x".repeat(half - 4);
+    value.push_str("id=12345;");
+    value.push_str(&"y".repeat(half));
+    value.push_str("id=678;");
+    let values = LargeStringArray::from(vec![value.as_str(), "id=9;"]);
+    let patterns = LargeStringArray::from(vec![r"id=(\d+);"]);
+
+    let result = regexp_extract(&[
+        Arc::new(values.clone()),
+        Arc::new(patterns.clone()),
+        Arc::new(Int64Array::from(vec![1, 1])),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec!["12345", "9"]);
+    assert_eq!(result.as_ref(), &expected);
+
+    let result = regexp_extract(&[
+        Arc::new(values),
+        Arc::new(patterns),
+        Arc::new(Int64Array::from(vec![1, 1])),
+        Arc::new(Int64Array::from(vec![2, 2])),
+    ])
+    .unwrap();
+    let expected = StringArray::from(vec!["678", ""]);
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_fixed_width() -> Result<()> {
+    let values = Arc::new(StringArray::from(vec![
+        Some("id=ab"),
+        Some("id=abcd"),
+        Some("id=abcdefgh"),
+        Some("no id"),
+        Some("id=日本"),
+        None,
+    ])) as ArrayRef;
+    let patterns = Arc::new(StringArray::from(vec![r"id=(\S+)"])) as ArrayRef;
+
+    let result = regexp_extract_fixed_width(&[values, patterns], 4)?;
+    // shorter captures are right-padded with NUL bytes and longer ones
+    // truncated, even within a multibyte character
+    let expected = FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+        vec![
+            Some(b"ab\0\0".to_vec()),
+            Some(b"abcd".to_vec()),
+            Some(b"abcd".to_vec()),
+            Some(vec![0; 4]),
+            Some("日本".as_bytes()[..4].to_vec()),
+            None,
+        ]
+        .into_iter(),
+        4,
+    )?;
+    assert_eq!(result.as_fixed_size_binary(), &expected);
+
+    let err = regexp_extract_fixed_width(
+        &[
+            Arc::new(StringArray::from(vec!["a"])),
+            Arc::new(StringArray::from(vec!["(a)"])),
+        ],
+        -1,
+    )
+    .expect_err("negative width should fail");
+    assert_eq!(
+        err.strip_backtrace(),
+        "Execution error: regexp_extract fixed width must not be negative, got -1"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_fixed_width_udf() -> Result<()> {
+    let func = RegexpExtractFunc::new().with_fixed_width(3);
+    assert_eq!(
+        func.return_type(&[DataType::Utf8, DataType::Utf8])?,
+        DataType::FixedSizeBinary(3)
+    );
+
+    let args = |value: ScalarValue| ScalarFunctionArgs {
+        args: vec![
+            ColumnarValue::Scalar(value),
+            ColumnarValue::Scalar(ScalarValue::from(r"(\d+)")),
+        ],
+        arg_fields: vec![
+            Field::new("arg_0", DataType::Utf8, true).into(),
+            Field::new("arg_1", DataType::Utf8, true).into(),
+        ],
+        number_rows: 1,
+        return_field: Field::new("f", DataType::FixedSizeBinary(3), true).into(),
+        config_options: Arc::new(ConfigOptions::default()),
+    };
+    let ColumnarValue::Scalar(result) =
+        func.invoke_with_args(args(ScalarValue::from("a 12345")))?
+    else {
+        panic!("expected a scalar result for scalar arguments");
+    };
+    assert_eq!(
+        result,
+        ScalarValue::FixedSizeBinary(3, Some(b"123".to_vec()))
+    );
+    let ColumnarValue::Scalar(result) =
+        func.invoke_with_args(args(ScalarValue::Utf8(None)))?
+    else {
+        panic!("expected a scalar result for scalar arguments");
+    };
+    assert_eq!(result, ScalarValue::FixedSizeBinary(3, None));
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_default_pattern() -> Result<()> {
+    let values: ArrayRef = Arc::new(StringArray::from(vec![
+        Some("a1-b2"),
+        Some("a1-b2"),
+        Some("x9"),
+        None,
+    ]));
+    let patterns: ArrayRef =
+        Arc::new(StringArray::from(vec![Some(r"b(\d)"), None, None, None]));
+    let dictionary_patterns: ArrayRef = Arc::new(
+        vec![Some(r"b(\d)"), None, None, None]
+            .into_iter()
+            .collect::<DictionaryArray<Int32Type>>(),
+    );
+    let invoke = |func: &RegexpExtractFunc, patterns: ColumnarValue| {
+        let arg_fields = vec![
+            Field::new("arg_0", DataType::Utf8, true).into(),
+            Field::new("arg_1", patterns.data_type(), true).into(),
+        ];
+        func.invoke_with_args(ScalarFunctionArgs {
+            args: vec![ColumnarValue::Array(Arc::clone(&values)), patterns],
+            arg_fields,
+            number_rows: values.len(),
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(ConfigOptions::default()),
+        })?
+        .into_array(values.len())
+    };
+
+    // without a default, NULL patterns yield NULL
+    let func = RegexpExtractFunc::new();
+    let expected = StringArray::from(vec![Some("2"), None, None, None]);
+    for patterns in [&patterns, &dictionary_patterns] {
+        let result = invoke(&func, ColumnarValue::Array(Arc::clone(patterns)))?;
+        assert_eq!(result.as_string::<i32>(), &expected);
+    }
+    let result = invoke(&func, ColumnarValue::Scalar(ScalarValue::Utf8(None)))?;
+    assert_eq!(result.null_count(), 4);
+
+    // with a default, the NULL patterns of the rows are replaced
+    let func = RegexpExtractFunc::new().with_default_pattern(r"(\w)\d");
+    let expected = StringArray::from(vec![Some("2"), Some("a"), Some("x"), None]);
+    for patterns in [&patterns, &dictionary_patterns] {
+        let result = invoke(&func, ColumnarValue::Array(Arc::clone(patterns)))?;
+        assert_eq!(result.as_string::<i32>(), &expected);
+    }
+    let result = invoke(&func, ColumnarValue::Scalar(ScalarValue::Utf8(None)))?;
+    assert_eq!(
+        result.as_string::<i32>(),
+        &StringArray::from(vec![Some("a"), Some("a"), Some("x"), None])
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "string_expressions")]
+fn test_regexp_extract_input_transform() -> Result<()> {
+    let values: ArrayRef = Arc::new(StringArray::from(vec![
+        Some("Error: DISK Full"),
+        Some("ΟΔΥΣΣΕΥΣ sailed"),
+        Some("die Straße"),
+        Some(""),
+        None,
+    ]));
+    let invoke = |func: &ScalarUDF, args: Vec<ColumnarValue>| {
+        let arg_fields = args
+            .iter()
+            .enumerate()
+            .map(|(idx, arg)| {
+                Field::new(format!("arg_{idx}"), arg.data_type(), true).into()
+            })
+            .collect();
+        func.invoke_with_args(ScalarFunctionArgs {
+            args,
+            arg_fields,
+            number_rows: values.len(),
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(ConfigOptions::default()),
+        })?
+        .into_array(values.len())
+    };
+
+    let unfused = ScalarUDF::from(RegexpExtractFunc::new());
+    for (transform, case_function, patterns) in [
+        (
+            InputTransform::Lower,
+            crate::string::lower(),
+            vec![r"error: (\w+)", r"(\w+ς)\b", r"(\w*ß\w*)"],
+        ),
+        (
+            InputTransform::Upper,
+            crate::string::upper(),
+            vec![r"ERROR: (\w+)", r"(\w+Σ)\b", r"(STRASSE)"],
+        ),
+    ] {
+        let fused =
+            ScalarUDF::from(RegexpExtractFunc::new().with_input_transform(transform));
+        let transformed = ColumnarValue::Array(invoke(
+            &case_function,
+            vec![ColumnarValue::Array(Arc::clone(&values))],
+        )?);
+        let pattern_array = ColumnarValue::Array(Arc::new(StringArray::from(vec![
+            patterns[0],
+            patterns[1],
+            patterns[2],
+            patterns[0],
+            patterns[1],
+        ])));
+        // the fused transform extracts the same as the explicit composition,
+        // for a scalar pattern as well as for a pattern array
+        for pattern in patterns
+            .iter()
+            .map(|pattern| ColumnarValue::Scalar(ScalarValue::from(*pattern)))
+            .chain([pattern_array])
+        {
+            let expected = invoke(&unfused, vec![transformed.clone(), pattern.clone()])?;
+            let result = invoke(
+                &fused,
+                vec![ColumnarValue::Array(Arc::clone(&values)), pattern],
+            )?;
+            assert_eq!(result.as_ref(), expected.as_ref(), "{transform:?}");
+        }
+    }
+
+    // the extracted strings are taken from the transformed strings
+    let fused = ScalarUDF::from(
+        RegexpExtractFunc::new().with_input_transform(InputTransform::Lower),
+    );
+    let result = invoke(
+        &fused,
+        vec![
+            ColumnarValue::Array(Arc::clone(&values)),
+            ColumnarValue::Scalar(ScalarValue::from(r"(\w+) (\w+)")),
+        ],
+    )?;
+    let expected = StringArray::from(vec![
+        Some("disk"),
+        Some("οδυσσευς"),
+        Some("die"),
+        Some(""),
+        None,
+    ]);
+    assert_eq!(result.as_string::<i32>(), &expected);
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_float_group_index() -> Result<()> {
+    let value = ScalarValue::from("100-200");
+    let pattern = ScalarValue::from(r"(\d+)-(\d+)");
+
+    // an integral float is taken as the integer group index
+    let result = regexp_extract_with_scalar_values(&[
+        value.clone(),
+        pattern.clone(),
+        ScalarValue::Float64(Some(2.0)),
+    ])?;
+    assert!(
+        matches!(result, ColumnarValue::Scalar(ScalarValue::Utf8(Some(v))) if v == "200")
+    );
+    let result = regexp_extract_with_array_values(&[
+        value.clone(),
+        pattern.clone(),
+        ScalarValue::Float64(Some(1.0)),
+    ])?;
+    assert_eq!(result.as_ref(), &StringArray::from(vec!["100"]));
+    let result = regexp_extract_with_scalar_values(&[
+        value.clone(),
+        pattern.clone(),
+        ScalarValue::Float64(None),
+    ])?;
+    assert!(matches!(
+        result,
+        ColumnarValue::Scalar(ScalarValue::Utf8(None))
+    ));
+
+    for index in [1.5, f64::NAN, f64::INFINITY] {
+        let args = [
+            value.clone(),
+            pattern.clone(),
+            ScalarValue::Float64(Some(index)),
+        ];
+        let err = regexp_extract_with_scalar_values(&args)
+            .expect_err("a non-integral group index should fail");
+        let expected = format!(
+            "Execution error: regexp_extract group index must be an integer, got {index}"
+        );
+        assert_eq!(err.strip_backtrace(), expected);
+        let err = regexp_extract_with_array_values(&args)
+            .expect_err("a non-integral group index should fail");
+        assert_eq!(err.strip_backtrace(), expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_regexp_extract_invalid_group_index() {
+    let values = StringArray::from(vec!["abc"]);
+    let patterns = StringArray::from(vec!["(b)"]);
+
+    for group_index in [2, -1] {
+        let err = regexp_extract(&[
+            Arc::new(values.clone()),
+            Arc::new(patterns.clone()),
+            Arc::new(Int64Array::from(vec![group_index])),
+        ])
+        .expect_err("out of range group index should fail");
+        assert_eq!(
+            err.strip_backtrace(),
+            format!("Execution error: Regex group index {group_index} is out of range, the pattern '(b)' has 1 capture groups")
+        );
+    }
+}
+
+#[derive(Debug)]
+struct OctalPatternCompiler;
+
+impl PatternCompiler for OctalPatternCompiler {
+    fn compile(&self, pattern: &str, _flags: Option<&str>) -> Result<Regex> {
+        regex::RegexBuilder::new(pattern)
+            .octal(true)
+            .build()
+            .map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+}
+
+#[test]
+fn test_regexp_extract_custom_pattern_compiler() {
+    let values = Arc::new(StringArray::from(vec!["xAy", "xBy"])) as ArrayRef;
+    // `\101` is the octal escape for 'A', which the default compiler rejects
+    let patterns = Arc::new(StringArray::from(vec![r"x(\101)y"])) as ArrayRef;
+
+    let err = regexp_extract(&[Arc::clone(&values), Arc::clone(&patterns)])
+        .expect_err("octal escapes are disabled by default");
+    assert!(err
+        .strip_backtrace()
+        .contains("unable to compile pattern 'x(\\101)y'"));
+
+    let result = regexp_extract_with_compiler(
+        &[Arc::clone(&values), Arc::clone(&patterns)],
+        &OctalPatternCompiler,
+    )
+    .unwrap();
+    let expected = StringArray::from(vec!["A", ""]);
+    assert_eq!(result.as_ref(), &expected);
+
+    let udf =
+        RegexpExtractFunc::new().with_pattern_compiler(Arc::new(OctalPatternCompiler));
+    let result = udf
+        .invoke_with_args(ScalarFunctionArgs {
+            args: vec![ColumnarValue::Array(values), ColumnarValue::Array(patterns)],
+            arg_fields: vec![
+                Field::new("arg_0", DataType::Utf8, true).into(),
+                Field::new("arg_1", DataType::Utf8, true).into(),
+            ],
+            number_rows: 2,
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(ConfigOptions::default()),
+        })
+        .unwrap();
+    let ColumnarValue::Array(result) = result else {
+        panic!("expected an array result");
+    };
+    assert_eq!(result.as_ref(), &expected);
+}
+
+#[test]
+fn test_regexp_extract_serialized_pattern_custom_compiler() {
+    let props = ExecutionProps::new();
+    let schema = Arc::new(DFSchema::empty());
+    let context = SimplifyContext::new(&props).with_schema(schema);
+    // `\101` is the octal escape for 'A', which the default compiler rejects
+    let args = vec![col("a"), lit(r"x(\101)y")];
+    let octal =
+        || RegexpExtractFunc::new().with_pattern_compiler(Arc::new(OctalPatternCompiler));
+    let ExprSimplifyResult::Simplified(Expr::ScalarFunction(simplified)) =
+        octal().simplify(args, &context).unwrap()
+    else {
+        panic!("a literal pattern should be precompiled");
+    };
+    let bytes = simplified
+        .func
+        .inner()
+        .as_any()
+        .downcast_ref::<RegexpExtractFunc>()
+        .unwrap()
+        .serialized_pattern()
+        .unwrap();
+
+    let invoke = |func: RegexpExtractFunc| {
+        func.with_serialized_pattern(&bytes)
+            .unwrap()
+            .invoke_with_args(ScalarFunctionArgs {
+                args: vec![
+                    ColumnarValue::Array(Arc::new(StringArray::from(vec!["xAy", "xBy"]))),
+                    ColumnarValue::Scalar(ScalarValue::from(r"x(\101)y")),
+                ],
+                arg_fields: vec![
+                    Field::new("a", DataType::Utf8, true).into(),
+                    Field::new("pattern", DataType::Utf8, true).into(),
+                ],
+                number_rows: 2,
+                return_field: Field::new("f", DataType::Utf8, true).into(),
+                config_options: Arc::new(ConfigOptions::default()),
+            })
+    };
+    // the restored pattern is compiled with the compiler of the function
+    let ColumnarValue::Array(result) = invoke(octal()).unwrap() else {
+        panic!("expected an array result");
+    };
+    assert_eq!(result.as_ref(), &StringArray::from(vec!["A", ""]));
+    let err = invoke(RegexpExtractFunc::new()).unwrap_err();
+    assert!(
+        err.strip_backtrace()
+            .contains("unable to compile pattern 'x(\\101)y'"),
+        "unexpected error: {err}"
+    );
+}
+
+/// Counts the patterns it compiles.
+#[derive(Debug, Default)]
+struct CountingPatternCompiler {
+    compilations: AtomicUsize,
+}
+
+impl PatternCompiler for CountingPatternCompiler {
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+        self.compilations.fetch_add(1, Ordering::Relaxed);
+        DefaultPatternCompiler.compile(pattern, flags)
+    }
+}
+
+/// Compiles with the [`DefaultPatternCompiler`] it wraps.
+#[derive(Debug)]
+struct WrappingPatternCompiler(DefaultPatternCompiler);
+
+impl PatternCompiler for WrappingPatternCompiler {
+    fn compile(&self, pattern: &str, flags: Option<&str>) -> Result<Regex> {
+        self.0.compile(pattern, flags)
+    }
+
+    fn wrapped(&self) -> Option<&dyn PatternCompiler> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn test_pattern_compiler_wrapped_analysis() {
+    // a wrapper answers the analysis of the compiler it wraps
+    let wrapper = WrappingPatternCompiler(DefaultPatternCompiler);
+    assert_eq!(
+        wrapper.anchored_prefix(r"^GET (\S+)", None).as_deref(),
+        Some("GET ")
+    );
+    assert_eq!(
+        wrapper.repeated_class("[0-9]+", None),
+        Some(vec!['0'..='9'])
+    );
+    assert!(wrapper.ascii_bytes_regex("[a-z]+=[0-9]+", None).is_some());
+
+    // a compiler that wraps none knows nothing about the patterns
+    assert_eq!(
+        OctalPatternCompiler.anchored_prefix(r"^GET (\S+)", None),
+        None
+    );
+    assert_eq!(OctalPatternCompiler.repeated_class("[0-9]+", None), None);
+    assert!(OctalPatternCompiler
+        .ascii_bytes_regex("[a-z]+=[0-9]+", None)
+        .is_none());
+}
+
+#[test]
+fn test_pattern_cache_set_capacity() {
+    let mut cache = PatternCache::new(3);
+    for pattern in ["(a)", "(b)", "(c)"] {
+        cache
+            .get_or_compile(pattern, || compile_pattern(pattern, None))
+            .unwrap();
+    }
+    cache.get(&"(a)").unwrap();
+
+    // shrinking evicts the least recently used patterns right away
+    cache.set_capacity(2);
+    assert!(cache.get(&"(b)").is_none());
+    assert!(cache.get(&"(c)").is_some());
+    assert!(cache.get(&"(a)").is_some());
+
+    // like the initial capacity, at least one pattern is kept
+    cache.set_capacity(0);
+    assert_eq!(cache.entries.len(), 1);
+    assert!(cache.get(&"(a)").is_some());
+}
+
+#[test]
+fn test_regexp_extract_pattern_cache_evicts_least_recently_used() {
+    let values = Arc::new(StringArray::from(vec!["a1"; 5])) as ArrayRef;
+    // `(a)` is used again right before `(\d)` evicts a pattern, so `(1)`
+    // is evicted and `(a)` stays cached
+    let patterns = Arc::new(StringArray::from(vec!["(a)", "(1)", "(a)", r"(\d)", "(a)"]))
+        as ArrayRef;
+
+    for (cache_size, expected_compilations) in [(1, 5), (2, 3), (3, 3), (0, 5)] {
+        let compiler = CountingPatternCompiler::default();
+        let result = regexp_extract_with_cache_size(
+            &[Arc::clone(&values), Arc::clone(&patterns)],
+            &compiler,
+            cache_size,
+        )
+        .unwrap();
+        let expected = StringArray::from(vec!["a", "1", "a", "1", "a"]);
+        assert_eq!(result.as_ref(), &expected);
+        assert_eq!(
+            compiler.compilations.load(Ordering::Relaxed),
+            expected_compilations,
+            "cache size {cache_size}"
+        );
+    }
+}
+
+#[test]
+fn test_regexp_extract_pattern_array_scalar_flags() {
+    let values =
+        Arc::new(StringArray::from(vec!["ABC", "xyz", "Abc", "XYZ", "aBc"])) as ArrayRef;
+    let patterns =
+        Arc::new(StringArray::from(vec!["(b)", "(Y)", "(b)", "(Y)", "(b)"])) as ArrayRef;
+    let group_indices = Arc::new(Int64Array::from(vec![1; 5])) as ArrayRef;
+    let flags = Arc::new(StringArray::from(vec!["i"])) as ArrayRef;
+
+    // the scalar flag applies to every row, and each distinct pattern is
+    // compiled once with it
+    let compiler = CountingPatternCompiler::default();
+    let result = regexp_extract_with_compiler(
+        &[values, patterns, group_indices, flags],
+        &compiler,
+    )
+    .unwrap();
+    let expected = StringArray::from(vec!["B", "y", "b", "Y", "B"]);
+    assert_eq!(result.as_ref(), &expected);
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn test_regexp_extract_ignore_case_multibyte_branches() {
+    let values = Arc::new(StringArray::from(vec![
+        Some("ÉTÉ-ẞß"),
+        Some("été-ßẞx"),
+        Some("Été-ss"),
+        Some("ÈTÉ-ß"),
+        None,
+    ])) as ArrayRef;
+    let group_indices = Arc::new(Int64Array::from(vec![1; 5])) as ArrayRef;
+    let pattern = "^été-(ß+)";
+    let expected =
+        StringArray::from(vec![Some("ẞß"), Some("ßẞ"), Some(""), Some(""), None]);
+
+    // the compile once branch for a scalar pattern and the per row branch
+    // for a pattern or flags array must apply the flag alike, also to the
+    // literal prefix of the anchored pattern
+    for (patterns, flags) in [
+        (
+            Arc::new(StringArray::from(vec![pattern])) as ArrayRef,
+            Arc::new(StringArray::from(vec!["i"])) as ArrayRef,
+        ),
+        (
+            Arc::new(StringArray::from(vec![pattern; 5])),
+            Arc::new(StringArray::from(vec!["i"])),
+        ),
+        (
+            Arc::new(StringArray::from(vec![pattern])),
+            Arc::new(StringArray::from(vec!["i"; 5])),
+        ),
+    ] {
+        let result = regexp_extract(&[
+            Arc::clone(&values),
+            patterns,
+            Arc::clone(&group_indices),
+            flags,
+        ])
+        .unwrap();
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
+
+#[test]
+fn test_regexp_extract_scalar_pattern_compiled_once() {
+    let values = Arc::new(StringArray::from_iter_values(
+        (0..1000).map(|i| format!("{i}-{}", i * 2)),
+    )) as ArrayRef;
+    let group_indices = Arc::new(Int64Array::from(vec![2; 1000])) as ArrayRef;
+
+    // a scalar pattern must be compiled once for the whole batch
+    let compiler = CountingPatternCompiler::default();
+    let result = regexp_extract_with_compiler(
+        &[
+            Arc::clone(&values),
+            Arc::new(StringArray::from(vec![r"(\d+)-(\d+)"])),
+            Arc::clone(&group_indices),
+        ],
+        &compiler,
+    )
+    .unwrap();
+    assert_eq!(result.len(), 1000);
+    assert_eq!(result.as_string::<i32>().value(999), "1998");
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 1);
+
+    // a pattern array is compiled once per distinct pattern
+    let compiler = CountingPatternCompiler::default();
+    let patterns = StringArray::from_iter_values((0..1000).map(|i| {
+        if i % 2 == 0 {
+            r"(\d+)-(\d+)"
+        } else {
+            r"(\d+)"
+        }
+    }));
+    regexp_extract_with_compiler(
+        &[
+            Arc::clone(&values),
+            Arc::new(patterns),
+            Arc::new(Int64Array::from(vec![1; 1000])),
+        ],
+        &compiler,
+    )
+    .unwrap();
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn test_regexp_extract_preload_patterns() {
+    let compiler = Arc::new(CountingPatternCompiler::default());
+    let func = RegexpExtractFunc::new()
+        .with_pattern_compiler(Arc::clone(&compiler) as Arc<dyn PatternCompiler>)
+        .preload_patterns(&[r"([a-z])\d", r"[a-z](\d)"])
+        .unwrap();
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 2);
+
+    let props = ExecutionProps::new();
+    let schema = Arc::new(DFSchema::empty());
+    let context = SimplifyContext::new(&props).with_schema(schema);
+    let ExprSimplifyResult::Simplified(Expr::ScalarFunction(simplified)) = func
+        .simplify(vec![col("a"), lit(r"([a-z])\d")], &context)
+        .unwrap()
+    else {
+        panic!("a literal pattern should be precompiled");
+    };
+    let simplified = simplified.func;
+    let invoke = |func: &ScalarUDF, pattern: ColumnarValue| {
+        let args = ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(vec!["a1", "b2", "c3"]))),
+                pattern,
+            ],
+            arg_fields: vec![
+                Field::new("arg_0", DataType::Utf8, true).into(),
+                Field::new("arg_1", DataType::Utf8, true).into(),
+            ],
+            number_rows: 3,
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(ConfigOptions::default()),
+        };
+        func.invoke_with_args(args).unwrap().into_array(3).unwrap()
+    };
+
+    // neither the literal pattern nor a pattern column of preloaded
+    // patterns is compiled at query time
+    let result = invoke(
+        &simplified,
+        ColumnarValue::Scalar(ScalarValue::from(r"([a-z])\d")),
+    );
+    assert_eq!(result.as_ref(), &StringArray::from(vec!["a", "b", "c"]));
+    let result = invoke(
+        &ScalarUDF::from(func),
+        ColumnarValue::Array(Arc::new(StringArray::from(vec![
+            r"[a-z](\d)",
+            r"([a-z])\d",
+            r"[a-z](\d)",
+        ]))),
+    );
+    assert_eq!(result.as_ref(), &StringArray::from(vec!["1", "b", "3"]));
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 2);
+
+    let err = RegexpExtractFunc::new()
+        .preload_patterns(&["(a)", "(b"])
+        .expect_err("an invalid preloaded pattern should fail");
+    assert!(
+        err.strip_backtrace()
+            .starts_with("Execution error: unable to compile pattern '(b'"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_regexp_extract_literal_pattern_precompiled() {
+    let compiler = Arc::new(CountingPatternCompiler::default());
+    let udf = RegexpExtractFunc::new()
+        .with_pattern_compiler(Arc::clone(&compiler) as Arc<dyn PatternCompiler>);
+
+    let props = ExecutionProps::new();
+    let schema = Arc::new(DFSchema::empty());
+    let context = SimplifyContext::new(&props).with_schema(schema);
+    let args = vec![col("a"), lit(r"(\d+)-(\d+)"), lit(2_i64)];
+    let ExprSimplifyResult::Simplified(Expr::ScalarFunction(simplified)) =
+        udf.simplify(args, &context).unwrap()
+    else {
+        panic!("a literal pattern should be precompiled");
+    };
+    // the pattern is compiled during planning
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 1);
+
+    // simplifying again keeps the precompiled pattern
+    assert!(matches!(
+        simplified
+            .func
+            .simplify(simplified.args.clone(), &context)
+            .unwrap(),
+        ExprSimplifyResult::Original(_)
+    ));
+
+    let values = Arc::new(StringArray::from(vec!["100-200", "1-2", "x"])) as ArrayRef;
+    for _ in 0..3 {
+        let result = simplified
+            .func
+            .invoke_with_args(ScalarFunctionArgs {
+                args: vec![
+                    ColumnarValue::Array(Arc::clone(&values)),
+                    ColumnarValue::Scalar(ScalarValue::from(r"(\d+)-(\d+)")),
+                    ColumnarValue::Scalar(ScalarValue::Int64(Some(2))),
+                ],
+                arg_fields: vec![
+                    Field::new("a", DataType::Utf8, true).into(),
+                    Field::new("pattern", DataType::Utf8, true).into(),
+                    Field::new("idx", DataType::Int64, true).into(),
+                ],
+                number_rows: 3,
+                return_field: Field::new("f", DataType::Utf8, true).into(),
+                config_options: Arc::new(ConfigOptions::default()),
+            })
+            .unwrap();
+        let ColumnarValue::Array(result) = result else {
+            panic!("expected an array result");
+        };
+        assert_eq!(result.as_ref(), &StringArray::from(vec!["200", "2", ""]));
+    }
+    // no batch compiles the pattern again
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 1);
+
+    // patterns that are not literals are compiled during execution
+    let args = vec![col("a"), col("b")];
+    assert!(matches!(
+        udf.simplify(args, &context).unwrap(),
+        ExprSimplifyResult::Original(_)
+    ));
+}
+
+#[test]
+fn test_regexp_extract_cast_literal_pattern_precompiled() {
+    let compiler = Arc::new(CountingPatternCompiler::default());
+    let udf = RegexpExtractFunc::new()
+        .with_pattern_compiler(Arc::clone(&compiler) as Arc<dyn PatternCompiler>);
+
+    let props = ExecutionProps::new();
+    let schema = Arc::new(DFSchema::empty());
+    let context = SimplifyContext::new(&props).with_schema(schema);
+    let pattern = cast(
+        try_cast(lit(r"(\d+)-(\d+)"), DataType::LargeUtf8),
+        DataType::Utf8View,
+    );
+    let flags = cast(lit("i"), DataType::Utf8View);
+    let args = vec![col("a"), pattern, lit(2_i64), flags];
+    let ExprSimplifyResult::Simplified(Expr::ScalarFunction(simplified)) =
+        udf.simplify(args, &context).unwrap()
+    else {
+        panic!("a literal pattern in casts should be precompiled");
+    };
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 1);
+
+    let values = Arc::new(StringViewArray::from(vec!["100-200", "1-2", "x"])) as ArrayRef;
+    for _ in 0..3 {
+        let result = simplified
+            .func
+            .invoke_with_args(ScalarFunctionArgs {
+                args: vec![
+                    ColumnarValue::Array(Arc::clone(&values)),
+                    ColumnarValue::Scalar(ScalarValue::Utf8View(Some(
+                        r"(\d+)-(\d+)".to_string(),
+                    ))),
+                    ColumnarValue::Scalar(ScalarValue::Int64(Some(2))),
+                    ColumnarValue::Scalar(ScalarValue::Utf8View(Some("i".to_string()))),
+                ],
+                arg_fields: vec![
+                    Field::new("a", DataType::Utf8View, true).into(),
+                    Field::new("pattern", DataType::Utf8View, true).into(),
+                    Field::new("idx", DataType::Int64, true).into(),
+                    Field::new("flags", DataType::Utf8View, true).into(),
+                ],
+                number_rows: 3,
+                return_field: Field::new("f", DataType::Utf8, true).into(),
+                config_options: Arc::new(ConfigOptions::default()),
+            })
+            .unwrap();
+        let ColumnarValue::Array(result) = result else {
+            panic!("expected an array result");
+        };
+        assert_eq!(result.as_ref(), &StringArray::from(vec!["200", "2", ""]));
+    }
+    // the batches reuse the pattern compiled during planning
+    assert_eq!(compiler.compilations.load(Ordering::Relaxed), 1);
+
+    // a cast to another type may change the literal
+    let args = vec![col("a"), cast(lit(12_i64), DataType::Utf8)];
+    assert!(matches!(
+        udf.simplify(args, &context).unwrap(),
+        ExprSimplifyResult::Original(_)
+    ));
+}
+
+#[test]
+fn test_regexp_extract_serialized_pattern() {
+    let props = ExecutionProps::new();
+    let schema = Arc::new(DFSchema::empty());
+    let context = SimplifyContext::new(&props).with_schema(schema);
+    let pattern = r"serialized (\w+)";
+    let args = vec![col("a"), lit(pattern), lit(1_i64), lit("i")];
+    let ExprSimplifyResult::Simplified(Expr::ScalarFunction(simplified)) =
+        RegexpExtractFunc::new().simplify(args, &context).unwrap()
+    else {
+        panic!("a literal pattern should be precompiled");
+    };
+    let simplified = simplified
+        .func
+        .inner()
+        .as_any()
+        .downcast_ref::<RegexpExtractFunc>()
+        .unwrap();
+    let bytes = simplified.serialized_pattern().unwrap();
+
+    let restored = RegexpExtractFunc::new()
+        .with_serialized_pattern(&bytes)
+        .unwrap();
+    assert_eq!(restored.precompiled, simplified.precompiled);
+    assert_eq!(restored.serialized_pattern().unwrap(), bytes);
+
+    let mut config = ConfigOptions::default();
+    config.extensions.insert(SharedRegexes::default());
+    let config = Arc::new(config);
+    let invoke = |func: &RegexpExtractFunc| {
+        let ColumnarValue::Array(result) = func
+            .invoke_with_args(ScalarFunctionArgs {
+                args: vec![
+                    ColumnarValue::Array(Arc::new(StringArray::from(vec![
+                        "SERIALIZED plan",
+                        "other",
+                    ]))),
+                    ColumnarValue::Scalar(ScalarValue::from(pattern)),
+                    ColumnarValue::Scalar(ScalarValue::Int64(Some(1))),
+                    ColumnarValue::Scalar(ScalarValue::from("i")),
+                ],
+                arg_fields: vec![
+                    Field::new("a", DataType::Utf8, true).into(),
+                    Field::new("pattern", DataType::Utf8, true).into(),
+                    Field::new("idx", DataType::Int64, true).into(),
+                    Field::new("flags", DataType::Utf8, true).into(),
+                ],
+                number_rows: 2,
+                return_field: Field::new("f", DataType::Utf8, true).into(),
+                config_options: Arc::clone(&config),
+            })
+            .unwrap()
+        else {
+            panic!("expected an array result");
+        };
+        assert_eq!(result.as_ref(), &StringArray::from(vec!["plan", ""]));
+    };
+    // the restored pattern is compiled on first use and shared with every
+    // other function of the session restored from the same bytes
+    let shared = SharedRegexes::from_config(&config).unwrap();
+    assert!(restored.precompiled.as_ref().unwrap().regex.get().is_none());
+    invoke(&restored);
+    assert!(restored.precompiled.as_ref().unwrap().regex.get().is_some());
+    assert_eq!(shared.compilations(), 1);
+    invoke(
+        &RegexpExtractFunc::new()
+            .with_serialized_pattern(&bytes)
+            .unwrap(),
+    );
+    assert_eq!(shared.compilations(), 1);
+
+    // patterns that are not literals are not serialized
+    assert_eq!(RegexpExtractFunc::new().serialized_pattern(), None);
+
+    for invalid in [
+        &bytes[..8],
+        &bytes[..bytes.len() - 3],
+        &b"not a pattern"[..],
+    ] {
+        let err = RegexpExtractFunc::new()
+            .with_serialized_pattern(invalid)
+            .expect_err("invalid bytes should fail");
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: invalid serialized regexp_extract pattern"
+        );
+    }
+}
+
+/// Invokes `func` on `args` with `config`, returning the array result.
+fn invoke_with_config(
+    func: &dyn ScalarUDFImpl,
+    args: Vec<ColumnarValue>,
+    config: &Arc<ConfigOptions>,
+) -> ArrayRef {
+    let arg_fields = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| Field::new(format!("arg_{i}"), arg.data_type(), true).into())
+        .collect();
+    let number_rows = args
+        .iter()
+        .find_map(|arg| match arg {
+            ColumnarValue::Array(array) => Some(array.len()),
+            ColumnarValue::Scalar(_) => None,
+        })
+        .unwrap_or(1);
+    let return_type = func
+        .return_type(&args.iter().map(|arg| arg.data_type()).collect::<Vec<_>>())
+        .unwrap();
+    func.invoke_with_args(ScalarFunctionArgs {
+        args,
+        arg_fields,
+        number_rows,
+        return_field: Field::new("f", return_type, true).into(),
+        config_options: Arc::clone(config),
+    })
+    .unwrap()
+    .into_array(number_rows)
+    .unwrap()
+}
+
+#[test]
+fn test_regexp_extract_shares_compiled_pattern() {
+    use crate::regex::regexpcount::RegexpCountFunc;
+
+    let mut config = ConfigOptions::default();
+    config.extensions.insert(SharedRegexes::default());
+    let config = Arc::new(config);
+    let shared = SharedRegexes::from_config(&config).unwrap();
+
+    let values = ColumnarValue::Array(Arc::new(StringArray::from(vec!["7 and 8", "9"])));
+    let pattern = || ColumnarValue::Scalar(ScalarValue::from(r"(\d+)"));
+    let group = ColumnarValue::Scalar(ScalarValue::Int64(Some(1)));
+    let extracted = invoke_with_config(
+        &RegexpExtractFunc::new(),
+        vec![values.clone(), pattern(), group.clone()],
+        &config,
+    );
+    assert_eq!(extracted.as_ref(), &StringArray::from(vec!["7", "9"]));
+    let counted = invoke_with_config(
+        &RegexpCountFunc::new(),
+        vec![values.clone(), pattern()],
+        &config,
+    );
+    assert_eq!(counted.as_ref(), &Int64Array::from(vec![2, 1]));
+    // a pattern column is compiled through the shared patterns too
+    let patterns = ColumnarValue::Array(Arc::new(StringArray::from(vec![r"(\d+)"; 2])));
+    invoke_with_config(
+        &RegexpExtractFunc::new(),
+        vec![values.clone(), patterns, group.clone()],
+        &config,
+    );
+    assert_eq!(shared.compilations(), 1);
+
+    // a custom compiler may compile differently, so it does not share
+    invoke_with_config(
+        &RegexpExtractFunc::new().with_pattern_compiler(Arc::new(OctalPatternCompiler)),
+        vec![values.clone(), pattern(), group.clone()],
+        &config,
+    );
+    assert_eq!(shared.compilations(), 1);
+
+    // another session compiles the pattern again
+    let mut other = ConfigOptions::default();
+    other.extensions.insert(SharedRegexes::default());
+    let other = Arc::new(other);
+    invoke_with_config(&RegexpCountFunc::new(), vec![values, pattern()], &other);
+    assert_eq!(
+        SharedRegexes::from_config(&other).unwrap().compilations(),
+        1
+    );
+    assert_eq!(shared.compilations(), 1);
+}
+
+#[test]
+fn test_shared_regexes_pattern_cache_size() {
+    let mut config = ConfigOptions::default();
+    config.extensions.insert(SharedRegexes::default());
+    config.set("regex.pattern_cache_size", "2").unwrap();
+    assert!(config
+        .entries()
+        .iter()
+        .any(|entry| entry.key == "regex.pattern_cache_size"
+            && entry.value.as_deref() == Some("2")));
+    let config = Arc::new(config);
+    let shared = SharedRegexes::from_config(&config).unwrap();
+    assert_eq!(shared.pattern_cache_size(), 2);
+
+    let values = ColumnarValue::Array(Arc::new(StringArray::from(vec!["a1"])));
+    let extract = |pattern: &str| {
+        invoke_with_config(
+            &RegexpExtractFunc::new(),
+            vec![
+                values.clone(),
+                ColumnarValue::Scalar(ScalarValue::from(pattern)),
+            ],
+            &config,
+        )
+    };
+    extract(r"(a)");
+    extract(r"(\d)");
+    extract(r"(a)");
+    assert_eq!(shared.compilations(), 2);
+    // the third pattern evicts the least recently used one, `(\d)`
+    extract(r"(\w)");
+    extract(r"(a)");
+    assert_eq!(shared.compilations(), 3);
+    extract(r"(\d)");
+    assert_eq!(shared.compilations(), 4);
+
+    // a clone of the options starts an empty cache of the same size
+    let cloned = ConfigOptions::clone(&config);
+    let cloned = SharedRegexes::from_config(&cloned).unwrap();
+    assert_eq!(cloned.pattern_cache_size(), 2);
+    assert_eq!(cloned.compilations(), 0);
+
+    let mut config = ConfigOptions::default();
+    config.extensions.insert(SharedRegexes::default());
+    assert!(config.set("regex.pattern_cache_size", "many").is_err());
+    assert!(config.set("regex.unknown", "1").is_err());
+}
+
+#[test]
+fn test_regexp_extract_crlf_lines() {
+    let values = Arc::new(StringArray::from(vec!["first\r\nsecond\r\n"])) as ArrayRef;
+    let extract = |pattern: &str, flags: &str| {
+        let result = regexp_extract(&[
+            Arc::clone(&values),
+            Arc::new(StringArray::from(vec![pattern])),
+            Arc::new(Int64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec![flags])),
+        ])
+        .unwrap();
+        result.as_string::<i32>().value(0).to_string()
+    };
+
+    // in multi-line mode `$` only matches before `\n`, so `\r` is part of the line
+    assert_eq!(extract(r"^(.+)$", "m"), "first\r");
+    assert_eq!(extract(r"^(\w+)$", "m"), "");
+    // CRLF mode treats `\r\n` as the line terminator
+    assert_eq!(extract(r"^(.+)$", "mR"), "first");
+    assert_eq!(extract(r"^(\w+)$", "mR"), "first");
+}
+
+/// Splits lines on the ASCII record separator.
+#[derive(Debug)]
+struct RecordSeparatorCompiler;
+
+impl PatternCompiler for RecordSeparatorCompiler {
+    fn compile(&self, pattern: &str, _flags: Option<&str>) -> Result<Regex> {
+        regex::RegexBuilder::new(pattern)
+            .multi_line(true)
+            .line_terminator(b'\x1e')
+            .build()
+            .map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+}
+
+#[test]
+fn test_regexp_extract_custom_line_terminator() {
+    let values = Arc::new(StringArray::from(vec!["a=1\x1eb=2\x1ec=3"])) as ArrayRef;
+    let patterns = Arc::new(StringArray::from(vec![r"^b=(\d)$"])) as ArrayRef;
+
+    let result = regexp_extract(&[Arc::clone(&values), Arc::clone(&patterns)]).unwrap();
+    assert_eq!(result.as_string::<i32>().value(0), "");
+
+    let result =
+        regexp_extract_with_compiler(&[values, patterns], &RecordSeparatorCompiler)
+            .unwrap();
+    assert_eq!(result.as_string::<i32>().value(0), "2");
+}
+
+#[test]
+fn test_row_patterns() {
+    let patterns = StringArray::from(vec![Some(r"(\d)"), None, Some(r"(\d)")]);
+    let mut row_patterns = RowPatterns::try_new("f", &patterns, 3).unwrap();
+    assert!(row_patterns.is_valid(0));
+    assert!(row_patterns.is_null(1));
+    let first = row_patterns.get(0).unwrap().as_str().to_string();
+    assert_eq!(first, row_patterns.get(2).unwrap().as_str());
+
+    let scalar = StringArray::from(vec![r"(\w)"]);
+    let mut row_patterns = RowPatterns::try_new("f", &scalar, 3).unwrap();
+    assert_eq!(row_patterns.get(2).unwrap().as_str(), r"(\w)");
+
+    let invalid = StringArray::from(vec![r"(", r"(\d)"]);
+    assert!(RowPatterns::try_new("f", &invalid, 2).is_ok());
+    let err = RowPatterns::try_new("f", &invalid, 3).err().unwrap();
+    assert!(err
+        .to_string()
+        .contains("f pattern array must be the same length as the values array"));
+}
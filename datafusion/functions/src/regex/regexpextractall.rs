@@ -0,0 +1,454 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{
+    group_index_at, invoke_materialized, validate_group_index, RegexpExtractConfig,
+    RowPatterns,
+};
+use arrow::array::{
+    Array, ArrayBuilder, ArrayRef, AsArray, Int64Array, ListBuilder, StringArrayType,
+    StringBuilder,
+};
+use arrow::datatypes::{DataType, Field, Int64Type};
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the specified capture group of every [regular expression](https://docs.rs/regex/latest/regex/#syntax) match in a string. Capture groups that did not participate in a match are returned as empty strings.",
+    syntax_example = "regexp_extract_all(str, regexp[, idx[, limit]])",
+    sql_example = r#"```sql
+> select regexp_extract_all('100-200, 300-400', '(\d+)-(\d+)', 1);
++---------------------------------------------------------------------------+
+| regexp_extract_all(Utf8("100-200, 300-400"),Utf8("(\d+)-(\d+)"),Int64(1)) |
++---------------------------------------------------------------------------+
+| [100, 300]                                                                |
++---------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "limit",
+        description = "Optional maximum number of matches to return for each row. Must be greater than 0. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractAllFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractAllFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractAllFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View, Utf8View]),
+                    TypeSignature::Exact(vec![Utf8, Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8, LargeUtf8]),
+                    TypeSignature::Exact(vec![Utf8View, Utf8View, Int64]),
+                    TypeSignature::Exact(vec![Utf8, Utf8, Int64]),
+                    TypeSignature::Exact(vec![LargeUtf8, LargeUtf8, Int64]),
+                    TypeSignature::Exact(vec![Utf8View, Utf8View, Int64, Int64]),
+                    TypeSignature::Exact(vec![Utf8, Utf8, Int64, Int64]),
+                    TypeSignature::Exact(vec![LargeUtf8, LargeUtf8, Int64, Int64]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractAllFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_all"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::List(Arc::new(Field::new_list_field(
+            DataType::Utf8,
+            true,
+        ))))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        let total_matches_limit = RegexpExtractConfig::from_config(&args.config_options)
+            .all_total_matches_limit;
+        invoke_materialized(&args.args, &[1], |args| {
+            regexp_extract_all_with_total_limit(args, total_matches_limit)
+        })
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Extracts the specified capture group of every match of a regular
+/// expression from each string of `args[0]`.
+///
+/// `args[1]` holds the patterns, either one per row or a single pattern that
+/// is applied to every row. The optional `args[2]` holds the capture group
+/// index, which defaults to 1, and the optional `args[3]` caps the number of
+/// matches returned for each row.
+pub fn regexp_extract_all(args: &[ArrayRef]) -> Result<ArrayRef> {
+    regexp_extract_all_with_total_limit(args, None)
+}
+
+/// Like [`regexp_extract_all`], but fails once the rows return more than
+/// `total_matches_limit` matches in total, if specified.
+pub fn regexp_extract_all_with_total_limit(
+    args: &[ArrayRef],
+    total_matches_limit: Option<usize>,
+) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(2..=4).contains(&args_len) {
+        return exec_err!(
+            "regexp_extract_all was called with {args_len} arguments. It requires at least 2 and at most 4."
+        );
+    }
+
+    let group_index_array = args.get(2).map(|a| a.as_primitive::<Int64Type>());
+    let limit_array = args.get(3).map(|a| a.as_primitive::<Int64Type>());
+
+    match (args[0].data_type(), args[1].data_type()) {
+        (DataType::Utf8, DataType::Utf8) => concrete_regexp_extract_all(
+            args[0].as_string::<i32>(),
+            args[1].as_string::<i32>(),
+            group_index_array,
+            limit_array,
+            total_matches_limit,
+        ),
+        (DataType::LargeUtf8, DataType::LargeUtf8) => concrete_regexp_extract_all(
+            args[0].as_string::<i64>(),
+            args[1].as_string::<i64>(),
+            group_index_array,
+            limit_array,
+            total_matches_limit,
+        ),
+        (DataType::Utf8View, DataType::Utf8View) => concrete_regexp_extract_all(
+            args[0].as_string_view(),
+            args[1].as_string_view(),
+            group_index_array,
+            limit_array,
+            total_matches_limit,
+        ),
+        other => {
+            exec_err!("Unsupported data type {other:?} for function regexp_extract_all")
+        }
+    }
+}
+
+fn concrete_regexp_extract_all<'a, S>(
+    values: S,
+    pattern_array: S,
+    group_index_array: Option<&Int64Array>,
+    limit_array: Option<&Int64Array>,
+    total_matches_limit: Option<usize>,
+) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a>,
+{
+    let mut builder = ListBuilder::new(StringBuilder::new());
+
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_all", pattern_array, values.len())?;
+    for i in 0..values.len() {
+        let group_index = match group_index_at(group_index_array, i) {
+            Some(group_index) if values.is_valid(i) && patterns.is_valid(i) => {
+                group_index
+            }
+            _ => {
+                builder.append_null();
+                continue;
+            }
+        };
+
+        let regex = patterns.get(i)?;
+        let limit = match limit_array {
+            Some(limit_array) if limit_array.is_valid(i) => {
+                Some(validate_limit(limit_array.value(i))?)
+            }
+            _ => None,
+        };
+
+        // one match past the total limit is enough to detect exceeding it,
+        // so a row matching excessively never buffers all of its matches
+        let limit = match total_matches_limit {
+            Some(total_limit) => {
+                let cap = total_limit
+                    .saturating_sub(builder.values().len())
+                    .saturating_add(1);
+                Some(limit.map_or(cap, |limit| limit.min(cap)))
+            }
+            None => limit,
+        };
+
+        extract_all_groups(builder.values(), regex, values.value(i), group_index, limit)?;
+        if let Some(total_limit) =
+            total_matches_limit.filter(|limit| builder.values().len() > *limit)
+        {
+            return exec_err!(
+                "regexp_extract_all returned more than {total_limit} matches in a batch at row {i}, above the limit set by regexp_extract.all_total_matches_limit"
+            );
+        }
+        builder.append(true);
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn validate_limit(limit: i64) -> Result<usize> {
+    match usize::try_from(limit) {
+        Ok(limit) if limit > 0 => Ok(limit),
+        _ => exec_err!("regexp_extract_all limit must be greater than 0, got {limit}"),
+    }
+}
+
+/// Appends the capture group `group_index` of every match of `regex` in
+/// `value` to `builder`, stopping after `limit` matches if specified.
+///
+/// The matches are appended in the order of their position in `value`, from
+/// left to right. Any change to how the matches are found must preserve it.
+///
+/// Patterns that can match the empty string, such as `a*` or `\b`, yield an
+/// empty match at every position where they do not match anything longer.
+/// [`Regex::captures_iter`] advances past each empty match and never reports
+/// one within a character or right after a non-empty match, so the number of
+/// matches is bounded by the number of characters of `value` plus one.
+fn extract_all_groups(
+    builder: &mut StringBuilder,
+    regex: &Regex,
+    value: &str,
+    group_index: i64,
+    limit: Option<usize>,
+) -> Result<()> {
+    let group_index = validate_group_index(regex, group_index)?;
+    regex
+        .captures_iter(value)
+        .take(limit.unwrap_or(usize::MAX))
+        .for_each(|captures| {
+            builder.append_value(captures.get(group_index).map_or("", |m| m.as_str()))
+        });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ListArray, StringArray};
+
+    fn string_list(rows: Vec<Option<Vec<&str>>>) -> ListArray {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for row in rows {
+            match row {
+                Some(values) => {
+                    values
+                        .into_iter()
+                        .for_each(|v| builder.values().append_value(v));
+                    builder.append(true);
+                }
+                None => builder.append_null(),
+            }
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_regexp_extract_all() {
+        let values =
+            StringArray::from(vec![Some("100-200, 300-400"), Some("no digits"), None]);
+        let patterns = StringArray::from(vec![r"(\d+)-(\d+)"]);
+        let group_indices = Int64Array::from(vec![2, 2, 2]);
+
+        let result = regexp_extract_all(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+        ])
+        .unwrap();
+
+        let expected = string_list(vec![Some(vec!["200", "400"]), Some(vec![]), None]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_all_preserves_match_order() {
+        // neither sorted nor reverse sorted, so any reordering is detected
+        let value = "k=zeta@0 k=alpha@9 k=mu@19 k=beta@27 k=omega@37";
+        let values = StringArray::from(vec![value, value]);
+        let patterns = StringArray::from(vec![r"k=(\w+)@(\d+)"]);
+        let group_indices = Int64Array::from(vec![1, 2]);
+
+        let result = regexp_extract_all(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+        ])
+        .unwrap();
+
+        // the second row holds the position of each match in the value
+        let expected = string_list(vec![
+            Some(vec!["zeta", "alpha", "mu", "beta", "omega"]),
+            Some(vec!["0", "9", "19", "27", "37"]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+        for position in ["0", "9", "19", "27", "37"] {
+            let position = position.parse::<usize>().unwrap();
+            assert!(value[position..].starts_with("k="));
+        }
+    }
+
+    #[test]
+    fn test_regexp_extract_all_limit() {
+        let values = StringArray::from(vec!["a1 a2 a3 a4 a5", "a1", "a1 a2 a3"]);
+        let patterns = StringArray::from(vec![r"a(\d)"]);
+        let group_indices = Int64Array::from(vec![1, 1, 1]);
+        let limits = Int64Array::from(vec![Some(3), Some(3), None]);
+
+        let result = regexp_extract_all(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+            Arc::new(limits),
+        ])
+        .unwrap();
+
+        let expected = string_list(vec![
+            Some(vec!["1", "2", "3"]),
+            Some(vec!["1"]),
+            Some(vec!["1", "2", "3"]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_all_total_matches_limit() {
+        let args: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["a1 a2", "a3", "a4 a5 a6", "a7"])),
+            Arc::new(StringArray::from(vec![r"a(\d)"])),
+            Arc::new(Int64Array::from(vec![1; 4])),
+        ];
+
+        // the limit is not exceeded by the first two rows, but by the third
+        let err = regexp_extract_all_with_total_limit(&args, Some(4))
+            .expect_err("the batch returns 7 matches");
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_extract_all returned more than 4 matches in a batch at row 2, above the limit set by regexp_extract.all_total_matches_limit"
+        );
+
+        let result = regexp_extract_all_with_total_limit(&args, Some(7)).unwrap();
+        let expected = string_list(vec![
+            Some(vec!["1", "2"]),
+            Some(vec!["3"]),
+            Some(vec!["4", "5", "6"]),
+            Some(vec!["7"]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_all_invalid_limit() {
+        for limit in [0, -1] {
+            let err = regexp_extract_all(&[
+                Arc::new(StringArray::from(vec!["a1 a2"])),
+                Arc::new(StringArray::from(vec![r"a(\d)"])),
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(Int64Array::from(vec![limit])),
+            ])
+            .expect_err("non positive limit should fail");
+            assert_eq!(
+                err.strip_backtrace(),
+                format!("Execution error: regexp_extract_all limit must be greater than 0, got {limit}")
+            );
+        }
+    }
+
+    #[test]
+    fn test_regexp_extract_all_zero_width_matches() {
+        let values = StringArray::from(vec!["baab", "", "ab cd", "éa"]);
+        let patterns = StringArray::from(vec!["a*", "a*", r"\b", "a*"]);
+        let group_indices = Int64Array::from(vec![0, 0, 0, 0]);
+
+        let result = regexp_extract_all(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+        ])
+        .unwrap();
+
+        // `a*` matches empty before the first `b`, then `aa`, and empty again
+        // only after the last `b`, not right after `aa`; `\b` matches empty at
+        // each of the 4 word boundaries; no empty match is reported within the
+        // 2 bytes of `é`
+        let expected = string_list(vec![
+            Some(vec!["", "aa", ""]),
+            Some(vec![""]),
+            Some(vec!["", "", "", ""]),
+            Some(vec!["", "a"]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_all_zero_width_matches_long_value() {
+        // every position of a long value yields at most one empty match
+        let value = "b".repeat(10_000);
+        let result = regexp_extract_all(&[
+            Arc::new(StringArray::from(vec![value.as_str()])),
+            Arc::new(StringArray::from(vec!["(a*)"])),
+            Arc::new(Int64Array::from(vec![1])),
+        ])
+        .unwrap();
+        let matches = result.as_list::<i32>().value(0);
+        assert_eq!(matches.len(), 10_001);
+        assert!(matches.as_string::<i32>().iter().all(|m| m == Some("")));
+    }
+}
@@ -0,0 +1,281 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, RowPatterns};
+use crate::regex::regexpextractmap::map_type;
+use arrow::array::{
+    Array, ArrayRef, AsArray, ListBuilder, MapBuilder, StringArrayType, StringBuilder,
+};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns a list with a map for every [regular expression](https://docs.rs/regex/latest/regex/#syntax) match in a string, in the order of the matches, from the names of the named capture groups to the values they captured. Unnamed capture groups are left out, and groups that did not participate in a match map to NULL. Returns an empty list if the regular expression does not match.",
+    syntax_example = "regexp_extract_all_named(str, regexp)",
+    sql_example = r#"```sql
+> select regexp_extract_all_named('a=1 b c=3', '(?<key>[a-z])(?:=(?<value>\d))?');
++-------------------------------------------------------------------------------------+
+| regexp_extract_all_named(Utf8("a=1 b c=3"),Utf8("(?<key>[a-z])(?:=(?<value>\d))?")) |
++-------------------------------------------------------------------------------------+
+| [{key: a, value: 1}, {key: b, value: NULL}, {key: c, value: 3}]                     |
++-------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractAllNamedFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractAllNamedFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractAllNamedFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View, Utf8View]),
+                    TypeSignature::Exact(vec![Utf8, Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8, LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractAllNamedFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_all_named"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::new_list(map_type(), true))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_all_named)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Builds a list with a map from the named capture groups to their captured
+/// values for every match of a regular expression in each string of
+/// `args[0]`.
+///
+/// `args[1]` holds the patterns, either one per row or a single pattern that
+/// is applied to every row.
+pub fn regexp_extract_all_named(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if args_len != 2 {
+        return exec_err!(
+            "regexp_extract_all_named was called with {args_len} arguments. It requires 2."
+        );
+    }
+
+    match (args[0].data_type(), args[1].data_type()) {
+        (DataType::Utf8, DataType::Utf8) => concrete_regexp_extract_all_named(
+            args[0].as_string::<i32>(),
+            args[1].as_string::<i32>(),
+        ),
+        (DataType::LargeUtf8, DataType::LargeUtf8) => concrete_regexp_extract_all_named(
+            args[0].as_string::<i64>(),
+            args[1].as_string::<i64>(),
+        ),
+        (DataType::Utf8View, DataType::Utf8View) => concrete_regexp_extract_all_named(
+            args[0].as_string_view(),
+            args[1].as_string_view(),
+        ),
+        other => exec_err!(
+            "Unsupported data type {other:?} for function regexp_extract_all_named"
+        ),
+    }
+}
+
+fn concrete_regexp_extract_all_named<'a, S>(
+    values: S,
+    pattern_array: S,
+) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a>,
+{
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_all_named", pattern_array, values.len())?;
+
+    let mut builder = ListBuilder::new(MapBuilder::new(
+        None,
+        StringBuilder::new(),
+        StringBuilder::new(),
+    ));
+    for i in 0..values.len() {
+        if values.is_null(i) || patterns.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+
+        let regex = patterns.get(i)?;
+        append_matches(builder.values(), regex, values.value(i))?;
+        builder.append(true);
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Appends a map of the named capture groups of every match of `regex` in
+/// `value` to `builder`.
+fn append_matches(
+    builder: &mut MapBuilder<StringBuilder, StringBuilder>,
+    regex: &Regex,
+    value: &str,
+) -> Result<()> {
+    // the names are the same for every match, only resolve them once
+    let named_groups = regex
+        .capture_names()
+        .enumerate()
+        .filter_map(|(group_index, name)| name.map(|name| (group_index, name)))
+        .collect::<Vec<_>>();
+
+    for captures in regex.captures_iter(value) {
+        for &(group_index, name) in &named_groups {
+            builder.keys().append_value(name);
+            builder
+                .values()
+                .append_option(captures.get(group_index).map(|m| m.as_str()));
+        }
+        builder.append(true)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ListArray, StringArray};
+
+    /// The entries of the maps of a list row
+    type Matches<'a> = Vec<Vec<(&'a str, Option<&'a str>)>>;
+
+    fn map_list(rows: Vec<Option<Matches>>) -> ListArray {
+        let mut builder = ListBuilder::new(MapBuilder::new(
+            None,
+            StringBuilder::new(),
+            StringBuilder::new(),
+        ));
+        for row in rows {
+            match row {
+                Some(matches) => {
+                    for entries in matches {
+                        for (key, value) in entries {
+                            builder.values().keys().append_value(key);
+                            builder.values().values().append_option(value);
+                        }
+                        builder.values().append(true).unwrap();
+                    }
+                    builder.append(true);
+                }
+                None => builder.append_null(),
+            }
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_regexp_extract_all_named() {
+        let values = StringArray::from(vec![
+            Some("a=1; b; c=3"),
+            Some("no matches here!"),
+            Some("x=9"),
+            None,
+        ]);
+        // the unnamed group is left out of the maps
+        let patterns = StringArray::from(vec![r"(?<key>[a-z])(=)?(?<value>\d)?(?:;|$)"]);
+
+        let result =
+            regexp_extract_all_named(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+
+        let expected = map_list(vec![
+            Some(vec![
+                vec![("key", Some("a")), ("value", Some("1"))],
+                // the value group did not participate in the match
+                vec![("key", Some("b")), ("value", None)],
+                vec![("key", Some("c")), ("value", Some("3"))],
+            ]),
+            Some(vec![]),
+            Some(vec![vec![("key", Some("x")), ("value", Some("9"))]]),
+            None,
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+        assert_eq!(result.data_type(), &DataType::new_list(map_type(), true));
+    }
+
+    #[test]
+    fn test_regexp_extract_all_named_array_pattern() {
+        let values = StringArray::from(vec!["2024-03-15 2025-01-02", "ab", "abc"]);
+        let patterns = StringArray::from(vec![
+            r"(?<year>\d{4})-\d{2}-(?<day>\d{2})",
+            r"(?<digit>\d)",
+            r"(\w)",
+        ]);
+
+        let result =
+            regexp_extract_all_named(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+
+        // a pattern without named groups yields an empty map per match
+        let expected = map_list(vec![
+            Some(vec![
+                vec![("year", Some("2024")), ("day", Some("15"))],
+                vec![("year", Some("2025")), ("day", Some("02"))],
+            ]),
+            Some(vec![]),
+            Some(vec![vec![], vec![], vec![]]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+}
@@ -0,0 +1,475 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{group_index_at, invoke_materialized};
+use arrow::array::{
+    Array, ArrayRef, AsArray, BinaryArrayType, BinaryBuilder, Int64Array, StringArray,
+    StringBuilder,
+};
+use arrow::datatypes::{DataType, Int64Type};
+use datafusion_common::{exec_err, plan_err, DataFusionError, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::bytes::Regex;
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// How [`RegexpExtractBytesFunc`] returns the captured bytes, which are not
+/// necessarily valid UTF-8.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BytesCaptureMode {
+    /// Returns the captured bytes as `Binary`
+    #[default]
+    Binary,
+    /// Returns the captured bytes as `Utf8` and fails on invalid UTF-8
+    Utf8,
+    /// Returns the captured bytes as `Utf8`, replacing invalid UTF-8 with
+    /// the replacement character `U+FFFD`
+    Utf8Lossy,
+}
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match of the specified capture group from a binary value, like `regexp_extract` but matching bytes. Returns an empty value if the regular expression does not match or the capture group did not participate in the match. Use the `-u` flag to match bytes that are not valid UTF-8.",
+    syntax_example = "regexp_extract_bytes(bytes, regexp[, idx[, flags]])",
+    sql_example = r#"```sql
+> select regexp_extract_bytes(X'3130302D323030', '(\d+)-(\d+)', 2);
++-----------------------------------------------------------------------------------+
+| regexp_extract_bytes(Binary("49,48,48,45,50,48,48"),Utf8("(\d+)-(\d+)"),Int64(2)) |
++-----------------------------------------------------------------------------------+
+| 323030                                                                            |
++-----------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "bytes",
+        description = "Binary expression to operate on. Can be a constant, column, or function, and any combination of operators."
+    ),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "flags",
+        description = "Optional regular expression flags, the same as the flags of `regexp_extract`."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractBytesFunc {
+    signature: Signature,
+    mode: BytesCaptureMode,
+}
+
+impl Default for RegexpExtractBytesFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractBytesFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            mode: BytesCaptureMode::default(),
+        }
+    }
+
+    /// Returns the captured bytes according to `mode` instead of as `Binary`.
+    pub fn with_capture_mode(mut self, mode: BytesCaptureMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractBytesFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_bytes"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(match self.mode {
+            BytesCaptureMode::Binary => DataType::Binary,
+            BytesCaptureMode::Utf8 | BytesCaptureMode::Utf8Lossy => DataType::Utf8,
+        })
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        // the pattern and the flags are compiled once if both are scalars
+        invoke_materialized(&args.args, &[1, 3], |args| {
+            regexp_extract_bytes(args, self.mode)
+        })
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if !(2..=4).contains(&arg_types.len()) {
+            return plan_err!(
+                "regexp_extract_bytes requires 2 to 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (0, Binary | LargeBinary | BinaryView) => Ok(data_type.clone()),
+                (0, Null) => Ok(Binary),
+                (0, other) => {
+                    plan_err!("regexp_extract_bytes value must be binary, got {other}")
+                }
+                (2, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (2, other) => plan_err!(
+                    "regexp_extract_bytes group index must be an integer, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_bytes argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Extracts the specified capture group of the first match of a regular
+/// expression from each binary value of `args[0]`, returning the captured
+/// bytes according to `mode`.
+///
+/// `args[1]` holds the `Utf8` patterns, either one per row or a single
+/// pattern that is applied to every row. The optional `args[2]` holds the
+/// capture group index, which defaults to 1, and the optional `args[3]` holds
+/// the `Utf8` flags, either one per row or a single value for every row.
+pub fn regexp_extract_bytes(
+    args: &[ArrayRef],
+    mode: BytesCaptureMode,
+) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(2..=4).contains(&args_len) {
+        return exec_err!(
+            "regexp_extract_bytes was called with {args_len} arguments. It requires at least 2 and at most 4."
+        );
+    }
+    // the pattern and the flags
+    if let Some(arg) = [1, 3]
+        .into_iter()
+        .filter_map(|idx| args.get(idx))
+        .find(|arg| arg.data_type() != &DataType::Utf8)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_bytes",
+            arg.data_type()
+        );
+    }
+
+    let pattern_array = args[1].as_string::<i32>();
+    let group_index_array = args.get(2).map(|a| a.as_primitive::<Int64Type>());
+    let flags_array = args.get(3).map(|a| a.as_string::<i32>());
+
+    match args[0].data_type() {
+        DataType::Binary => concrete_regexp_extract_bytes(
+            args[0].as_binary::<i32>(),
+            pattern_array,
+            group_index_array,
+            flags_array,
+            mode,
+        ),
+        DataType::LargeBinary => concrete_regexp_extract_bytes(
+            args[0].as_binary::<i64>(),
+            pattern_array,
+            group_index_array,
+            flags_array,
+            mode,
+        ),
+        DataType::BinaryView => concrete_regexp_extract_bytes(
+            args[0].as_binary_view(),
+            pattern_array,
+            group_index_array,
+            flags_array,
+            mode,
+        ),
+        other => {
+            exec_err!("Unsupported data type {other:?} for function regexp_extract_bytes")
+        }
+    }
+}
+
+fn concrete_regexp_extract_bytes<'a, B>(
+    values: B,
+    pattern_array: &StringArray,
+    group_index_array: Option<&Int64Array>,
+    flags_array: Option<&StringArray>,
+    mode: BytesCaptureMode,
+) -> Result<ArrayRef>
+where
+    B: BinaryArrayType<'a>,
+{
+    if pattern_array.len() != 1 && values.len() != pattern_array.len() {
+        return exec_err!(
+            "regexp_extract_bytes pattern array must be the same length as the values array; got {} and {}",
+            pattern_array.len(),
+            values.len()
+        );
+    }
+    if let Some(flags_array) = flags_array.filter(|flags| flags.len() != 1) {
+        if values.len() != flags_array.len() {
+            return exec_err!(
+                "regexp_extract_bytes flags array must be the same length as the values array; got {} and {}",
+                flags_array.len(),
+                values.len()
+            );
+        }
+    }
+
+    let mut builder = CaptureBuilder::new(mode, values.len());
+    let mut regex_cache: HashMap<(&str, Option<&str>), Regex> = HashMap::new();
+    for i in 0..values.len() {
+        let pattern_index = if pattern_array.len() == 1 { 0 } else { i };
+        let group_index = match group_index_at(group_index_array, i) {
+            Some(group_index)
+                if values.is_valid(i) && pattern_array.is_valid(pattern_index) =>
+            {
+                group_index
+            }
+            _ => {
+                builder.append_null();
+                continue;
+            }
+        };
+
+        let pattern = pattern_array.value(pattern_index);
+        let flags = flags_array.and_then(|flags_array| {
+            let flags_index = if flags_array.len() == 1 { 0 } else { i };
+            flags_array
+                .is_valid(flags_index)
+                .then(|| flags_array.value(flags_index))
+        });
+        let regex = match regex_cache.entry((pattern, flags)) {
+            Entry::Occupied(occupied_entry) => occupied_entry.into_mut(),
+            Entry::Vacant(vacant_entry) => {
+                vacant_entry.insert(compile_bytes_pattern(pattern, flags)?)
+            }
+        };
+        let captured = extract_bytes_group(regex, values.value(i), group_index)?;
+        builder.append_value(captured, i)?;
+    }
+
+    Ok(builder.finish())
+}
+
+/// Builds the result of [`regexp_extract_bytes`] for a [`BytesCaptureMode`].
+enum CaptureBuilder {
+    Binary(BinaryBuilder),
+    Utf8 { builder: StringBuilder, lossy: bool },
+}
+
+impl CaptureBuilder {
+    fn new(mode: BytesCaptureMode, capacity: usize) -> Self {
+        match mode {
+            BytesCaptureMode::Binary => {
+                Self::Binary(BinaryBuilder::with_capacity(capacity, 0))
+            }
+            BytesCaptureMode::Utf8 | BytesCaptureMode::Utf8Lossy => Self::Utf8 {
+                builder: StringBuilder::with_capacity(capacity, 0),
+                lossy: mode == BytesCaptureMode::Utf8Lossy,
+            },
+        }
+    }
+
+    fn append_null(&mut self) {
+        match self {
+            Self::Binary(builder) => builder.append_null(),
+            Self::Utf8 { builder, .. } => builder.append_null(),
+        }
+    }
+
+    /// Appends the bytes captured in row `row`.
+    fn append_value(&mut self, captured: &[u8], row: usize) -> Result<()> {
+        match self {
+            Self::Binary(builder) => builder.append_value(captured),
+            Self::Utf8 {
+                builder,
+                lossy: true,
+            } => builder.append_value(String::from_utf8_lossy(captured)),
+            Self::Utf8 {
+                builder,
+                lossy: false,
+            } => match std::str::from_utf8(captured) {
+                Ok(captured) => builder.append_value(captured),
+                Err(e) => {
+                    return exec_err!(
+                        "regexp_extract_bytes captured invalid UTF-8 in row {row}: {e}"
+                    )
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> ArrayRef {
+        match self {
+            Self::Binary(mut builder) => Arc::new(builder.finish()),
+            Self::Utf8 { mut builder, .. } => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/// Compiles `pattern` into a bytes [`Regex`] with the optional inline `flags`.
+fn compile_bytes_pattern(pattern: &str, flags: Option<&str>) -> Result<Regex> {
+    let regex = match flags {
+        None | Some("") => Regex::new(pattern),
+        Some(flags) if flags.contains('g') => {
+            return exec_err!("regexp_extract_bytes() does not support the global flag");
+        }
+        Some(flags) => Regex::new(&format!("(?{flags}){pattern}")),
+    };
+    regex.map_err(|e| {
+        DataFusionError::Execution(format!("unable to compile pattern '{pattern}': {e}"))
+    })
+}
+
+/// Returns the capture group `group_index` of the first match of `regex` in
+/// `value`, or no bytes if there is no match or the group did not participate
+/// in the match.
+fn extract_bytes_group<'h>(
+    regex: &Regex,
+    value: &'h [u8],
+    group_index: i64,
+) -> Result<&'h [u8]> {
+    let group_count = regex.captures_len() - 1;
+    let group_index = match usize::try_from(group_index) {
+        Ok(group_index) if group_index <= group_count => group_index,
+        _ => {
+            return exec_err!(
+                "Regex group index {group_index} is out of range, the pattern '{}' has {group_count} capture groups",
+                regex.as_str()
+            )
+        }
+    };
+    if group_index == 0 {
+        return Ok(regex.find(value).map_or(&[], |m| m.as_bytes()));
+    }
+    if !regex.is_match(value) {
+        return Ok(&[]);
+    }
+    Ok(regex
+        .captures(value)
+        .and_then(|captures| captures.get(group_index))
+        .map_or(&[], |m| m.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{BinaryArray, BinaryViewArray};
+
+    /// `a` followed by the invalid UTF-8 byte `0xFF` and `b`
+    const INVALID_UTF8: &[u8] = b"x=a\xFFb;";
+
+    fn extract(values: ArrayRef, mode: BytesCaptureMode) -> Result<ArrayRef> {
+        regexp_extract_bytes(
+            &[
+                values,
+                Arc::new(StringArray::from(vec![r"x=(.+);"])),
+                Arc::new(Int64Array::from(vec![1, 1, 1])),
+                Arc::new(StringArray::from(vec!["-u"])),
+            ],
+            mode,
+        )
+    }
+
+    fn values() -> ArrayRef {
+        Arc::new(BinaryArray::from(vec![
+            Some(INVALID_UTF8),
+            Some(b"x=ok;".as_slice()),
+            None,
+        ]))
+    }
+
+    #[test]
+    fn test_regexp_extract_bytes_binary() {
+        let result = extract(values(), BytesCaptureMode::Binary).unwrap();
+        let expected =
+            BinaryArray::from(vec![Some(b"a\xFFb".as_slice()), Some(b"ok"), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_bytes_utf8() {
+        let err = extract(values(), BytesCaptureMode::Utf8)
+            .expect_err("invalid UTF-8 should fail");
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_extract_bytes captured invalid UTF-8 in row 0: invalid utf-8 sequence of 1 bytes from index 1"
+        );
+
+        let valid = Arc::new(BinaryViewArray::from(vec![
+            Some(b"x=ok;".as_slice()),
+            Some(b"no match"),
+            None,
+        ]));
+        let result = extract(valid, BytesCaptureMode::Utf8).unwrap();
+        let expected = StringArray::from(vec![Some("ok"), Some(""), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_bytes_utf8_lossy() {
+        let result = extract(values(), BytesCaptureMode::Utf8Lossy).unwrap();
+        let expected = StringArray::from(vec![Some("a\u{FFFD}b"), Some("ok"), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_bytes_nul() {
+        let values = Arc::new(BinaryArray::from(vec![
+            Some(b"x=a\0b;".as_slice()),
+            Some(b"x=\0\xFF;"),
+            Some(b"x=;"),
+        ]));
+
+        let result = extract(values, BytesCaptureMode::Binary).unwrap();
+        let expected =
+            BinaryArray::from(vec![Some(b"a\0b".as_slice()), Some(b"\0\xFF"), Some(b"")]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
@@ -0,0 +1,382 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{compile_pattern, find_group, validate_group_index};
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{exec_err, plan_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::{Regex, RegexSet};
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Tries a list of [regular expressions](https://docs.rs/regex/latest/regex/#syntax) in order and extracts the specified capture group from the first match of the first regular expression that matches a string. The regular expressions may have different group layouts, but the group index must be valid for each of them. Like `regexp_extract`, returns an empty string if none of the regular expressions match.",
+    syntax_example = "regexp_extract_coalesce(str, regexps[, idx])",
+    sql_example = r#"```sql
+> select regexp_extract_coalesce('15.03.2024', ['(\d{4})-\d{2}-\d{2}', '\d{2}\.\d{2}\.(\d{4})'], 1);
++----------------------------------------------------------------------------------------------------------------------------+
+| regexp_extract_coalesce(Utf8("15.03.2024"),make_array(Utf8("(\d{4})-\d{2}-\d{2}"),Utf8("\d{2}\.\d{2}\.(\d{4})")),Int64(1)) |
++----------------------------------------------------------------------------------------------------------------------------+
+| 2024                                                                                                                       |
++----------------------------------------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexps",
+        description = "List of the regular expressions to try, in order. Must be a constant."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract, which must be valid for every regular expression. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractCoalesceFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractCoalesceFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractCoalesceFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractCoalesceFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_coalesce"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        let (arrays, is_scalar) = coalesce_arrays(&args.args, "regexp_extract_coalesce")?;
+        let result = regexp_extract_coalesce(&arrays);
+        if is_scalar {
+            // If all inputs are scalar, keeps output as scalar
+            let result = result.and_then(|arr| ScalarValue::try_from_array(&arr, 0));
+            result.map(ColumnarValue::Scalar)
+        } else {
+            result.map(ColumnarValue::Array)
+        }
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coalesce_arg_types(arg_types, "regexp_extract_coalesce")
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Materializes the arguments of `name`, a function with the arguments of
+/// `regexp_extract_coalesce`, returning them along with whether all of them
+/// are scalars. The list of patterns must be a constant.
+pub(crate) fn coalesce_arrays(
+    args: &[ColumnarValue],
+    name: &str,
+) -> Result<(Vec<ArrayRef>, bool)> {
+    let Some(ColumnarValue::Scalar(ScalarValue::List(patterns))) = args.get(1) else {
+        return exec_err!("{name} patterns must be a constant list");
+    };
+    let len = args
+        .iter()
+        .fold(Option::<usize>::None, |acc, arg| match arg {
+            ColumnarValue::Scalar(_) => acc,
+            ColumnarValue::Array(a) => Some(a.len()),
+        });
+
+    let inferred_length = len.unwrap_or(1);
+    let mut arrays = vec![
+        args[0].to_array(inferred_length)?,
+        Arc::clone(patterns) as ArrayRef,
+    ];
+    if let Some(group_index) = args.get(2) {
+        arrays.push(group_index.to_array(inferred_length)?);
+    }
+    Ok((arrays, len.is_none()))
+}
+
+/// Coerces the arguments of `name`, a function with the arguments of
+/// `regexp_extract_coalesce`.
+pub(crate) fn coalesce_arg_types(
+    arg_types: &[DataType],
+    name: &str,
+) -> Result<Vec<DataType>> {
+    use DataType::*;
+    if !(2..=3).contains(&arg_types.len()) {
+        return plan_err!("{name} requires 2 or 3 arguments, got {}", arg_types.len());
+    }
+
+    arg_types
+        .iter()
+        .enumerate()
+        .map(|(idx, data_type)| match (idx, data_type) {
+            (0, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+            (0, other) => {
+                plan_err!("{name} argument {idx} must be a string, got {other}")
+            }
+            (1, List(field) | LargeList(field) | FixedSizeList(field, _))
+                if matches!(field.data_type(), Utf8 | LargeUtf8 | Utf8View | Null) =>
+            {
+                Ok(string_list_type())
+            }
+            (1, Null) => Ok(string_list_type()),
+            (1, other) => plan_err!(
+                "{name} expects a list of patterns as the second argument, got {other}"
+            ),
+            (_, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                Ok(Int64)
+            }
+            (_, other) => plan_err!("{name} group index must be an integer, got {other}"),
+        })
+        .collect()
+}
+
+/// The `List<Utf8>` type of the patterns of `regexp_extract_coalesce`.
+fn string_list_type() -> DataType {
+    DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+}
+
+/// Extracts the capture group `args[2]`, 1 by default, from the first match
+/// of the first pattern of the list `args[1]` that matches each string of
+/// `args[0]`.
+///
+/// `args[1]` is a single element array with the list of patterns, which is
+/// applied to every row. The strings are expected to be `Utf8` and the group
+/// indices `Int64`.
+pub fn regexp_extract_coalesce(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let result = coalesce_matches(args, "regexp_extract_coalesce")?
+        .into_iter()
+        .map(|row| row.map(|(value, _)| value))
+        .collect::<StringArray>();
+    Ok(Arc::new(result))
+}
+
+/// The capture group extracted from a row and the index of the pattern that
+/// matched it, if any.
+pub(crate) type CoalesceMatch<'a> = (&'a str, Option<usize>);
+
+/// Returns the [`CoalesceMatch`] of each row of the arguments of
+/// [`regexp_extract_coalesce`], or `None` for the rows with a NULL string or
+/// group index.
+///
+/// The index is `None` if none of the patterns match, in which case the
+/// capture group is the empty string.
+pub(crate) fn coalesce_matches<'a>(
+    args: &'a [ArrayRef],
+    name: &str,
+) -> Result<Vec<Option<CoalesceMatch<'a>>>> {
+    let args_len = args.len();
+    if !(2..=3).contains(&args_len) {
+        return exec_err!(
+            "{name} was called with {args_len} arguments. It requires at least 2 and at most 3."
+        );
+    }
+    if args[0].data_type() != &DataType::Utf8 {
+        return exec_err!(
+            "Unsupported data type {:?} for function {name}",
+            args[0].data_type()
+        );
+    }
+    if args[1].data_type() != &string_list_type() || args[1].len() != 1 {
+        return exec_err!(
+            "{name} patterns must be a single list of strings, got {} values of type {:?}",
+            args[1].len(),
+            args[1].data_type()
+        );
+    }
+    let group_index_array = match args.get(2) {
+        Some(group_index_array) => match group_index_array.as_primitive_opt() {
+            Some(group_index_array) => Some(group_index_array),
+            None => {
+                return exec_err!(
+                    "Unsupported data type {:?} for the group index of function {name}",
+                    group_index_array.data_type()
+                )
+            }
+        },
+        None => None,
+    };
+
+    let values = args[0].as_string::<i32>();
+    let pattern_list = args[1].as_list::<i32>();
+    if pattern_list.is_null(0) {
+        return Ok(vec![None; values.len()]);
+    }
+    let patterns = pattern_list.value(0);
+    let patterns = patterns.as_string::<i32>();
+    if patterns.null_count() > 0 {
+        return exec_err!("{name} patterns must not be NULL");
+    }
+    // the patterns are compiled on their own first for the errors to name the
+    // invalid pattern
+    let regexes = patterns
+        .iter()
+        .flatten()
+        .map(|pattern| compile_pattern(pattern, None))
+        .collect::<Result<Vec<_>>>()?;
+    let set = RegexSet::new(patterns.iter().flatten()).map_err(|e| {
+        DataFusionError::Execution(format!("unable to compile the {name} patterns: {e}"))
+    })?;
+
+    (0..values.len())
+        .map(|i| {
+            let group_index = group_index_array
+                .map_or(Some(1), |a: &Int64Array| a.is_valid(i).then(|| a.value(i)));
+            match group_index {
+                Some(group_index) if values.is_valid(i) => {
+                    coalesce_group(&regexes, &set, values.value(i), group_index).map(Some)
+                }
+                _ => Ok(None),
+            }
+        })
+        .collect()
+}
+
+/// Returns the capture group `group_index` of the first of `regexes` that
+/// matches `value` along with its index, or the empty string if none of them
+/// match.
+///
+/// `set` holds the same patterns as `regexes`, so that the first match is
+/// found in a single pass over `value`.
+fn coalesce_group<'h>(
+    regexes: &[Regex],
+    set: &RegexSet,
+    value: &'h str,
+    group_index: i64,
+) -> Result<CoalesceMatch<'h>> {
+    // the group index has to be valid for every pattern, not only for the
+    // ones that are tried before a match
+    for regex in regexes {
+        validate_group_index(regex, group_index)?;
+    }
+    // the indices of the matching patterns are in ascending order
+    let Some(pattern_index) = set.matches(value).iter().next() else {
+        return Ok(("", None));
+    };
+    let group = find_group(&regexes[pattern_index], value, group_index)?;
+    Ok((group.unwrap_or(""), Some(pattern_index)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ListBuilder, StringBuilder};
+
+    fn string_list(values: Vec<&str>) -> ArrayRef {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        values
+            .into_iter()
+            .for_each(|v| builder.values().append_value(v));
+        builder.append(true);
+        Arc::new(builder.finish())
+    }
+
+    #[test]
+    fn test_regexp_extract_coalesce() {
+        let values = StringArray::from(vec![
+            Some("2024-03-15"),
+            Some("15.03.2024"),
+            Some("03/15/2024 or 2025-01-02"),
+            Some("no date"),
+            None,
+        ]);
+        // the groups of the patterns are laid out differently
+        let patterns = string_list(vec![
+            r"(\d{4})-(\d{2})-\d{2}",
+            r"\d{2}\.(\d{2})\.(\d{4})",
+            r"(\d{2})/\d{2}/(\d{4})",
+        ]);
+        let group_indices = Int64Array::from(vec![1, 2, 2, 1, 1]);
+
+        let result = regexp_extract_coalesce(&[
+            Arc::new(values),
+            patterns,
+            Arc::new(group_indices),
+        ])
+        .unwrap();
+
+        // the first pattern that matches wins, even if a later pattern
+        // matches earlier in the string
+        let expected = StringArray::from(vec![
+            Some("2024"),
+            Some("2024"),
+            Some("01"),
+            Some(""),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_coalesce_default_group() {
+        let values = StringArray::from(vec!["id: 42", "#7"]);
+        let patterns = string_list(vec![r"id: (\d+)", r"#(\d+)"]);
+
+        let result = regexp_extract_coalesce(&[Arc::new(values), patterns]).unwrap();
+        assert_eq!(result.as_ref(), &StringArray::from(vec!["42", "7"]));
+    }
+
+    #[test]
+    fn test_regexp_extract_coalesce_group_index_per_pattern() {
+        let values = StringArray::from(vec!["a1"]);
+        // the first pattern matches, but the second one has no group 2
+        let patterns = string_list(vec![r"(a)(\d)", r"(a)"]);
+        let group_indices = Int64Array::from(vec![2]);
+
+        let err = regexp_extract_coalesce(&[
+            Arc::new(values),
+            patterns,
+            Arc::new(group_indices),
+        ])
+        .expect_err("out of range group index should fail");
+        assert!(
+            err.strip_backtrace().starts_with(
+                "Execution error: Regex group index 2 is out of range, the pattern '(a)'"
+            ),
+            "unexpected error: {err}"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextractcoalesce::{
+    coalesce_arg_types, coalesce_arrays, coalesce_matches,
+};
+use arrow::array::{ArrayRef, Int64Builder, StringBuilder, StructBuilder};
+use arrow::datatypes::{DataType, Field, Fields};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Like `regexp_extract_coalesce`, extracts the specified capture group from the first match of the first of a list of [regular expressions](https://docs.rs/regex/latest/regex/#syntax) that matches a string, but returns a struct of the extracted `value` and the `pattern_index` of the regular expression that matched, counting from 1 like list elements. This tells which rule of a rule-based extraction fired. If none of the regular expressions match, the value is an empty string and the pattern index is NULL.",
+    syntax_example = "regexp_extract_coalesce_indexed(str, regexps[, idx])",
+    sql_example = r#"```sql
+> select regexp_extract_coalesce_indexed('15.03.2024', ['(\d{4})-\d{2}-\d{2}', '\d{2}\.\d{2}\.(\d{4})'], 1);
++------------------------------------------------------------------------------------------------------------------------------------+
+| regexp_extract_coalesce_indexed(Utf8("15.03.2024"),make_array(Utf8("(\d{4})-\d{2}-\d{2}"),Utf8("\d{2}\.\d{2}\.(\d{4})")),Int64(1)) |
++------------------------------------------------------------------------------------------------------------------------------------+
+| {value: 2024, pattern_index: 2}                                                                                                    |
++------------------------------------------------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexps",
+        description = "List of the regular expressions to try, in order. Must be a constant."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract, which must be valid for every regular expression. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractCoalesceIndexedFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractCoalesceIndexedFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractCoalesceIndexedFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractCoalesceIndexedFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_coalesce_indexed"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Struct(indexed_fields()))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        let (arrays, is_scalar) =
+            coalesce_arrays(&args.args, "regexp_extract_coalesce_indexed")?;
+        let result = regexp_extract_coalesce_indexed(&arrays);
+        if is_scalar {
+            // If all inputs are scalar, keeps output as scalar
+            let result = result.and_then(|arr| ScalarValue::try_from_array(&arr, 0));
+            result.map(ColumnarValue::Scalar)
+        } else {
+            result.map(ColumnarValue::Array)
+        }
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coalesce_arg_types(arg_types, "regexp_extract_coalesce_indexed")
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The fields of the struct returned by `regexp_extract_coalesce_indexed`.
+fn indexed_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("value", DataType::Utf8, true),
+        Field::new("pattern_index", DataType::Int64, true),
+    ])
+}
+
+/// Like [`regexp_extract_coalesce`], but returns a struct of the extracted
+/// value and the index of the pattern that matched, counting from 1.
+///
+/// [`regexp_extract_coalesce`]: crate::regex::regexpextractcoalesce::regexp_extract_coalesce
+pub fn regexp_extract_coalesce_indexed(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let matches = coalesce_matches(args, "regexp_extract_coalesce_indexed")?;
+
+    let mut builder = StructBuilder::new(
+        indexed_fields(),
+        vec![
+            Box::new(StringBuilder::with_capacity(matches.len(), 0)),
+            Box::new(Int64Builder::with_capacity(matches.len())),
+        ],
+    );
+    for row in matches {
+        let (value, pattern_index) = match row {
+            Some((value, pattern_index)) => {
+                (Some(value), pattern_index.map(|index| index as i64 + 1))
+            }
+            None => (None, None),
+        };
+        builder
+            .field_builder::<StringBuilder>(0)
+            .expect("the value field is a string")
+            .append_option(value);
+        builder
+            .field_builder::<Int64Builder>(1)
+            .expect("the pattern index field is an integer")
+            .append_option(pattern_index);
+        builder.append(value.is_some());
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{
+        Array, AsArray, Int64Array, ListBuilder, StringArray, StructArray,
+    };
+    use arrow::datatypes::Int64Type;
+
+    fn string_list(values: Vec<&str>) -> ArrayRef {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        values
+            .into_iter()
+            .for_each(|v| builder.values().append_value(v));
+        builder.append(true);
+        Arc::new(builder.finish())
+    }
+
+    #[test]
+    fn test_regexp_extract_coalesce_indexed() {
+        let values = StringArray::from(vec![
+            Some("2024-03-15"),
+            Some("15.03.2024"),
+            Some("03/15/2024 or 2025-01-02"),
+            Some("no date"),
+            None,
+        ]);
+        let patterns = string_list(vec![
+            r"(\d{4})-(\d{2})-\d{2}",
+            r"\d{2}\.(\d{2})\.(\d{4})",
+            r"(\d{2})/\d{2}/(\d{4})",
+        ]);
+        let group_indices = Int64Array::from(vec![1, 2, 2, 1, 1]);
+
+        let result = regexp_extract_coalesce_indexed(&[
+            Arc::new(values),
+            patterns,
+            Arc::new(group_indices),
+        ])
+        .unwrap();
+        let result = result.as_struct();
+
+        // the first pattern in the list that matches is reported, even if a
+        // later pattern matches earlier in the string
+        let expected_values = StringArray::from(vec![
+            Some("2024"),
+            Some("2024"),
+            Some("01"),
+            Some(""),
+            None,
+        ]);
+        let expected_indices =
+            Int64Array::from(vec![Some(1), Some(2), Some(1), None, None]);
+        assert_eq!(result.column(0).as_ref(), &expected_values);
+        assert_eq!(
+            result.column(1).as_primitive::<Int64Type>(),
+            &expected_indices
+        );
+        // only a NULL string yields a NULL struct, a string without a match
+        // yields a NULL index
+        assert_eq!(result.logical_null_count(), 1);
+        assert!(result.is_null(4));
+    }
+
+    #[test]
+    fn test_regexp_extract_coalesce_indexed_overlapping_patterns() {
+        // every pattern matches the first row, and only the last one matches
+        // the second
+        let values = StringArray::from(vec!["error: disk full", "warning"]);
+        let patterns = string_list(vec![r"(error): (\w+)", r"(\w+):", r"(\w+)"]);
+
+        let result =
+            regexp_extract_coalesce_indexed(&[Arc::new(values), patterns]).unwrap();
+        let expected = StructArray::new(
+            indexed_fields(),
+            vec![
+                Arc::new(StringArray::from(vec!["error", "warning"])),
+                Arc::new(Int64Array::from(vec![1, 3])),
+            ],
+            None,
+        );
+        assert_eq!(result.as_struct(), &expected);
+    }
+}
@@ -0,0 +1,246 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{extract_group, invoke_materialized, RowPatterns};
+use arrow::array::{Array, ArrayRef, AsArray, StringArray, StringBuilder};
+use arrow::datatypes::{DataType, Int64Type};
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Joins the given strings with a separator and extracts the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match of the specified capture group from the result, like `regexp_extract(concat_ws(sep, str[, ..., str_n]), regexp, idx)` but without materializing the joined strings. NULL strings are skipped.",
+    syntax_example = "regexp_extract_concat(sep, regexp, idx, str[, ..., str_n])",
+    sql_example = r#"```sql
+> select regexp_extract_concat('-', '(\d+)-(\d+)', 2, '100', '200');
++---------------------------------------------------------------------------------------+
+| regexp_extract_concat(Utf8("-"),Utf8("(\d+)-(\d+)"),Int64(2),Utf8("100"),Utf8("200")) |
++---------------------------------------------------------------------------------------+
+| 200                                                                                   |
++---------------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "sep",
+        description = "Separator inserted between the joined strings."
+    ),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Index of the capture group to extract. Group 0 is the entire match. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "str",
+        description = "String expression to join. Can be a constant, column, or function, and any combination of operators."
+    ),
+    argument(name = "str_n", description = "Subsequent string expressions to join.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractConcatFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractConcatFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractConcatFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractConcatFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_concat"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_concat)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() < 4 {
+            return plan_err!(
+                "regexp_extract_concat requires at least 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (2, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (2, other) => plan_err!(
+                    "regexp_extract_concat group index must be an integer, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_concat argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Joins `args[3..]` with the separator `args[0]` for each row and extracts
+/// the capture group `args[2]` of the first match of `args[1]`.
+///
+/// The pattern may be a single element array, in which case it is applied to
+/// every row. All string arguments are expected to be `Utf8`.
+pub fn regexp_extract_concat(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() < 4 {
+        return exec_err!(
+            "regexp_extract_concat was called with {} arguments. It requires at least 4.",
+            args.len()
+        );
+    }
+    if let Some((_, arg)) = args
+        .iter()
+        .enumerate()
+        .find(|(idx, arg)| *idx != 2 && arg.data_type() != &DataType::Utf8)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_concat",
+            arg.data_type()
+        );
+    }
+
+    let separators = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let group_index_array = args[2].as_primitive::<Int64Type>();
+    let columns = args[3..]
+        .iter()
+        .map(|a| a.as_string::<i32>())
+        .collect::<Vec<&StringArray>>();
+
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_concat", pattern_array, separators.len())?;
+
+    let mut builder = StringBuilder::with_capacity(separators.len(), 0);
+    // reused across rows to avoid allocating the joined value for every row
+    let mut joined = String::new();
+    for i in 0..separators.len() {
+        if separators.is_null(i) || patterns.is_null(i) || group_index_array.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+
+        joined.clear();
+        let separator = separators.value(i);
+        for column in columns.iter().filter(|column| column.is_valid(i)) {
+            if !joined.is_empty() {
+                joined.push_str(separator);
+            }
+            joined.push_str(column.value(i));
+        }
+
+        let regex = patterns.get(i)?;
+        builder.append_value(extract_group(regex, &joined, group_index_array.value(i))?);
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    #[test]
+    fn test_regexp_extract_concat() {
+        let separators = StringArray::from(vec!["-", "-", "::", "-"]);
+        let patterns = StringArray::from(vec![r"(\w+)-(\w+)-(\w+)"]);
+        let group_indices = Int64Array::from(vec![3, 0, 1, 2]);
+        let first = StringArray::from(vec![Some("a"), Some("x"), Some("a"), None]);
+        let second = StringArray::from(vec![Some("b"), Some("y"), Some("b"), Some("m")]);
+        let third = StringArray::from(vec![Some("c"), Some("z"), Some("c"), Some("n")]);
+
+        let result = regexp_extract_concat(&[
+            Arc::new(separators),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+            Arc::new(first),
+            Arc::new(second),
+            Arc::new(third),
+        ])
+        .unwrap();
+
+        // the separator is part of the joined value, so a pattern has to
+        // account for it: "a::b::c" does not match and the NULL in the last
+        // row is skipped, leaving only two parts
+        let expected = StringArray::from(vec!["c", "x-y-z", "", ""]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_concat_separator_offsets() {
+        let separators = StringArray::from(vec![", "]);
+        let patterns = StringArray::from(vec![r", (\d+)"]);
+        let group_indices = Int64Array::from(vec![1]);
+        let first = StringArray::from(vec!["abc"]);
+        let second = StringArray::from(vec!["123"]);
+
+        let result = regexp_extract_concat(&[
+            Arc::new(separators),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+            Arc::new(first),
+            Arc::new(second),
+        ])
+        .unwrap();
+
+        let expected = StringArray::from(vec!["123"]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
@@ -0,0 +1,320 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, regexp_extract};
+use arrow::array::{Array, ArrayRef, AsArray, StringArray, StructArray};
+use arrow::datatypes::{DataType, Fields};
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the specified capture group of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match from a string field of a struct, like `regexp_extract(struct['field'], regexp, idx)` without projecting the field first. Returns NULL if the struct is NULL, has no field of that name or the field is NULL, and otherwise behaves like `regexp_extract` on the field.",
+    syntax_example = "regexp_extract_field(struct, field, regexp[, idx])",
+    sql_example = r#"```sql
+> select regexp_extract_field({id: 'user-42', name: 'Ada'}, 'id', 'user-(\d+)', 1);
++--------------------------------------------------------------------------------------------------------------------------------+
+| regexp_extract_field(named_struct(Utf8("id"),Utf8("user-42"),Utf8("name"),Utf8("Ada")),Utf8("id"),Utf8("user-(\d+)"),Int64(1)) |
++--------------------------------------------------------------------------------------------------------------------------------+
+| 42                                                                                                                             |
++--------------------------------------------------------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "struct",
+        description = "Struct to extract from. Can be a constant, column, or function, and any combination of struct operators."
+    ),
+    argument(
+        name = "field",
+        description = "Name of the string field to extract from. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractFieldFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractFieldFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractFieldFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractFieldFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_field"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[2], regexp_extract_field)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if !(3..=4).contains(&arg_types.len()) {
+            return plan_err!(
+                "regexp_extract_field requires 3 or 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (0, Struct(fields)) => Ok(Struct(utf8_string_fields(fields))),
+                (0, other) => plan_err!(
+                    "regexp_extract_field expects a struct as the first argument, got {other}"
+                ),
+                (1 | 2, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (1 | 2, other) => plan_err!(
+                    "regexp_extract_field argument {idx} must be a string, got {other}"
+                ),
+                (_, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (_, other) => plan_err!(
+                    "regexp_extract_field group index must be an integer, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns `fields` with the string fields as `Utf8` and the other fields
+/// unchanged.
+fn utf8_string_fields(fields: &Fields) -> Fields {
+    fields
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::LargeUtf8 | DataType::Utf8View => {
+                Arc::new(field.as_ref().clone().with_data_type(DataType::Utf8))
+            }
+            _ => Arc::clone(field),
+        })
+        .collect()
+}
+
+/// Extracts the capture group `args[3]`, 1 by default, from the first match
+/// of the pattern `args[2]` in the field named `args[1]` of each struct of
+/// `args[0]`.
+///
+/// The string fields of the structs are expected to be `Utf8`, the field
+/// names and the patterns to be `Utf8` and the group indices `Int64`. The
+/// patterns may be a single element array, in which case it is applied to
+/// every row.
+pub fn regexp_extract_field(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(3..=4).contains(&args_len) {
+        return exec_err!(
+            "regexp_extract_field was called with {args_len} arguments. It requires at least 3 and at most 4."
+        );
+    }
+    let Some(structs) = args[0].as_struct_opt() else {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_field",
+            args[0].data_type()
+        );
+    };
+    if args[1].data_type() != &DataType::Utf8 {
+        return exec_err!(
+            "Unsupported data type {:?} for the field name of function regexp_extract_field",
+            args[1].data_type()
+        );
+    }
+    if args[1].len() != structs.len() {
+        return exec_err!(
+            "regexp_extract_field field name array must be the same length as the struct array; got {} and {}",
+            args[1].len(),
+            structs.len()
+        );
+    }
+
+    let looked_up = lookup_fields(structs, args[1].as_string::<i32>())?;
+    // the remaining arguments are passed through, a NULL value yields NULL
+    let mut extract_args = vec![Arc::new(looked_up) as ArrayRef];
+    extract_args.extend(args[2..].iter().cloned());
+    regexp_extract(&extract_args)
+}
+
+/// Returns the value of the field of each struct named by the name of the
+/// same row, or NULL if there is no such field.
+fn lookup_fields(structs: &StructArray, names: &StringArray) -> Result<StringArray> {
+    let fields = structs.fields();
+    (0..structs.len())
+        .map(|i| {
+            if structs.is_null(i) || names.is_null(i) {
+                return Ok(None);
+            }
+            let name = names.value(i);
+            let Some(index) = fields.iter().position(|field| field.name() == name) else {
+                return Ok(None);
+            };
+            let Some(values) = structs.column(index).as_string_opt::<i32>() else {
+                return exec_err!(
+                    "regexp_extract_field field '{name}' must be a string, got {}",
+                    fields[index].data_type()
+                );
+            };
+            Ok(values.is_valid(i).then(|| values.value(i)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+    use arrow::datatypes::Field;
+
+    fn people() -> ArrayRef {
+        let fields = Fields::from(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("age", DataType::Int64, true),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec![
+                Some("user-42"),
+                Some("guest"),
+                None,
+                Some("user-7"),
+            ])),
+            Arc::new(StringArray::from(vec![
+                Some("Ada"),
+                Some("Grace"),
+                Some("Alan"),
+                Some("Edsger"),
+            ])),
+            Arc::new(Int64Array::from(vec![36, 85, 41, 72])),
+        ];
+        let nulls = Some(vec![true, true, true, false].into());
+        Arc::new(StructArray::new(fields, columns, nulls))
+    }
+
+    #[test]
+    fn test_regexp_extract_field() {
+        let result = regexp_extract_field(&[
+            people(),
+            Arc::new(StringArray::from(vec!["id"; 4])),
+            Arc::new(StringArray::from(vec![r"user-(\d+)"])),
+            Arc::new(Int64Array::from(vec![1; 4])),
+        ])
+        .unwrap();
+
+        // a present field that does not match yields an empty string like
+        // `regexp_extract`, NULL fields and NULL structs yield NULL
+        let expected = StringArray::from(vec![Some("42"), Some(""), None, None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_field_name_per_row() {
+        let names =
+            StringArray::from(vec![Some("name"), Some("missing"), None, Some("id")]);
+
+        // the group index defaults to 1
+        let result = regexp_extract_field(&[
+            people(),
+            Arc::new(names),
+            Arc::new(StringArray::from(vec![r"^(\w)"])),
+        ])
+        .unwrap();
+        let expected = StringArray::from(vec![Some("A"), None, None, None]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let err = regexp_extract_field(&[
+            people(),
+            Arc::new(StringArray::from(vec!["age"; 4])),
+            Arc::new(StringArray::from(vec![r"(\d)"])),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_extract_field field 'age' must be a string, got Int64"
+        );
+    }
+
+    #[test]
+    fn test_regexp_extract_field_coerce_types() {
+        use DataType::*;
+        let func = RegexpExtractFieldFunc::new();
+        let view_struct = Struct(Fields::from(vec![
+            Field::new("id", Utf8View, true),
+            Field::new("name", LargeUtf8, true),
+            Field::new("age", Int32, true),
+        ]));
+        let coerced = func
+            .coerce_types(&[view_struct, Utf8View, Utf8, Int32])
+            .unwrap();
+        let Struct(fields) = &coerced[0] else {
+            panic!("expected a struct, got {}", coerced[0]);
+        };
+        assert_eq!(fields[0].data_type(), &Utf8);
+        assert_eq!(fields[1].data_type(), &Utf8);
+        assert_eq!(fields[2].data_type(), &Int32);
+        assert_eq!(&coerced[1..], &[Utf8, Utf8, Int64]);
+
+        let err = func.coerce_types(&[Utf8, Utf8, Utf8]).unwrap_err();
+        assert!(
+            err.strip_backtrace()
+                .contains("expects a struct as the first argument"),
+            "unexpected error: {err}"
+        );
+    }
+}
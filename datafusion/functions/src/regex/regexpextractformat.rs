@@ -0,0 +1,339 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, RowPatterns};
+use arrow::array::{Array, ArrayRef, AsArray, StringArrayType, StringBuilder};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Assembles the capture groups of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match in a string into a template. Unlike `regexp_replace`, the parts of the string outside of the match are not part of the result. Returns an empty string if the regular expression does not match.",
+    syntax_example = "regexp_extract_format(str, regexp, template)",
+    sql_example = r#"```sql
+> select regexp_extract_format('date: 2024-03-15', '(\d+)-(\d+)-(\d+)', '$3/$2/$1');
++--------------------------------------------------------------------------------------------+
+| regexp_extract_format(Utf8("date: 2024-03-15"),Utf8("(\d+)-(\d+)-(\d+)"),Utf8("$3/$2/$1")) |
++--------------------------------------------------------------------------------------------+
+| 15/03/2024                                                                                 |
++--------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "template",
+        description = "Template of the result. `$n` and `${name}` refer to capture groups by index and name, `$$` is a literal `$`. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractFormatFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractFormatFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractFormatFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View, Utf8View, Utf8View]),
+                    TypeSignature::Exact(vec![Utf8, Utf8, Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8, LargeUtf8, LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractFormatFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_format"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_format)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Expands the template `args[2]` with the capture groups of the first match
+/// of a regular expression in each string of `args[0]`.
+///
+/// `args[1]` holds the patterns, either one per row or a single pattern that
+/// is applied to every row.
+pub fn regexp_extract_format(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if args_len != 3 {
+        return exec_err!(
+            "regexp_extract_format was called with {args_len} arguments. It requires 3."
+        );
+    }
+    regexp_extract_format_with_default(args, None)
+}
+
+/// Like [`regexp_extract_format`], but yields the value of `defaults` instead
+/// of an empty string for the rows without a match.
+///
+/// `defaults` is either one value per row or a single value that is applied
+/// to every row, and must have the type of the strings.
+pub(crate) fn regexp_extract_format_with_default(
+    args: &[ArrayRef],
+    defaults: Option<&ArrayRef>,
+) -> Result<ArrayRef> {
+    if let Some(defaults) = defaults.filter(|d| d.data_type() != args[0].data_type()) {
+        return exec_err!(
+            "Unsupported data type {:?} for the default of function regexp_extract_format_or",
+            defaults.data_type()
+        );
+    }
+
+    match (
+        args[0].data_type(),
+        args[1].data_type(),
+        args[2].data_type(),
+    ) {
+        (DataType::Utf8, DataType::Utf8, DataType::Utf8) => {
+            concrete_regexp_extract_format(
+                args[0].as_string::<i32>(),
+                args[1].as_string::<i32>(),
+                args[2].as_string::<i32>(),
+                defaults.map(|d| d.as_string::<i32>()),
+            )
+        }
+        (DataType::LargeUtf8, DataType::LargeUtf8, DataType::LargeUtf8) => {
+            concrete_regexp_extract_format(
+                args[0].as_string::<i64>(),
+                args[1].as_string::<i64>(),
+                args[2].as_string::<i64>(),
+                defaults.map(|d| d.as_string::<i64>()),
+            )
+        }
+        (DataType::Utf8View, DataType::Utf8View, DataType::Utf8View) => {
+            concrete_regexp_extract_format(
+                args[0].as_string_view(),
+                args[1].as_string_view(),
+                args[2].as_string_view(),
+                defaults.map(|d| d.as_string_view()),
+            )
+        }
+        other => {
+            exec_err!(
+                "Unsupported data type {other:?} for function regexp_extract_format"
+            )
+        }
+    }
+}
+
+fn concrete_regexp_extract_format<'a, S>(
+    values: S,
+    pattern_array: S,
+    template_array: S,
+    default_array: Option<S>,
+) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a>,
+{
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_format", pattern_array, values.len())?;
+    if let Some(default_array) = default_array
+        .as_ref()
+        .filter(|d| d.len() != 1 && d.len() != values.len())
+    {
+        return exec_err!(
+            "regexp_extract_format_or default array must be the same length as the values array; got {} and {}",
+            default_array.len(),
+            values.len()
+        );
+    }
+
+    let mut builder = StringBuilder::with_capacity(values.len(), 0);
+    // reused across rows to avoid allocating the expanded template for every row
+    let mut expanded = String::new();
+    for i in 0..values.len() {
+        if values.is_null(i) || patterns.is_null(i) || template_array.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+
+        let regex = patterns.get(i)?;
+        let matched = expand_template(
+            regex,
+            values.value(i),
+            template_array.value(i),
+            &mut expanded,
+        );
+        match &default_array {
+            Some(default_array) if !matched => {
+                let default_index = if default_array.len() == 1 { 0 } else { i };
+                if default_array.is_null(default_index) {
+                    builder.append_null();
+                } else {
+                    builder.append_value(default_array.value(default_index));
+                }
+            }
+            _ => builder.append_value(&expanded),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Replaces the content of `dst` with `template` expanded with the capture
+/// groups of the first match of `regex` in `value`, or clears it if there is
+/// no match. Returns whether `regex` matched.
+fn expand_template(regex: &Regex, value: &str, template: &str, dst: &mut String) -> bool {
+    dst.clear();
+    match regex.captures(value) {
+        Some(captures) => {
+            captures.expand(template, dst);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{LargeStringArray, StringArray, StringViewArray};
+
+    #[test]
+    fn test_regexp_extract_format() {
+        let values = StringArray::from(vec![
+            Some("date: 2024-03-15"),
+            Some("no date"),
+            None,
+            Some("1999-12-31 and 2000-01-01"),
+        ]);
+        let patterns = StringArray::from(vec![r"(\d+)-(\d+)-(\d+)"]);
+        let templates = StringArray::from(vec!["$3/$2/$1"; 4]);
+
+        let result = regexp_extract_format(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(templates),
+        ])
+        .unwrap();
+
+        let expected = StringArray::from(vec![
+            Some("15/03/2024"),
+            Some(""),
+            None,
+            Some("31/12/1999"),
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_format_templates() {
+        let values =
+            StringArray::from(vec!["John Smith", "key=value", "price: 42", "a-b", "x"]);
+        let patterns = StringArray::from(vec![
+            r"(?<first>\w+) (?<last>\w+)",
+            r"(\w+)=(\w+)",
+            r"(\d+)",
+            r"(\w)-(\w)",
+            r"(x)",
+        ]);
+        let templates = StringArray::from(vec![
+            // named groups
+            "${last}, ${first}",
+            // literal separators around indexed groups
+            "[$2 <- $1]",
+            // `$$` is a literal dollar sign
+            "$$$1.00",
+            // `${1}` delimits the group name from the following literal
+            "${1}x${2}",
+            // the template does not need to refer to any group
+            "constant",
+        ]);
+
+        let result = regexp_extract_format(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(templates),
+        ])
+        .unwrap();
+
+        let expected = StringArray::from(vec![
+            "Smith, John",
+            "[value <- key]",
+            "$42.00",
+            "axb",
+            "constant",
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_format_string_types() {
+        let expected = StringArray::from(vec!["b-a"]);
+
+        let result = regexp_extract_format(&[
+            Arc::new(LargeStringArray::from(vec!["a b"])),
+            Arc::new(LargeStringArray::from(vec![r"(\w) (\w)"])),
+            Arc::new(LargeStringArray::from(vec!["$2-$1"])),
+        ])
+        .unwrap();
+        assert_eq!(result.as_ref(), &expected);
+
+        let result = regexp_extract_format(&[
+            Arc::new(StringViewArray::from(vec!["a b"])),
+            Arc::new(StringViewArray::from(vec![r"(\w) (\w)"])),
+            Arc::new(StringViewArray::from(vec!["$2-$1"])),
+        ])
+        .unwrap();
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
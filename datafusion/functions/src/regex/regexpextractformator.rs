@@ -0,0 +1,202 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::invoke_materialized;
+use crate::regex::regexpextractformat::regexp_extract_format_with_default;
+use arrow::array::ArrayRef;
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Assembles the capture groups of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match in a string into a template like `regexp_extract_format`, but returns the given default instead of an empty string if the regular expression does not match.",
+    syntax_example = "regexp_extract_format_or(str, regexp, template, default)",
+    sql_example = r#"```sql
+> select regexp_extract_format_or('no date', '(\d+)-(\d+)-(\d+)', '$3/$2/$1', 'unknown');
++------------------------------------------------------------------------------------------------------+
+| regexp_extract_format_or(Utf8("no date"),Utf8("(\d+)-(\d+)-(\d+)"),Utf8("$3/$2/$1"),Utf8("unknown")) |
++------------------------------------------------------------------------------------------------------+
+| unknown                                                                                              |
++------------------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "template",
+        description = "Template of the result. `$n` and `${name}` refer to capture groups by index and name, `$$` is a literal `$`. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "default",
+        description = "String to return if the regular expression does not match. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractFormatOrFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractFormatOrFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractFormatOrFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View; 4]),
+                    TypeSignature::Exact(vec![Utf8; 4]),
+                    TypeSignature::Exact(vec![LargeUtf8; 4]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractFormatOrFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_format_or"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1, 3], regexp_extract_format_or)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Expands the template `args[2]` with the capture groups of the first match
+/// of `args[1]` in each string of `args[0]`, or yields the default `args[3]`
+/// if there is no match.
+///
+/// The pattern and the default may be single element arrays, in which case
+/// they are applied to every row.
+pub fn regexp_extract_format_or(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if args_len != 4 {
+        return exec_err!(
+            "regexp_extract_format_or was called with {args_len} arguments. It requires 4."
+        );
+    }
+    regexp_extract_format_with_default(&args[..3], Some(&args[3]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{StringArray, StringViewArray};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_regexp_extract_format_or() {
+        let values = StringArray::from(vec![
+            Some("date: 2024-03-15"),
+            Some("no date"),
+            None,
+            Some("2024-3-1"),
+        ]);
+        let patterns = StringArray::from(vec![r"(\d+)-(\d+)-(\d+)"]);
+        let templates = StringArray::from(vec!["$3/$2/$1"; 4]);
+        let defaults = StringArray::from(vec!["unknown"]);
+
+        let result = regexp_extract_format_or(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(templates),
+            Arc::new(defaults),
+        ])
+        .unwrap();
+
+        let expected = StringArray::from(vec![
+            Some("15/03/2024"),
+            Some("unknown"),
+            None,
+            Some("1/3/2024"),
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_format_or_default_array() {
+        let values = StringViewArray::from(vec!["a=1", "b", "c", "d="]);
+        let patterns = StringViewArray::from(vec![r"(\w)=(\d*)"]);
+        let templates = StringViewArray::from(vec!["$1:$2"; 4]);
+        // the default may be empty or NULL, and a match that expands to an
+        // empty group is not replaced by it
+        let defaults =
+            StringViewArray::from(vec![Some("x"), Some("missing"), None, Some("y")]);
+
+        let result = regexp_extract_format_or(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(templates),
+            Arc::new(defaults),
+        ])
+        .unwrap();
+
+        let expected =
+            StringArray::from(vec![Some("a:1"), Some("missing"), None, Some("d:")]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_format_or_default_type() {
+        let err = regexp_extract_format_or(&[
+            Arc::new(StringArray::from(vec!["a"])),
+            Arc::new(StringArray::from(vec!["(a)"])),
+            Arc::new(StringArray::from(vec!["$1"])),
+            Arc::new(StringViewArray::from(vec!["x"])),
+        ])
+        .expect_err("mismatched default type should fail");
+        assert!(
+            err.strip_backtrace()
+                .starts_with("Execution error: Unsupported data type Utf8View for the default of function regexp_extract_format_or"),
+            "unexpected error: {err}"
+        );
+    }
+}
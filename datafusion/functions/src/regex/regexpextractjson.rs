@@ -0,0 +1,314 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, RowPatterns};
+use arrow::array::{Array, ArrayRef, AsArray, BooleanArray, StringBuilder};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::fmt::Write;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the named capture groups of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match from a string as a JSON object string, with a member for each named group in the order of the regular expression. Groups that did not participate in the match are `null`. Strings without a match yield NULL, or `{}` if `empty_object` is true.",
+    syntax_example = "regexp_extract_json(str, regexp[, empty_object])",
+    sql_example = r#"```sql
+> select regexp_extract_json('user=ann id=42', 'user=(?<user>\w+) id=(?<id>\d+)');
++-------------------------------------------------------------------------------------+
+| regexp_extract_json(Utf8("user=ann id=42"),Utf8("user=(?<user>\w+) id=(?<id>\d+)")) |
++-------------------------------------------------------------------------------------+
+| {"user":"ann","id":"42"}                                                            |
++-------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "empty_object",
+        description = "Whether a string without a match yields an empty JSON object instead of NULL. Defaults to false. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractJsonFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractJsonFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractJsonFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractJsonFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_json"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_json)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if !(2..=3).contains(&arg_types.len()) {
+            return plan_err!(
+                "regexp_extract_json requires 2 or 3 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (2, Boolean | Null) => Ok(Boolean),
+                (2, other) => plan_err!(
+                    "regexp_extract_json expects a boolean as the third argument, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_json argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Extracts the named capture groups of the first match of the pattern
+/// `args[1]` in each string of `args[0]` as a JSON object string.
+///
+/// The pattern may be a single element array, in which case it is applied to
+/// every row. The strings are expected to be `Utf8` and the optional
+/// `args[2]`, whether a string without a match yields `{}` instead of NULL,
+/// `Boolean`. A NULL `args[2]` is taken to be false.
+pub fn regexp_extract_json(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if !(2..=3).contains(&args.len()) {
+        return exec_err!(
+            "regexp_extract_json was called with {} arguments. It requires 2 or 3.",
+            args.len()
+        );
+    }
+    if let Some(arg) = args[..2]
+        .iter()
+        .find(|arg| arg.data_type() != &DataType::Utf8)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_json",
+            arg.data_type()
+        );
+    }
+    if let Some(arg) = args
+        .get(2)
+        .filter(|arg| arg.data_type() != &DataType::Boolean)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for the empty_object argument of function regexp_extract_json",
+            arg.data_type()
+        );
+    }
+
+    let values = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let empty_object_array = args.get(2).map(|arg| arg.as_boolean());
+    if pattern_array.len() != 1 && pattern_array.len() != values.len() {
+        return exec_err!(
+            "regexp_extract_json pattern array must be the same length as the values array; got {} and {}",
+            pattern_array.len(),
+            values.len()
+        );
+    }
+
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_json", pattern_array, values.len())?;
+
+    let mut builder = StringBuilder::with_capacity(values.len(), 0);
+    let mut json = String::new();
+    for i in 0..values.len() {
+        if values.is_null(i) || patterns.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+
+        let regex = patterns.get(i)?;
+        if write_named_groups(&mut json, regex, values.value(i)) {
+            builder.append_value(&json);
+        } else if empty_object_at(empty_object_array, i) {
+            builder.append_value("{}");
+        } else {
+            builder.append_null();
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Returns whether a string without a match yields `{}` in row `i`.
+fn empty_object_at(empty_object_array: Option<&BooleanArray>, i: usize) -> bool {
+    empty_object_array.is_some_and(|array| {
+        let index = if array.len() == 1 { 0 } else { i };
+        array.is_valid(index) && array.value(index)
+    })
+}
+
+/// Writes the named groups of the first match of `regex` in `value` to `json`
+/// as a JSON object, returning false without writing if there is no match.
+fn write_named_groups(json: &mut String, regex: &Regex, value: &str) -> bool {
+    let Some(captures) = regex.captures(value) else {
+        return false;
+    };
+    json.clear();
+    json.push('{');
+    let names = regex
+        .capture_names()
+        .enumerate()
+        .filter_map(|(group_index, name)| Some((group_index, name?)));
+    for (member_index, (group_index, name)) in names.enumerate() {
+        if member_index > 0 {
+            json.push(',');
+        }
+        write_json_string(json, name);
+        json.push(':');
+        match captures.get(group_index) {
+            Some(group) => write_json_string(json, group.as_str()),
+            None => json.push_str("null"),
+        }
+    }
+    json.push('}');
+    true
+}
+
+/// Writes `value` to `json` as a JSON string, escaping the quotes, the
+/// backslashes and the control characters.
+fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\u{8}' => json.push_str("\\b"),
+            '\u{c}' => json.push_str("\\f"),
+            c if c < ' ' => {
+                write!(json, "\\u{:04x}", c as u32).expect("writing to a String")
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+
+    #[test]
+    fn test_regexp_extract_json() {
+        let values = StringArray::from(vec![
+            Some("user=ann id=42"),
+            Some("user=bob"),
+            Some("no user"),
+            None,
+        ]);
+        let patterns = StringArray::from(vec![r"user=(?<user>\w+)(?: id=(?<id>\d+))?"]);
+
+        let result =
+            regexp_extract_json(&[Arc::new(values.clone()), Arc::new(patterns.clone())])
+                .unwrap();
+        // the groups that did not participate are null members
+        let expected = StringArray::from(vec![
+            Some(r#"{"user":"ann","id":"42"}"#),
+            Some(r#"{"user":"bob","id":null}"#),
+            None,
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let empty_object = BooleanArray::from(vec![true]);
+        let result = regexp_extract_json(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(empty_object),
+        ])
+        .unwrap();
+        // a NULL string is still NULL
+        let expected = StringArray::from(vec![
+            Some(r#"{"user":"ann","id":"42"}"#),
+            Some(r#"{"user":"bob","id":null}"#),
+            Some("{}"),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_json_escaping() {
+        let values = StringArray::from(vec![
+            r#"msg="say \"hi\"" path=C:\temp"#,
+            "msg=\"line\none\ttab\u{1}\" path=/é",
+        ]);
+        let patterns =
+            StringArray::from(vec![r#"(?s)msg="(?<msg>.*)" path=(?<path>\S+)"#]);
+
+        let result =
+            regexp_extract_json(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+        let expected = StringArray::from(vec![
+            r#"{"msg":"say \\\"hi\\\"","path":"C:\\temp"}"#,
+            r#"{"msg":"line\none\ttab\u0001","path":"/é"}"#,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
@@ -0,0 +1,289 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{
+    group_index_at, invoke_materialized, validate_group_index, RowPatterns,
+};
+use arrow::array::{Array, ArrayRef, AsArray, StringArray};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the specified capture group of the last [regular expression](https://docs.rs/regex/latest/regex/#syntax) match from a string, for example the last component of a path. Like `regexp_extract`, returns an empty string if the regular expression does not match or the capture group did not participate in the match. The matches do not overlap, so the last match may start before the last position the regular expression would match at. To extract a suffix, such as a file extension, anchor the regular expression at the end with `$`, which also lets the match be found without trying every earlier position.",
+    syntax_example = "regexp_extract_last(str, regexp[, idx])",
+    sql_example = r#"```sql
+> select regexp_extract_last('/usr/local/bin/tool', '/([^/]+)', 1);
++----------------------------------------------------------------------------+
+| regexp_extract_last(Utf8("/usr/local/bin/tool"),Utf8("/([^/]+)"),Int64(1)) |
++----------------------------------------------------------------------------+
+| tool                                                                       |
++----------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractLastFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractLastFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractLastFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractLastFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_last"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_last)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if !(2..=3).contains(&arg_types.len()) {
+            return plan_err!(
+                "regexp_extract_last requires 2 or 3 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (2, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (2, other) => plan_err!(
+                    "regexp_extract_last group index must be an integer, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_last argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Extracts the capture group `args[2]`, 1 by default, of the last match of
+/// `args[1]` from each string of `args[0]`.
+///
+/// The pattern may be a single element array, in which case it is applied to
+/// every row. The strings are expected to be `Utf8` and the group indices
+/// `Int64`.
+pub fn regexp_extract_last(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(2..=3).contains(&args_len) {
+        return exec_err!(
+            "regexp_extract_last was called with {args_len} arguments. It requires at least 2 and at most 3."
+        );
+    }
+    if let Some(arg) = args[..2]
+        .iter()
+        .find(|arg| arg.data_type() != &DataType::Utf8)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_last",
+            arg.data_type()
+        );
+    }
+    let group_index_array = match args.get(2) {
+        Some(group_index_array) => match group_index_array.as_primitive_opt() {
+            Some(group_index_array) => Some(group_index_array),
+            None => {
+                return exec_err!(
+                    "Unsupported data type {:?} for the group index of function regexp_extract_last",
+                    group_index_array.data_type()
+                )
+            }
+        },
+        None => None,
+    };
+
+    let values = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_last", pattern_array, values.len())?;
+
+    let result = (0..values.len())
+        .map(|i| {
+            let group_index = match group_index_at(group_index_array, i) {
+                Some(group_index) if values.is_valid(i) && patterns.is_valid(i) => {
+                    group_index
+                }
+                _ => return Ok(None),
+            };
+            let regex = patterns.get(i)?;
+            last_group(regex, values.value(i), group_index).map(Some)
+        })
+        .collect::<Result<StringArray>>()?;
+    Ok(Arc::new(result))
+}
+
+/// Returns the capture group `group_index` of the last match of `regex` in
+/// `value`, or the empty string if there is no match or the group did not
+/// participate in the match.
+fn last_group<'h>(regex: &Regex, value: &'h str, group_index: i64) -> Result<&'h str> {
+    let group_index = validate_group_index(regex, group_index)?;
+    // finding the matches does not track the capture groups, so they are only
+    // resolved for the last match
+    let Some(last) = regex.find_iter(value).last() else {
+        return Ok("");
+    };
+    if group_index == 0 {
+        return Ok(last.as_str());
+    }
+    // searching from the start of the last match finds the same match again,
+    // as looking around it still sees the preceding text
+    Ok(regex
+        .captures_at(value, last.start())
+        .and_then(|captures| captures.get(group_index))
+        .map_or("", |m| m.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    #[test]
+    fn test_regexp_extract_last_path_component() {
+        let values = StringArray::from(vec![
+            Some("/usr/local/bin/tool"),
+            Some("relative/dir/"),
+            Some("file.tar.gz"),
+            Some(""),
+            None,
+        ]);
+        let patterns = StringArray::from(vec![r"([^/]+)/?"]);
+
+        let result =
+            regexp_extract_last(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+        // a trailing slash is skipped by the optional `/?`
+        let expected = StringArray::from(vec![
+            Some("tool"),
+            Some("dir"),
+            Some("file.tar.gz"),
+            Some(""),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_last_anchored_suffix() {
+        let values =
+            StringArray::from(vec!["archive.tar.gz", "photo.JPG", "Makefile", ".bashrc"]);
+        let group_indices = Int64Array::from(vec![1, 1, 1, 0]);
+
+        // anchoring the extension at the end and the unanchored last match of
+        // a dot and a name agree, except for names without an extension
+        let anchored = regexp_extract_last(&[
+            Arc::new(values.clone()),
+            Arc::new(StringArray::from(vec![r"\.([^.]+)$"])),
+            Arc::new(group_indices.clone()),
+        ])
+        .unwrap();
+        let expected = StringArray::from(vec!["gz", "JPG", "", ".bashrc"]);
+        assert_eq!(anchored.as_ref(), &expected);
+
+        let unanchored = regexp_extract_last(&[
+            Arc::new(values),
+            Arc::new(StringArray::from(vec![r"\.([^.]+)"])),
+            Arc::new(group_indices),
+        ])
+        .unwrap();
+        assert_eq!(unanchored.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_last_look_around() {
+        // the last match is found again from its start, where `\b` still sees
+        // the preceding character
+        let values = StringArray::from(vec!["ab1 cd2 ef3x", "k=1,k=22"]);
+        let patterns = StringArray::from(vec![r"\b([a-z]+)(\d)\b", r"k=(\d+)"]);
+
+        let result =
+            regexp_extract_last(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+        let expected = StringArray::from(vec!["cd", "22"]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_last_invalid_group_index() {
+        let err = regexp_extract_last(&[
+            Arc::new(StringArray::from(vec!["a"])),
+            Arc::new(StringArray::from(vec!["(a)"])),
+            Arc::new(Int64Array::from(vec![2])),
+        ])
+        .expect_err("out of range group index should fail");
+        assert!(
+            err.strip_backtrace()
+                .starts_with("Execution error: Regex group index 2 is out of range"),
+            "unexpected error: {err}"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{
+    extract_group, group_index_at, invoke_materialized, RowPatterns,
+};
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns the length in characters of the specified capture group of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match in a string, like `character_length(regexp_extract(str, regexp, idx))` but without extracting the group. Returns 0 if the regular expression does not match or the capture group did not participate in the match. The length counts Unicode code points, not bytes, so a capture such as `Zoë` has a length of 3.",
+    syntax_example = "regexp_extract_len(str, regexp[, idx])",
+    sql_example = r#"```sql
+> select regexp_extract_len('name=Zoë;', 'name=([^;]*)', 1);
++---------------------------------------------------------------------+
+| regexp_extract_len(Utf8("name=Zoë;"),Utf8("name=([^;]*)"),Int64(1)) |
++---------------------------------------------------------------------+
+| 3                                                                   |
++---------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to measure. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractLenFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractLenFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractLenFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractLenFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_len"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Int64)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_len)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if !(2..=3).contains(&arg_types.len()) {
+            return plan_err!(
+                "regexp_extract_len requires 2 or 3 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (2, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (2, other) => plan_err!(
+                    "regexp_extract_len group index must be an integer, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_len argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the length in characters of the capture group `args[2]`, 1 by
+/// default, of the first match of `args[1]` in each string of `args[0]`.
+///
+/// The pattern may be a single element array, in which case it is applied to
+/// every row. The strings are expected to be `Utf8` and the group indices
+/// `Int64`.
+pub fn regexp_extract_len(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(2..=3).contains(&args_len) {
+        return exec_err!(
+            "regexp_extract_len was called with {args_len} arguments. It requires at least 2 and at most 3."
+        );
+    }
+    if let Some(arg) = args[..2]
+        .iter()
+        .find(|arg| arg.data_type() != &DataType::Utf8)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_len",
+            arg.data_type()
+        );
+    }
+    let group_index_array = match args.get(2) {
+        Some(group_index_array) => match group_index_array.as_primitive_opt() {
+            Some(group_index_array) => Some(group_index_array),
+            None => {
+                return exec_err!(
+                    "Unsupported data type {:?} for the group index of function regexp_extract_len",
+                    group_index_array.data_type()
+                )
+            }
+        },
+        None => None,
+    };
+
+    let values = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_len", pattern_array, values.len())?;
+
+    let result = (0..values.len())
+        .map(|i| {
+            let group_index = match group_index_at(group_index_array, i) {
+                Some(group_index) if values.is_valid(i) && patterns.is_valid(i) => {
+                    group_index
+                }
+                _ => return Ok(None),
+            };
+            let regex = patterns.get(i)?;
+            // the group borrows from the value, so measuring it copies nothing
+            let group = extract_group(regex, values.value(i), group_index)?;
+            Ok(Some(group.chars().count() as i64))
+        })
+        .collect::<Result<Int64Array>>()?;
+    Ok(Arc::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+
+    #[test]
+    fn test_regexp_extract_len() {
+        let values = StringArray::from(vec![
+            Some("name=Zoë;"),
+            Some("name=Ada;"),
+            Some("name=;"),
+            Some("id=1"),
+            Some("name=東京;"),
+            None,
+        ]);
+        let patterns = StringArray::from(vec!["name=([^;]*)"]);
+
+        let result = regexp_extract_len(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+        // the characters are counted, `Zoë` is 4 bytes and `東京` is 6 bytes
+        let expected =
+            Int64Array::from(vec![Some(3), Some(3), Some(0), Some(0), Some(2), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_len_group_index() {
+        let values = StringArray::from(vec!["été-42", "été-42", "été-42", "été-42"]);
+        let patterns = StringArray::from(vec![r"(\w+)-(\d+)|(x)"]);
+        let group_indices = Int64Array::from(vec![Some(0), Some(1), Some(3), None]);
+
+        let result = regexp_extract_len(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+        ])
+        .unwrap();
+        // a group that did not participate in the match has a length of 0
+        let expected = Int64Array::from(vec![Some(6), Some(3), Some(0), None]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let err = regexp_extract_len(&[
+            Arc::new(StringArray::from(vec!["a"])),
+            Arc::new(StringArray::from(vec!["(a)"])),
+            Arc::new(Int64Array::from(vec![2])),
+        ])
+        .expect_err("out of range group index should fail");
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Regex group index 2 is out of range, the pattern '(a)' has 1 capture groups"
+        );
+    }
+}
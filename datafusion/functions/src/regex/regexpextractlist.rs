@@ -0,0 +1,316 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, regexp_extract};
+use arrow::array::{Array, ArrayRef, AsArray, ListArray, UInt32Array};
+use arrow::buffer::OffsetBuffer;
+use arrow::compute::take;
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match of the specified capture group from every string of a list, like `regexp_extract` applied to each element. The result has the same shape as the input list.",
+    syntax_example = "regexp_extract_list(array, regexp[, idx])",
+    sql_example = r#"```sql
+> select regexp_extract_list(['a-1', 'b-2', 'c'], '(\w)-(\d)', 2);
++-----------------------------------------------------------------------------------------------+
+| regexp_extract_list(make_array(Utf8("a-1"),Utf8("b-2"),Utf8("c")),Utf8("(\w)-(\d)"),Int64(2)) |
++-----------------------------------------------------------------------------------------------+
+| [1, 2, ]                                                                                      |
++-----------------------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "array",
+        description = "List of strings to extract from. Can be a constant, column, or function, and any combination of array operators."
+    ),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractListFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractListFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractListFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractListFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_list"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_list_type())
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_list)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if !(2..=3).contains(&arg_types.len()) {
+            return plan_err!(
+                "regexp_extract_list requires 2 or 3 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        let list_type = match &arg_types[0] {
+            List(field) | LargeList(field) | FixedSizeList(field, _)
+                if matches!(field.data_type(), Utf8 | LargeUtf8 | Utf8View | Null) =>
+            {
+                string_list_type()
+            }
+            Null => string_list_type(),
+            other => {
+                return plan_err!(
+                    "regexp_extract_list expects a list of strings as the first argument, got {other}"
+                )
+            }
+        };
+        let pattern_type = match &arg_types[1] {
+            Utf8 | LargeUtf8 | Utf8View | Null => Utf8,
+            other => {
+                return plan_err!(
+                    "regexp_extract_list pattern must be a string, got {other}"
+                )
+            }
+        };
+
+        let mut coerced = vec![list_type, pattern_type];
+        match arg_types.get(2) {
+            Some(Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                coerced.push(Int64)
+            }
+            Some(other) => {
+                return plan_err!(
+                    "regexp_extract_list group index must be an integer, got {other}"
+                )
+            }
+            None => {}
+        }
+        Ok(coerced)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The `List<Utf8>` type of the argument and the result of
+/// `regexp_extract_list`.
+fn string_list_type() -> DataType {
+    DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+}
+
+/// Extracts the specified capture group of the first match of a regular
+/// expression from every string of each list of `args[0]`.
+///
+/// `args[1]` holds the patterns, either one per row or a single pattern that
+/// is applied to every row. The optional `args[2]` holds the capture group
+/// index, which defaults to 1. The lists are expected to be `List<Utf8>`.
+pub fn regexp_extract_list(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(2..=3).contains(&args_len) {
+        return exec_err!(
+            "regexp_extract_list was called with {args_len} arguments. It requires at least 2 and at most 3."
+        );
+    }
+    let DataType::List(field) = args[0].data_type() else {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_list",
+            args[0].data_type()
+        );
+    };
+    if field.data_type() != &DataType::Utf8 {
+        return exec_err!(
+            "Unsupported list element type {:?} for function regexp_extract_list",
+            field.data_type()
+        );
+    }
+
+    let lists = args[0].as_list::<i32>();
+    let pattern_array = &args[1];
+    if pattern_array.len() != 1 && pattern_array.len() != lists.len() {
+        return exec_err!(
+            "regexp_extract_list pattern array must be the same length as the list array; got {} and {}",
+            pattern_array.len(),
+            lists.len()
+        );
+    }
+
+    // only extract from the elements that belong to a list
+    let offsets = lists.offsets();
+    let first_offset = offsets[0] as usize;
+    let last_offset = offsets[offsets.len() - 1] as usize;
+    let elements = lists
+        .values()
+        .slice(first_offset, last_offset - first_offset);
+    let element_offsets =
+        OffsetBuffer::new(offsets.iter().map(|o| o - offsets[0]).collect());
+
+    // the row of every element, to repeat the per row arguments for each of
+    // its elements
+    let element_rows =
+        UInt32Array::from_iter_values(element_offsets.windows(2).enumerate().flat_map(
+            |(row, w)| std::iter::repeat_n(row as u32, (w[1] - w[0]) as usize),
+        ));
+    let mut element_args = vec![elements];
+    element_args.push(if pattern_array.len() == 1 {
+        Arc::clone(pattern_array)
+    } else {
+        take(pattern_array, &element_rows, None)?
+    });
+    if let Some(group_index_array) = args.get(2) {
+        element_args.push(take(group_index_array, &element_rows, None)?);
+    }
+
+    let extracted = regexp_extract(&element_args)?;
+    Ok(Arc::new(ListArray::try_new(
+        Arc::new(Field::new_list_field(DataType::Utf8, true)),
+        element_offsets,
+        extracted,
+        lists.nulls().cloned(),
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, ListBuilder, StringArray, StringBuilder};
+
+    fn string_list(rows: Vec<Option<Vec<Option<&str>>>>) -> ListArray {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for row in rows {
+            match row {
+                Some(values) => {
+                    values
+                        .into_iter()
+                        .for_each(|v| builder.values().append_option(v));
+                    builder.append(true);
+                }
+                None => builder.append_null(),
+            }
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_regexp_extract_list() {
+        let lists = string_list(vec![
+            Some(vec![Some("a-1"), Some("b-2"), Some("c-3")]),
+            Some(vec![]),
+            None,
+            Some(vec![Some("d-4"), None, Some("e")]),
+            Some(vec![Some("f-6")]),
+        ]);
+        let patterns = StringArray::from(vec![r"(\w)-(\d)"]);
+        let group_indices =
+            Int64Array::from(vec![Some(2), Some(2), Some(2), Some(1), None]);
+
+        let result = regexp_extract_list(&[
+            Arc::new(lists),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+        ])
+        .unwrap();
+
+        let expected = string_list(vec![
+            Some(vec![Some("1"), Some("2"), Some("3")]),
+            Some(vec![]),
+            None,
+            Some(vec![Some("d"), None, Some("")]),
+            // a NULL group index yields NULL elements
+            Some(vec![None]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_list_array_pattern() {
+        let lists = string_list(vec![
+            Some(vec![Some("ab"), Some("cd")]),
+            Some(vec![Some("12"), Some("x3"), Some("45")]),
+        ]);
+        let patterns = StringArray::from(vec![r"[a-z](\w)", r"(\d)\d"]);
+
+        let result = regexp_extract_list(&[Arc::new(lists), Arc::new(patterns)]).unwrap();
+
+        let expected = string_list(vec![
+            Some(vec![Some("b"), Some("d")]),
+            Some(vec![Some("1"), Some(""), Some("4")]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_list_sliced() {
+        let lists = string_list(vec![
+            Some(vec![Some("a1")]),
+            Some(vec![Some("b2"), Some("c3")]),
+            Some(vec![Some("d4")]),
+        ])
+        .slice(1, 2);
+        let patterns = StringArray::from(vec![r"\w(\d)"]);
+
+        let result = regexp_extract_list(&[Arc::new(lists), Arc::new(patterns)]).unwrap();
+
+        let expected = string_list(vec![
+            Some(vec![Some("2"), Some("3")]),
+            Some(vec![Some("4")]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+}
@@ -0,0 +1,274 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, RowPatterns};
+use arrow::array::{
+    Array, ArrayRef, AsArray, MapBuilder, StringArrayType, StringBuilder,
+};
+use arrow::datatypes::{DataType, Field, Fields};
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns a map from the capture group names of a [regular expression](https://docs.rs/regex/latest/regex/#syntax) to the values they captured in the first match in a string. Unnamed capture groups use the key `c` followed by the group index, for example `c2`, and groups that did not participate in the match map to NULL. Returns an empty map if the regular expression does not match.",
+    syntax_example = "regexp_extract_map(str, regexp)",
+    sql_example = r#"```sql
+> select regexp_extract_map('2024-03-15', '(?<year>\d+)-(\d+)-(?<day>\d+)');
++-------------------------------------------------------------------------------+
+| regexp_extract_map(Utf8("2024-03-15"),Utf8("(?<year>\d+)-(\d+)-(?<day>\d+)")) |
++-------------------------------------------------------------------------------+
+| {year: 2024, c2: 03, day: 15}                                                 |
++-------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractMapFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractMapFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractMapFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View, Utf8View]),
+                    TypeSignature::Exact(vec![Utf8, Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8, LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractMapFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_map"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(map_type())
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_map)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The `Map<Utf8, Utf8>` type returned by `regexp_extract_map` and in the lists
+/// of `regexp_extract_all_named`, matching the
+/// type built by the default [`MapBuilder`].
+pub(crate) fn map_type() -> DataType {
+    let entries = Fields::from(vec![
+        Field::new("keys", DataType::Utf8, false),
+        Field::new("values", DataType::Utf8, true),
+    ]);
+    DataType::Map(
+        Arc::new(Field::new("entries", DataType::Struct(entries), false)),
+        false,
+    )
+}
+
+/// Builds a map from the capture group names to the captured values of the
+/// first match of a regular expression in each string of `args[0]`.
+///
+/// `args[1]` holds the patterns, either one per row or a single pattern that
+/// is applied to every row.
+pub fn regexp_extract_map(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if args_len != 2 {
+        return exec_err!(
+            "regexp_extract_map was called with {args_len} arguments. It requires 2."
+        );
+    }
+
+    match (args[0].data_type(), args[1].data_type()) {
+        (DataType::Utf8, DataType::Utf8) => concrete_regexp_extract_map(
+            args[0].as_string::<i32>(),
+            args[1].as_string::<i32>(),
+        ),
+        (DataType::LargeUtf8, DataType::LargeUtf8) => concrete_regexp_extract_map(
+            args[0].as_string::<i64>(),
+            args[1].as_string::<i64>(),
+        ),
+        (DataType::Utf8View, DataType::Utf8View) => concrete_regexp_extract_map(
+            args[0].as_string_view(),
+            args[1].as_string_view(),
+        ),
+        other => {
+            exec_err!("Unsupported data type {other:?} for function regexp_extract_map")
+        }
+    }
+}
+
+fn concrete_regexp_extract_map<'a, S>(values: S, pattern_array: S) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a>,
+{
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_map", pattern_array, values.len())?;
+
+    let mut builder = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+    for i in 0..values.len() {
+        if values.is_null(i) || patterns.is_null(i) {
+            builder.append(false)?;
+            continue;
+        }
+
+        let regex = patterns.get(i)?;
+        append_named_groups(&mut builder, regex, values.value(i));
+        builder.append(true)?;
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Appends an entry for every capture group of `regex` to the current map of
+/// `builder` if `regex` matches `value`.
+fn append_named_groups(
+    builder: &mut MapBuilder<StringBuilder, StringBuilder>,
+    regex: &Regex,
+    value: &str,
+) {
+    let Some(captures) = regex.captures(value) else {
+        return;
+    };
+
+    // group 0 is the entire match and has no name
+    for (group_index, name) in regex.capture_names().enumerate().skip(1) {
+        match name {
+            Some(name) => builder.keys().append_value(name),
+            None => builder.keys().append_value(format!("c{group_index}")),
+        }
+        builder
+            .values()
+            .append_option(captures.get(group_index).map(|m| m.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{MapArray, StringArray, StringViewArray};
+
+    /// The entries of a map row
+    type Entries<'a> = Vec<(&'a str, Option<&'a str>)>;
+
+    fn string_map(rows: Vec<Option<Entries>>) -> MapArray {
+        let mut builder =
+            MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        for row in rows {
+            match row {
+                Some(entries) => {
+                    for (key, value) in entries {
+                        builder.keys().append_value(key);
+                        builder.values().append_option(value);
+                    }
+                    builder.append(true).unwrap();
+                }
+                None => builder.append(false).unwrap(),
+            }
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_regexp_extract_map() {
+        let values = StringArray::from(vec![
+            Some("2024-03-15"),
+            Some("2024-03"),
+            Some("no date"),
+            None,
+        ]);
+        let patterns = StringArray::from(vec![r"(?<year>\d+)-(\d+)(?:-(?<day>\d+))?"]);
+
+        let result = regexp_extract_map(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+
+        let expected = string_map(vec![
+            Some(vec![
+                ("year", Some("2024")),
+                ("c2", Some("03")),
+                ("day", Some("15")),
+            ]),
+            // the optional day group did not participate in the match
+            Some(vec![
+                ("year", Some("2024")),
+                ("c2", Some("03")),
+                ("day", None),
+            ]),
+            Some(vec![]),
+            None,
+        ]);
+        assert_eq!(result.as_map(), &expected);
+        assert_eq!(result.data_type(), &map_type());
+    }
+
+    #[test]
+    fn test_regexp_extract_map_array_pattern() {
+        let values = StringViewArray::from(vec!["a=1", "b:2", "c"]);
+        let patterns = StringViewArray::from(vec![
+            r"(?<key>\w)=(?<value>\d)",
+            r"(\w):(\d)",
+            r"(?<key>\w)",
+        ]);
+
+        let result = regexp_extract_map(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+
+        let expected = string_map(vec![
+            Some(vec![("key", Some("a")), ("value", Some("1"))]),
+            Some(vec![("c1", Some("b")), ("c2", Some("2"))]),
+            Some(vec![("key", Some("c"))]),
+        ]);
+        assert_eq!(result.as_map(), &expected);
+    }
+}
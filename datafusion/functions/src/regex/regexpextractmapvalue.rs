@@ -0,0 +1,375 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, regexp_extract};
+use arrow::array::{Array, ArrayRef, AsArray, MapArray, StringArray};
+use arrow::datatypes::{DataType, Field, Fields};
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Looks up a key in a map of strings and extracts the specified capture group of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match from the value of the key. Returns NULL if the map does not contain the key or its value is NULL, and otherwise behaves like `regexp_extract` on the value.",
+    syntax_example = "regexp_extract_map_value(map, key, regexp[, idx])",
+    sql_example = r#"```sql
+> select regexp_extract_map_value(MAP {'id': 'user-42', 'name': 'Ada'}, 'id', 'user-(\d+)', 1);
++---------------------------------------------------------------------------------------------------------------------------------------------------+
+| regexp_extract_map_value(map(make_array(Utf8("id"),Utf8("name")),make_array(Utf8("user-42"),Utf8("Ada"))),Utf8("id"),Utf8("user-(\d+)"),Int64(1)) |
++---------------------------------------------------------------------------------------------------------------------------------------------------+
+| 42                                                                                                                                                |
++---------------------------------------------------------------------------------------------------------------------------------------------------+
+```"#,
+    argument(
+        name = "map",
+        description = "Map with string keys and values. Can be a constant, column, or function, and any combination of map operators."
+    ),
+    argument(
+        name = "key",
+        description = "Key of the value to extract from. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Optional index of the capture group to extract. Group 0 is the entire match. Defaults to 1. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractMapValueFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractMapValueFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractMapValueFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractMapValueFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_map_value"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[2], regexp_extract_map_value)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if !(3..=4).contains(&arg_types.len()) {
+            return plan_err!(
+                "regexp_extract_map_value requires 3 or 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (0, Map(entries, sorted)) => match string_map_type(entries, *sorted) {
+                    Some(map_type) => Ok(map_type),
+                    None => plan_err!(
+                        "regexp_extract_map_value expects a map with string keys and values as the first argument, got {data_type}"
+                    ),
+                },
+                (0, other) => plan_err!(
+                    "regexp_extract_map_value expects a map as the first argument, got {other}"
+                ),
+                (1 | 2, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (1 | 2, other) => plan_err!(
+                    "regexp_extract_map_value argument {idx} must be a string, got {other}"
+                ),
+                (_, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (_, other) => plan_err!(
+                    "regexp_extract_map_value group index must be an integer, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the map type with the entries of `entries` and `Utf8` keys and
+/// values, or `None` if the keys or the values are not strings.
+fn string_map_type(entries: &Arc<Field>, sorted: bool) -> Option<DataType> {
+    let DataType::Struct(fields) = entries.data_type() else {
+        return None;
+    };
+    let [key, value] = fields.iter().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let is_string = |field: &Field| {
+        matches!(
+            field.data_type(),
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+        )
+    };
+    if !is_string(key) || !is_string(value) {
+        return None;
+    }
+
+    let fields = Fields::from(vec![
+        key.as_ref().clone().with_data_type(DataType::Utf8),
+        value.as_ref().clone().with_data_type(DataType::Utf8),
+    ]);
+    let entries = entries
+        .as_ref()
+        .clone()
+        .with_data_type(DataType::Struct(fields));
+    Some(DataType::Map(Arc::new(entries), sorted))
+}
+
+/// Extracts the capture group `args[3]`, 1 by default, from the first match
+/// of the pattern `args[2]` in the value of the key `args[1]` in each map of
+/// `args[0]`.
+///
+/// The maps are expected to have `Utf8` keys and values, the keys and the
+/// patterns to be `Utf8` and the group indices `Int64`. The patterns may be a
+/// single element array, in which case it is applied to every row.
+pub fn regexp_extract_map_value(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if !(3..=4).contains(&args_len) {
+        return exec_err!(
+            "regexp_extract_map_value was called with {args_len} arguments. It requires at least 3 and at most 4."
+        );
+    }
+    let Some(maps) = args[0].as_map_opt() else {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_map_value",
+            args[0].data_type()
+        );
+    };
+    if maps.keys().data_type() != &DataType::Utf8
+        || maps.values().data_type() != &DataType::Utf8
+    {
+        return exec_err!(
+            "regexp_extract_map_value expects a map with Utf8 keys and values, got {:?}",
+            args[0].data_type()
+        );
+    }
+    if args[1].data_type() != &DataType::Utf8 {
+        return exec_err!(
+            "Unsupported data type {:?} for the key of function regexp_extract_map_value",
+            args[1].data_type()
+        );
+    }
+    if args[1].len() != maps.len() {
+        return exec_err!(
+            "regexp_extract_map_value key array must be the same length as the map array; got {} and {}",
+            args[1].len(),
+            maps.len()
+        );
+    }
+
+    let looked_up = lookup_values(maps, args[1].as_string::<i32>());
+    // the remaining arguments are passed through, a NULL value yields NULL
+    let mut extract_args = vec![Arc::new(looked_up) as ArrayRef];
+    extract_args.extend(args[2..].iter().cloned());
+    regexp_extract(&extract_args)
+}
+
+/// Returns the value of the first entry of each map whose key is the key of
+/// the same row, or NULL if there is none.
+fn lookup_values(maps: &MapArray, keys: &StringArray) -> StringArray {
+    let map_keys = maps.keys().as_string::<i32>();
+    let map_values = maps.values().as_string::<i32>();
+    let offsets = maps.value_offsets();
+    (0..maps.len())
+        .map(|i| {
+            if maps.is_null(i) || keys.is_null(i) {
+                return None;
+            }
+            let key = keys.value(i);
+            let entries = offsets[i] as usize..offsets[i + 1] as usize;
+            entries
+                .into_iter()
+                .find(|&entry| map_keys.value(entry) == key)
+                .and_then(|entry| {
+                    map_values.is_valid(entry).then(|| map_values.value(entry))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, MapBuilder, StringBuilder};
+
+    /// The entries of a map row
+    type Entries<'a> = Vec<(&'a str, Option<&'a str>)>;
+
+    fn string_map(rows: Vec<Option<Entries>>) -> ArrayRef {
+        let mut builder =
+            MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        for row in rows {
+            match row {
+                Some(entries) => {
+                    for (key, value) in entries {
+                        builder.keys().append_value(key);
+                        builder.values().append_option(value);
+                    }
+                    builder.append(true).unwrap();
+                }
+                None => builder.append(false).unwrap(),
+            }
+        }
+        Arc::new(builder.finish())
+    }
+
+    #[test]
+    fn test_regexp_extract_map_value() {
+        let maps = string_map(vec![
+            Some(vec![("id", Some("user-42")), ("name", Some("Ada"))]),
+            Some(vec![("name", Some("Grace"))]),
+            Some(vec![("id", Some("guest"))]),
+            Some(vec![("id", None)]),
+            Some(vec![]),
+            None,
+            Some(vec![("id", Some("user-7")), ("id", Some("user-8"))]),
+        ]);
+        let keys = StringArray::from(vec!["id"; 7]);
+        let patterns = StringArray::from(vec![r"user-(\d+)"]);
+
+        let result = regexp_extract_map_value(&[
+            maps,
+            Arc::new(keys),
+            Arc::new(patterns),
+            Arc::new(Int64Array::from(vec![1; 7])),
+        ])
+        .unwrap();
+
+        // missing keys, NULL values and NULL maps yield NULL, a present value
+        // that does not match yields an empty string like `regexp_extract`,
+        // and the first entry of a duplicate key is used
+        let expected = StringArray::from(vec![
+            Some("42"),
+            None,
+            Some(""),
+            None,
+            None,
+            None,
+            Some("7"),
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_map_value_key_per_row() {
+        let maps = string_map(vec![
+            Some(vec![("a", Some("x1")), ("b", Some("y2"))]),
+            Some(vec![("a", Some("x3")), ("b", Some("y4"))]),
+            Some(vec![("a", Some("x5"))]),
+        ]);
+        let keys = StringArray::from(vec![Some("b"), Some("a"), None]);
+
+        // the group index defaults to 1
+        let result = regexp_extract_map_value(&[
+            maps,
+            Arc::new(keys),
+            Arc::new(StringArray::from(vec![r"[a-z](\d)"])),
+        ])
+        .unwrap();
+        let expected = StringArray::from(vec![Some("2"), Some("3"), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_map_value_coerce_types() {
+        use DataType::*;
+        let func = RegexpExtractMapValueFunc::new();
+        let view_map = Map(
+            Arc::new(Field::new(
+                "entries",
+                Struct(Fields::from(vec![
+                    Field::new("key", Utf8View, false),
+                    Field::new("value", LargeUtf8, true),
+                ])),
+                false,
+            )),
+            false,
+        );
+        let coerced = func
+            .coerce_types(&[view_map, Utf8View, Utf8, Int32])
+            .unwrap();
+        let Map(entries, false) = &coerced[0] else {
+            panic!("expected a map, got {}", coerced[0]);
+        };
+        let Struct(fields) = entries.data_type() else {
+            panic!("expected struct entries, got {}", entries.data_type());
+        };
+        assert_eq!(fields[0].data_type(), &Utf8);
+        assert_eq!(fields[1].data_type(), &Utf8);
+        assert_eq!(&coerced[1..], &[Utf8, Utf8, Int64]);
+
+        let int_map = Map(
+            Arc::new(Field::new(
+                "entries",
+                Struct(Fields::from(vec![
+                    Field::new("key", Utf8, false),
+                    Field::new("value", Int64, true),
+                ])),
+                false,
+            )),
+            false,
+        );
+        let err = func.coerce_types(&[int_map, Utf8, Utf8]).unwrap_err();
+        assert!(
+            err.strip_backtrace()
+                .contains("expects a map with string keys and values"),
+            "unexpected error: {err}"
+        );
+    }
+}
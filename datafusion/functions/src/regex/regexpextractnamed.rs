@@ -0,0 +1,318 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, RowPatterns};
+use arrow::array::{Array, ArrayRef, AsArray, ListBuilder, StringBuilder};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the named capture groups of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match from a string, returning the captured values in the order of the given names. Like `regexp_extract`, groups that did not participate in the match and strings without a match yield empty strings. Names that are not a capture group of the regular expression yield NULL.",
+    syntax_example = "regexp_extract_named(str, regexp, names)",
+    sql_example = r#"```sql
+> select regexp_extract_named('2024-03-15', '(?<year>\d+)-(?<month>\d+)-(?<day>\d+)', ['day', 'year']);
++------------------------------------------------------------------------------------------------------------------------------+
+| regexp_extract_named(Utf8("2024-03-15"),Utf8("(?<year>\d+)-(?<month>\d+)-(?<day>\d+)"),make_array(Utf8("day"),Utf8("year"))) |
++------------------------------------------------------------------------------------------------------------------------------+
+| [15, 2024]                                                                                                                   |
++------------------------------------------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "names",
+        description = "List of the names of the capture groups to extract. Can be a constant, column, or function, and any combination of array operators."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractNamedFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractNamedFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractNamedFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractNamedFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_named"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_list_type())
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1, 2], regexp_extract_named)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 3 {
+            return plan_err!(
+                "regexp_extract_named requires 3 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (2, List(field) | LargeList(field) | FixedSizeList(field, _))
+                    if matches!(field.data_type(), Utf8 | LargeUtf8 | Utf8View | Null) =>
+                {
+                    Ok(string_list_type())
+                }
+                (2, Null) => Ok(string_list_type()),
+                (2, other) => plan_err!(
+                    "regexp_extract_named expects a list of group names as the third argument, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_named argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The `List<Utf8>` type of the group names and the result of
+/// `regexp_extract_named`.
+fn string_list_type() -> DataType {
+    DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+}
+
+/// Extracts the capture groups named by each list of `args[2]` from the first
+/// match of the pattern `args[1]` in each string of `args[0]`.
+///
+/// The patterns and the name lists may be single element arrays, in which
+/// case they are applied to every row. The strings are expected to be `Utf8`
+/// and the name lists `List<Utf8>`.
+pub fn regexp_extract_named(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 3 {
+        return exec_err!(
+            "regexp_extract_named was called with {} arguments. It requires 3.",
+            args.len()
+        );
+    }
+    if let Some(arg) = args[..2]
+        .iter()
+        .find(|arg| arg.data_type() != &DataType::Utf8)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_named",
+            arg.data_type()
+        );
+    }
+    if args[2].data_type() != &string_list_type() {
+        return exec_err!(
+            "Unsupported data type {:?} for the group names of function regexp_extract_named",
+            args[2].data_type()
+        );
+    }
+
+    let values = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let names_array = args[2].as_list::<i32>();
+    for (arg, len) in [
+        ("pattern", pattern_array.len()),
+        ("names", names_array.len()),
+    ] {
+        if len != 1 && len != values.len() {
+            return exec_err!(
+                "regexp_extract_named {arg} array must be the same length as the values array; got {len} and {}",
+                values.len()
+            );
+        }
+    }
+
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_named", pattern_array, values.len())?;
+
+    let mut builder = ListBuilder::new(StringBuilder::new());
+    for i in 0..values.len() {
+        let names_index = if names_array.len() == 1 { 0 } else { i };
+        if values.is_null(i) || patterns.is_null(i) || names_array.is_null(names_index) {
+            builder.append_null();
+            continue;
+        }
+
+        let regex = patterns.get(i)?;
+        let names = names_array.value(names_index);
+        append_named_groups(
+            builder.values(),
+            regex,
+            values.value(i),
+            names.as_string::<i32>().iter(),
+        );
+        builder.append(true);
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Appends the value captured by the first match of `regex` in `value` for
+/// each of `names`, or NULL for the names that are not a group of `regex`.
+fn append_named_groups<'n>(
+    builder: &mut StringBuilder,
+    regex: &Regex,
+    value: &str,
+    names: impl Iterator<Item = Option<&'n str>>,
+) {
+    let captures = regex.captures(value);
+    for name in names {
+        let group_index = name.and_then(|name| {
+            regex
+                .capture_names()
+                .position(|group_name| group_name == Some(name))
+        });
+        match group_index {
+            Some(group_index) => builder.append_value(
+                captures
+                    .as_ref()
+                    .and_then(|captures| captures.get(group_index))
+                    .map_or("", |m| m.as_str()),
+            ),
+            None => builder.append_null(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ListArray, StringArray};
+
+    fn string_list(rows: Vec<Option<Vec<Option<&str>>>>) -> ListArray {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for row in rows {
+            match row {
+                Some(values) => {
+                    values
+                        .into_iter()
+                        .for_each(|v| builder.values().append_option(v));
+                    builder.append(true);
+                }
+                None => builder.append_null(),
+            }
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_regexp_extract_named() {
+        let values = StringArray::from(vec![
+            Some("2024-03-15"),
+            Some("2024-03"),
+            Some("no date"),
+            None,
+        ]);
+        let patterns =
+            StringArray::from(vec![r"(?<year>\d+)-(?<month>\d+)(?:-(?<day>\d+))?"]);
+        let names = string_list(vec![Some(vec![
+            Some("day"),
+            Some("bogus"),
+            Some("year"),
+            None,
+            Some("month"),
+        ])]);
+
+        let result = regexp_extract_named(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(names),
+        ])
+        .unwrap();
+
+        // unknown and NULL names yield NULL, groups without a value yield an
+        // empty string
+        let expected = string_list(vec![
+            Some(vec![Some("15"), None, Some("2024"), None, Some("03")]),
+            Some(vec![Some(""), None, Some("2024"), None, Some("03")]),
+            Some(vec![Some(""), None, Some(""), None, Some("")]),
+            None,
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+        assert_eq!(result.data_type(), &string_list_type());
+    }
+
+    #[test]
+    fn test_regexp_extract_named_array_names() {
+        let values = StringArray::from(vec!["a=1", "b=2", "c=3", "d=4"]);
+        let patterns = StringArray::from(vec![r"(?<key>\w)=(?<value>\d)"]);
+        let names = string_list(vec![
+            Some(vec![Some("value"), Some("key")]),
+            Some(vec![]),
+            None,
+            Some(vec![Some("key"), Some("key")]),
+        ]);
+
+        let result = regexp_extract_named(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(names),
+        ])
+        .unwrap();
+
+        let expected = string_list(vec![
+            Some(vec![Some("1"), Some("a")]),
+            Some(vec![]),
+            None,
+            Some(vec![Some("d"), Some("d")]),
+        ]);
+        assert_eq!(result.as_list::<i32>(), &expected);
+    }
+}
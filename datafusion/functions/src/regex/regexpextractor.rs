@@ -0,0 +1,255 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, regexp_extract_with_match_mask};
+use arrow::array::{Array, ArrayRef, AsArray, StringArray};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match of the specified capture group from a string like `regexp_extract`, but returns the given sentinel instead of an empty string if the regular expression does not match. An empty capture group of a match still yields an empty string, so the sentinel only marks the rows without a match. Passing the string itself as the sentinel returns the strings without a match unchanged, which rewrites only the rows that need it.",
+    syntax_example = "regexp_extract_or(str, regexp, idx, sentinel)",
+    sql_example = r#"```sql
+> select regexp_extract_or('no digits', '(\d+)', 1, 'n/a');
++-------------------------------------------------------------------------+
+| regexp_extract_or(Utf8("no digits"),Utf8("(\d+)"),Int64(1),Utf8("n/a")) |
++-------------------------------------------------------------------------+
+| n/a                                                                     |
++-------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Index of the capture group to extract. Group 0 is the entire match. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "sentinel",
+        description = "String to return if the regular expression does not match. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractOrFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractOrFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractOrFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractOrFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_or"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1, 3], regexp_extract_or)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 4 {
+            return plan_err!(
+                "regexp_extract_or requires 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (2, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (2, other) => plan_err!(
+                    "regexp_extract_or group index must be an integer, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_or argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Extracts the capture group `args[2]` of the first match of `args[1]` from
+/// each string of `args[0]`, or the sentinel `args[3]` if there is no match.
+///
+/// The pattern and the sentinel may be single element arrays, in which case
+/// they are applied to every row. All string arguments are expected to be
+/// `Utf8`.
+pub fn regexp_extract_or(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 4 {
+        return exec_err!(
+            "regexp_extract_or was called with {} arguments. It requires 4.",
+            args.len()
+        );
+    }
+    if args[3].data_type() != &DataType::Utf8 {
+        return exec_err!(
+            "Unsupported data type {:?} for the sentinel of function regexp_extract_or",
+            args[3].data_type()
+        );
+    }
+
+    // the mask tells the rows without a match apart from empty capture groups
+    let (extracted, matched) = regexp_extract_with_match_mask(&args[..3])?;
+    let extracted = extracted.as_string::<i32>();
+    let sentinels = args[3].as_string::<i32>();
+    if sentinels.len() != 1 && sentinels.len() != extracted.len() {
+        return exec_err!(
+            "regexp_extract_or sentinel array must be the same length as the values array; got {} and {}",
+            sentinels.len(),
+            extracted.len()
+        );
+    }
+
+    let result = extracted
+        .iter()
+        .zip(matched.iter())
+        .enumerate()
+        .map(|(i, (value, matched))| match matched {
+            Some(false) => {
+                let sentinel_index = if sentinels.len() == 1 { 0 } else { i };
+                sentinels
+                    .is_valid(sentinel_index)
+                    .then(|| sentinels.value(sentinel_index))
+            }
+            _ => value,
+        })
+        .collect::<StringArray>();
+    Ok(Arc::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    #[test]
+    fn test_regexp_extract_or() {
+        let values =
+            StringArray::from(vec![Some("key=100"), Some("key="), Some("no key"), None]);
+        let patterns = StringArray::from(vec![r"key=(\d*)"]);
+        let group_indices = Int64Array::from(vec![1; 4]);
+        let sentinels = StringArray::from(vec!["<none>"]);
+
+        let result = regexp_extract_or(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+            Arc::new(sentinels),
+        ])
+        .unwrap();
+
+        // a matched empty capture group is not replaced by the sentinel
+        let expected =
+            StringArray::from(vec![Some("100"), Some(""), Some("<none>"), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_or_sentinel_array() {
+        let values = StringArray::from(vec!["a1", "b", "c", "d"]);
+        let patterns = StringArray::from(vec![r"(\d)"]);
+        let group_indices = Int64Array::from(vec![1; 4]);
+        // the sentinel may be empty or NULL, like the results it replaces
+        let sentinels =
+            StringArray::from(vec![Some("x"), Some("missing"), Some(""), None]);
+
+        let result = regexp_extract_or(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+            Arc::new(sentinels),
+        ])
+        .unwrap();
+
+        let expected =
+            StringArray::from(vec![Some("1"), Some("missing"), Some(""), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_or_passthrough() {
+        let values = Arc::new(StringArray::from(vec![
+            Some("user:alice"),
+            Some("bob"),
+            Some("user:"),
+            None,
+        ])) as ArrayRef;
+        let patterns = StringArray::from(vec![r"^user:(\w*)$"]);
+        let group_indices = Int64Array::from(vec![1; 4]);
+
+        // the values themselves as the sentinel pass the rows without a match
+        // through unchanged
+        let result = regexp_extract_or(&[
+            Arc::clone(&values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+            values,
+        ])
+        .unwrap();
+
+        let expected =
+            StringArray::from(vec![Some("alice"), Some("bob"), Some(""), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
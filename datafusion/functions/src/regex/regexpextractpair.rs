@@ -0,0 +1,326 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{
+    group_index_at, invoke_materialized, validate_group_index, RowPatterns,
+};
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array, StringBuilder, StructBuilder};
+use arrow::datatypes::{DataType, Field, Fields};
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts two capture groups of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match from a string as a struct with the fields `a` and `b`, for example the start and end of a range like `10-20`. The string is matched once for both groups, which is cheaper than two `regexp_extract` calls. If the regular expression does not match, both fields are NULL. A capture group that did not participate in the match is an empty string, like in `regexp_extract`.",
+    syntax_example = "regexp_extract_pair(str, regexp, idx_a, idx_b)",
+    sql_example = r#"```sql
+> select regexp_extract_pair('10-20', '(\d+)-(\d+)', 1, 2);
++--------------------------------------------------------------------------+
+| regexp_extract_pair(Utf8("10-20"),Utf8("(\d+)-(\d+)"),Int64(1),Int64(2)) |
++--------------------------------------------------------------------------+
+| {a: 10, b: 20}                                                           |
++--------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx_a",
+        description = "Index of the capture group extracted into the field `a`. Group 0 is the entire match. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx_b",
+        description = "Index of the capture group extracted into the field `b`. Group 0 is the entire match. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractPairFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractPairFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractPairFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractPairFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_pair"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Struct(pair_fields()))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_pair)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 4 {
+            return plan_err!(
+                "regexp_extract_pair requires 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (
+                    2 | 3,
+                    Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null,
+                ) => Ok(Int64),
+                (2 | 3, other) => plan_err!(
+                    "regexp_extract_pair group index must be an integer, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_pair argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The fields of the struct returned by `regexp_extract_pair`.
+fn pair_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("a", DataType::Utf8, true),
+        Field::new("b", DataType::Utf8, true),
+    ])
+}
+
+/// Extracts the capture groups `args[2]` and `args[3]` of the first match of
+/// `args[1]` from each string of `args[0]` into a struct of the fields `a`
+/// and `b`.
+///
+/// The pattern may be a single element array, in which case it is applied to
+/// every row. The strings are expected to be `Utf8` and the group indices
+/// `Int64`. A NULL string, pattern or group index yields a NULL struct, and a
+/// string without a match a struct of NULL fields.
+pub fn regexp_extract_pair(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if args_len != 4 {
+        return exec_err!(
+            "regexp_extract_pair was called with {args_len} arguments. It requires 4."
+        );
+    }
+    if let Some(arg) = args[..2]
+        .iter()
+        .find(|arg| arg.data_type() != &DataType::Utf8)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_pair",
+            arg.data_type()
+        );
+    }
+    let group_a_array = group_index_array(&args[2])?;
+    let group_b_array = group_index_array(&args[3])?;
+
+    let values = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_pair", pattern_array, values.len())?;
+
+    let mut builder = StructBuilder::new(
+        pair_fields(),
+        vec![
+            Box::new(StringBuilder::with_capacity(values.len(), 0)),
+            Box::new(StringBuilder::with_capacity(values.len(), 0)),
+        ],
+    );
+    for i in 0..values.len() {
+        let pair = match (
+            group_index_at(Some(group_a_array), i),
+            group_index_at(Some(group_b_array), i),
+        ) {
+            (Some(group_a), Some(group_b))
+                if values.is_valid(i) && patterns.is_valid(i) =>
+            {
+                let regex = patterns.get(i)?;
+                Some(pair_groups(regex, values.value(i), group_a, group_b)?)
+            }
+            _ => None,
+        };
+        let (a, b) = pair.flatten().unzip();
+        builder
+            .field_builder::<StringBuilder>(0)
+            .expect("the a field is a string")
+            .append_option(a);
+        builder
+            .field_builder::<StringBuilder>(1)
+            .expect("the b field is a string")
+            .append_option(b);
+        builder.append(pair.is_some());
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+fn group_index_array(arg: &ArrayRef) -> Result<&Int64Array> {
+    match arg.as_primitive_opt() {
+        Some(group_index_array) => Ok(group_index_array),
+        None => exec_err!(
+            "Unsupported data type {:?} for the group index of function regexp_extract_pair",
+            arg.data_type()
+        ),
+    }
+}
+
+/// Returns the capture groups `group_a` and `group_b` of the first match of
+/// `regex` in `value`, or `None` if there is no match.
+///
+/// A group that did not participate in the match is the empty string.
+fn pair_groups<'h>(
+    regex: &Regex,
+    value: &'h str,
+    group_a: i64,
+    group_b: i64,
+) -> Result<Option<(&'h str, &'h str)>> {
+    let group_a = validate_group_index(regex, group_a)?;
+    let group_b = validate_group_index(regex, group_b)?;
+    Ok(regex.captures(value).map(|captures| {
+        let group = |index| captures.get(index).map_or("", |m| m.as_str());
+        (group(group_a), group(group_b))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{StringArray, StructArray};
+    use arrow::buffer::NullBuffer;
+
+    #[test]
+    fn test_regexp_extract_pair_range() {
+        let values = StringArray::from(vec![
+            Some("10-20"),
+            Some("pages 3-7, 9-12"),
+            Some("42"),
+            Some("5-"),
+            None,
+        ]);
+        let result = regexp_extract_pair(&[
+            Arc::new(values),
+            Arc::new(StringArray::from(vec![r"(\d+)-(\d+)?"])),
+            Arc::new(Int64Array::from(vec![1; 5])),
+            Arc::new(Int64Array::from(vec![2; 5])),
+        ])
+        .unwrap();
+        // only the first match is extracted, an optional group that did not
+        // participate is empty, and a string without a match yields NULL
+        // fields in a valid struct
+        let expected = StructArray::new(
+            pair_fields(),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("10"),
+                    Some("3"),
+                    None,
+                    Some("5"),
+                    None,
+                ])),
+                Arc::new(StringArray::from(vec![
+                    Some("20"),
+                    Some("7"),
+                    None,
+                    Some(""),
+                    None,
+                ])),
+            ],
+            Some(NullBuffer::from(vec![true, true, true, true, false])),
+        );
+        assert_eq!(result.as_struct(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_pair_per_row_patterns() {
+        let values = StringArray::from(vec!["key=value", "2024-03", "a:b"]);
+        let patterns =
+            StringArray::from(vec![Some(r"(\w+)=(\w+)"), Some(r"(\d{4})-(\d{2})"), None]);
+        // the groups may be swapped or repeated
+        let result = regexp_extract_pair(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(Int64Array::from(vec![2, 0, 1])),
+            Arc::new(Int64Array::from(vec![1, 2, 1])),
+        ])
+        .unwrap();
+        let result = result.as_struct();
+        assert_eq!(
+            result.column(0).as_ref(),
+            &StringArray::from(vec![Some("value"), Some("2024-03"), None])
+        );
+        assert_eq!(
+            result.column(1).as_ref(),
+            &StringArray::from(vec![Some("key"), Some("03"), None])
+        );
+        assert!(result.is_null(2));
+    }
+
+    #[test]
+    fn test_regexp_extract_pair_invalid_group_index() {
+        let err = regexp_extract_pair(&[
+            Arc::new(StringArray::from(vec!["a"])),
+            Arc::new(StringArray::from(vec!["(a)"])),
+            Arc::new(Int64Array::from(vec![1])),
+            Arc::new(Int64Array::from(vec![2])),
+        ])
+        .expect_err("out of range group index should fail");
+        assert!(
+            err.strip_backtrace()
+                .starts_with("Execution error: Regex group index 2 is out of range"),
+            "unexpected error: {err}"
+        );
+    }
+}
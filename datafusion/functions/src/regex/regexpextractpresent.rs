@@ -0,0 +1,292 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, RowPatterns};
+use arrow::array::{
+    Array, ArrayRef, AsArray, ListBuilder, StringArrayType, StringBuilder, StructBuilder,
+};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::{exec_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns a list of the capture groups of a [regular expression](https://docs.rs/regex/latest/regex/#syntax) that participated in the first match in a string, as structs of the group `name` and the captured `value`. Groups that did not participate, such as the groups of the branches of an alternation that did not match, are left out, which keeps the result compact for patterns with many optional groups. Unnamed capture groups use the name `c` followed by the group index, like in `regexp_extract_map`. Returns an empty list if the regular expression does not match.",
+    syntax_example = "regexp_extract_present(str, regexp)",
+    sql_example = r#"```sql
+> select regexp_extract_present('id=42', 'id=(?<id>\d+)|name=(?<name>\w+)');
++-------------------------------------------------------------------------------+
+| regexp_extract_present(Utf8("id=42"),Utf8("id=(?<id>\d+)|name=(?<name>\w+)")) |
++-------------------------------------------------------------------------------+
+| [{name: id, value: 42}]                                                       |
++-------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractPresentFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractPresentFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractPresentFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View, Utf8View]),
+                    TypeSignature::Exact(vec![Utf8, Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8, LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractPresentFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_present"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::List(group_field()))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_present)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The fields of the structs in the lists returned by
+/// `regexp_extract_present`.
+fn group_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, false),
+    ])
+}
+
+/// The field of the list elements returned by `regexp_extract_present`.
+fn group_field() -> FieldRef {
+    Arc::new(Field::new_list_field(
+        DataType::Struct(group_fields()),
+        false,
+    ))
+}
+
+/// Builds a list of the name and value of every capture group that
+/// participated in the first match of a regular expression in each string of
+/// `args[0]`.
+///
+/// `args[1]` holds the patterns, either one per row or a single pattern that
+/// is applied to every row.
+pub fn regexp_extract_present(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let args_len = args.len();
+    if args_len != 2 {
+        return exec_err!(
+            "regexp_extract_present was called with {args_len} arguments. It requires 2."
+        );
+    }
+
+    match (args[0].data_type(), args[1].data_type()) {
+        (DataType::Utf8, DataType::Utf8) => concrete_regexp_extract_present(
+            args[0].as_string::<i32>(),
+            args[1].as_string::<i32>(),
+        ),
+        (DataType::LargeUtf8, DataType::LargeUtf8) => concrete_regexp_extract_present(
+            args[0].as_string::<i64>(),
+            args[1].as_string::<i64>(),
+        ),
+        (DataType::Utf8View, DataType::Utf8View) => concrete_regexp_extract_present(
+            args[0].as_string_view(),
+            args[1].as_string_view(),
+        ),
+        other => {
+            exec_err!(
+                "Unsupported data type {other:?} for function regexp_extract_present"
+            )
+        }
+    }
+}
+
+fn concrete_regexp_extract_present<'a, S>(values: S, pattern_array: S) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a>,
+{
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_present", pattern_array, values.len())?;
+
+    let mut builder = ListBuilder::new(StructBuilder::from_fields(group_fields(), 0))
+        .with_field(group_field());
+    for i in 0..values.len() {
+        if values.is_null(i) || patterns.is_null(i) {
+            builder.append(false);
+            continue;
+        }
+
+        let regex = patterns.get(i)?;
+        append_present_groups(builder.values(), regex, values.value(i));
+        builder.append(true);
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Appends a struct for every capture group of `regex` that participated in
+/// the first match in `value` to `builder`.
+fn append_present_groups(builder: &mut StructBuilder, regex: &Regex, value: &str) {
+    let Some(captures) = regex.captures(value) else {
+        return;
+    };
+
+    // group 0 is the entire match and has no name
+    for (group_index, name) in regex.capture_names().enumerate().skip(1) {
+        let Some(group) = captures.get(group_index) else {
+            continue;
+        };
+        let names = builder
+            .field_builder::<StringBuilder>(0)
+            .expect("the name field is a string");
+        match name {
+            Some(name) => names.append_value(name),
+            None => names.append_value(format!("c{group_index}")),
+        }
+        builder
+            .field_builder::<StringBuilder>(1)
+            .expect("the value field is a string")
+            .append_value(group.as_str());
+        builder.append(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{StringArray, StringViewArray};
+
+    /// The groups of a list row
+    type Groups<'a> = Vec<(&'a str, &'a str)>;
+
+    fn group_lists(rows: Vec<Option<Groups>>) -> ArrayRef {
+        let mut builder = ListBuilder::new(StructBuilder::from_fields(group_fields(), 0))
+            .with_field(group_field());
+        for row in rows {
+            match row {
+                Some(groups) => {
+                    for (name, value) in groups {
+                        let groups = builder.values();
+                        groups
+                            .field_builder::<StringBuilder>(0)
+                            .unwrap()
+                            .append_value(name);
+                        groups
+                            .field_builder::<StringBuilder>(1)
+                            .unwrap()
+                            .append_value(value);
+                        groups.append(true);
+                    }
+                    builder.append(true);
+                }
+                None => builder.append(false),
+            }
+        }
+        Arc::new(builder.finish())
+    }
+
+    #[test]
+    fn test_regexp_extract_present_alternation() {
+        let values = StringArray::from(vec![
+            Some("id=42"),
+            Some("name=ada"),
+            Some("tag:x"),
+            Some("none"),
+            None,
+        ]);
+        // each branch of the alternation has its own groups, and only the
+        // groups of the branch that matched participate
+        let patterns =
+            StringArray::from(vec![r"id=(?<id>\d+)|name=(?<name>\w+)|(\w+):(\w)"]);
+
+        let result =
+            regexp_extract_present(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+
+        let expected = group_lists(vec![
+            Some(vec![("id", "42")]),
+            Some(vec![("name", "ada")]),
+            Some(vec![("c3", "tag"), ("c4", "x")]),
+            Some(vec![]),
+            None,
+        ]);
+        assert_eq!(&result, &expected);
+        assert_eq!(result.data_type(), &DataType::List(group_field()));
+    }
+
+    #[test]
+    fn test_regexp_extract_present_optional_groups() {
+        let values = StringViewArray::from(vec!["2024-03-15", "2024-03", "2024"]);
+        let patterns = StringViewArray::from(vec![
+            r"(?<year>\d{4})(?:-(?<month>\d{2}))?(?:-(?<day>\d{2}))?",
+            r"(?<year>\d{4})(?:-(?<month>\d{2}))?(?:-(?<day>\d{2}))?",
+            // an empty group participates
+            r"(?<year>\d{4})(?<rest>.*)",
+        ]);
+
+        let result =
+            regexp_extract_present(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+
+        let expected = group_lists(vec![
+            Some(vec![("year", "2024"), ("month", "03"), ("day", "15")]),
+            Some(vec![("year", "2024"), ("month", "03")]),
+            Some(vec![("year", "2024"), ("rest", "")]),
+        ]);
+        assert_eq!(&result, &expected);
+    }
+}
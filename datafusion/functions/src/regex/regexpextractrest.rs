@@ -0,0 +1,198 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, RowPatterns};
+use arrow::array::{Array, ArrayRef, AsArray, StringArray};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns the rest of a string after the end of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match, or NULL if the regular expression does not match. Calling it repeatedly with a pattern anchored by `^` consumes a string one token at a time, with `regexp_extract` reading each token. A match that ends at the end of the string returns an empty string.",
+    syntax_example = "regexp_extract_rest(str, regexp)",
+    sql_example = r#"```sql
+> select regexp_extract_rest('größe=42;farbe=grün', '^[^;]*;');
++------------------------------------------------------------------+
+| regexp_extract_rest(Utf8("größe=42;farbe=grün"),Utf8("^[^;]*;")) |
++------------------------------------------------------------------+
+| farbe=grün                                                       |
++------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractRestFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractRestFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractRestFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractRestFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_rest"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_rest)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 2 {
+            return plan_err!(
+                "regexp_extract_rest requires 2 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match data_type {
+                Utf8 | LargeUtf8 | Utf8View | Null => Ok(Utf8),
+                other => plan_err!(
+                    "regexp_extract_rest argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the rest of each string of `args[0]` after the end of the first
+/// match of `args[1]`, or NULL if it does not match.
+///
+/// The pattern may be a single element array, in which case it is applied to
+/// every row. The strings are expected to be `Utf8`.
+pub fn regexp_extract_rest(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 2 {
+        return exec_err!(
+            "regexp_extract_rest was called with {} arguments. It requires 2.",
+            args.len()
+        );
+    }
+    if let Some(arg) = args.iter().find(|arg| arg.data_type() != &DataType::Utf8) {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_rest",
+            arg.data_type()
+        );
+    }
+
+    let values = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_rest", pattern_array, values.len())?;
+
+    let result = (0..values.len())
+        .map(|i| {
+            if values.is_null(i) || patterns.is_null(i) {
+                return Ok(None);
+            }
+            let regex = patterns.get(i)?;
+            let value = values.value(i);
+            // a match of a `str` ends on a character boundary, so slicing
+            // after it never splits a character
+            Ok(regex.find(value).map(|m| &value[m.end()..]))
+        })
+        .collect::<Result<StringArray>>()?;
+    Ok(Arc::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regexp_extract_rest() {
+        let values = StringArray::from(vec![
+            Some("größe=42;farbe=grün"),
+            Some("東京;大阪;"),
+            Some("no separator"),
+            Some("ends;"),
+            None,
+        ]);
+        let patterns = StringArray::from(vec!["^[^;]*;"]);
+
+        let result =
+            regexp_extract_rest(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+        // the rest starts after the multibyte characters of the match and may
+        // hold multibyte characters itself
+        let expected = StringArray::from(vec![
+            Some("farbe=grün"),
+            Some("大阪;"),
+            None,
+            Some(""),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_rest_pattern_per_row() {
+        let values = StringArray::from(vec!["é1ü2ö3", "é1ü2ö3", "é1ü2ö3", "é1ü2ö3"]);
+        let patterns = StringArray::from(vec![Some(r"\d"), Some("ü"), Some(""), None]);
+
+        let result =
+            regexp_extract_rest(&[Arc::new(values), Arc::new(patterns)]).unwrap();
+        // an empty match at the start returns the whole string
+        let expected =
+            StringArray::from(vec![Some("ü2ö3"), Some("2ö3"), Some("é1ü2ö3"), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
@@ -0,0 +1,421 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{
+    compile_pattern, extract_group, group_index_at, invoke_materialized,
+};
+use arrow::array::{Array, ArrayRef, AsArray, ListArray, StringArray, StringBuilder};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match of the specified capture group from a string, like `regexp_extract`, with a regular expression built from a template. Each `{N}` placeholder of the template is replaced by the parameter at index N, counting from 0, escaped so that it matches literally. This builds patterns from data without letting it inject regular expression syntax. Braces of the regular expression itself are written twice, as in `\\d{{3}}`. Returns NULL if a parameter in the template is NULL.",
+    syntax_example = "regexp_extract_template(str, template, params, idx)",
+    sql_example = r#"```sql
+> select regexp_extract_template('host=db.internal port=5432', '{0}=(\S+)', ['host'], 1);
++-----------------------------------------------------------------------------------------------------------------+
+| regexp_extract_template(Utf8("host=db.internal port=5432"),Utf8("{0}=(\S+)"),make_array(Utf8("host")),Int64(1)) |
++-----------------------------------------------------------------------------------------------------------------+
+| db.internal                                                                                                     |
++-----------------------------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "template",
+        description = "Regular expression with `{N}` placeholders for the parameters. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "params",
+        description = "List of the strings that replace the placeholders. Can be a constant, column, or function, and any combination of array operators."
+    ),
+    argument(
+        name = "idx",
+        description = "Index of the capture group to extract. Group 0 is the entire match. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractTemplateFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractTemplateFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractTemplateFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractTemplateFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_template"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        // the pattern is built and compiled once if the template and the
+        // parameters are both scalars
+        invoke_materialized(&args.args, &[1, 2], regexp_extract_template)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 4 {
+            return plan_err!(
+                "regexp_extract_template requires 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (2, List(field) | LargeList(field) | FixedSizeList(field, _))
+                    if matches!(field.data_type(), Utf8 | LargeUtf8 | Utf8View | Null) =>
+                {
+                    Ok(string_list_type())
+                }
+                (2, Null) => Ok(string_list_type()),
+                (2, other) => plan_err!(
+                    "regexp_extract_template expects a list of parameters as the third argument, got {other}"
+                ),
+                (3, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (3, other) => plan_err!(
+                    "regexp_extract_template group index must be an integer, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_template argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// The `List<Utf8>` type of the parameters of `regexp_extract_template`.
+fn string_list_type() -> DataType {
+    DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+}
+
+/// Extracts the capture group `args[3]` of the first match of the pattern
+/// built from the template `args[1]` and the parameters `args[2]` from each
+/// string of `args[0]`.
+///
+/// The templates and the parameter lists may be single element arrays, in
+/// which case they are applied to every row. The strings are expected to be
+/// `Utf8`, the parameter lists `List<Utf8>` and the group indices `Int64`.
+pub fn regexp_extract_template(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 4 {
+        return exec_err!(
+            "regexp_extract_template was called with {} arguments. It requires 4.",
+            args.len()
+        );
+    }
+    if let Some(arg) = args[..2]
+        .iter()
+        .find(|arg| arg.data_type() != &DataType::Utf8)
+    {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_template",
+            arg.data_type()
+        );
+    }
+    if args[2].data_type() != &string_list_type() {
+        return exec_err!(
+            "Unsupported data type {:?} for the parameters of function regexp_extract_template",
+            args[2].data_type()
+        );
+    }
+    let Some(group_index_array) = args[3].as_primitive_opt() else {
+        return exec_err!(
+            "Unsupported data type {:?} for the group index of function regexp_extract_template",
+            args[3].data_type()
+        );
+    };
+
+    let values = args[0].as_string::<i32>();
+    let template_array = args[1].as_string::<i32>();
+    let params_array = args[2].as_list::<i32>();
+    for (arg, len) in [
+        ("template", template_array.len()),
+        ("params", params_array.len()),
+    ] {
+        if len != 1 && len != values.len() {
+            return exec_err!(
+                "regexp_extract_template {arg} array must be the same length as the values array; got {len} and {}",
+                values.len()
+            );
+        }
+    }
+
+    let is_pattern_scalar = template_array.len() == 1 && params_array.len() == 1;
+    let scalar_regex = if is_pattern_scalar {
+        // the template and the parameters are scalars: compile them once for
+        // all rows, `None` for a NULL template or parameter
+        template_pattern(template_array, params_array, 0)?
+            .map(|pattern| compile_pattern(&pattern, None))
+            .transpose()?
+    } else {
+        None
+    };
+
+    let mut regex_cache: HashMap<String, Regex> = HashMap::new();
+    let mut builder = StringBuilder::with_capacity(values.len(), 0);
+    for i in 0..values.len() {
+        let Some(group_index) = group_index_at(Some(group_index_array), i) else {
+            builder.append_null();
+            continue;
+        };
+        if values.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+        let regex = if is_pattern_scalar {
+            scalar_regex.as_ref()
+        } else {
+            match template_pattern(template_array, params_array, i)? {
+                Some(pattern) => Some(match regex_cache.entry(pattern) {
+                    Entry::Occupied(occupied_entry) => &*occupied_entry.into_mut(),
+                    Entry::Vacant(vacant_entry) => {
+                        let regex = compile_pattern(vacant_entry.key(), None)?;
+                        &*vacant_entry.insert(regex)
+                    }
+                }),
+                None => None,
+            }
+        };
+        match regex {
+            Some(regex) => {
+                builder.append_value(extract_group(regex, values.value(i), group_index)?)
+            }
+            None => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Returns the pattern of row `i`, built from its template and parameters,
+/// or `None` if the template or a parameter it uses is NULL.
+fn template_pattern(
+    template_array: &StringArray,
+    params_array: &ListArray,
+    i: usize,
+) -> Result<Option<String>> {
+    let template_index = if template_array.len() == 1 { 0 } else { i };
+    let params_index = if params_array.len() == 1 { 0 } else { i };
+    if template_array.is_null(template_index) || params_array.is_null(params_index) {
+        return Ok(None);
+    }
+    let params = params_array.value(params_index);
+    let params = params.as_string::<i32>().iter().collect::<Vec<_>>();
+    fill_template(template_array.value(template_index), &params)
+}
+
+/// Replaces each `{N}` placeholder of `template` with the N-th of `params`,
+/// escaped with [`regex::escape`] so that it matches literally, and each
+/// `{{` and `}}` with a single brace.
+///
+/// Returns `None` if a parameter used by the template is NULL.
+pub fn fill_template(template: &str, params: &[Option<&str>]) -> Result<Option<String>> {
+    let mut pattern = String::with_capacity(template.len());
+    let mut chars = template.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '{' if chars.next_if(|(_, c)| *c == '{').is_some() => pattern.push('{'),
+            '}' if chars.next_if(|(_, c)| *c == '}').is_some() => pattern.push('}'),
+            '{' => {
+                let Some(end) = template[start..].find('}').map(|end| start + end) else {
+                    return exec_err!(
+                        "regexp_extract_template placeholder at byte {start} of '{template}' is not closed, write {{{{ for a literal brace"
+                    );
+                };
+                let placeholder = &template[start + 1..end];
+                let param = match placeholder.parse::<usize>() {
+                    Ok(index) if placeholder.bytes().all(|b| b.is_ascii_digit()) => {
+                        match params.get(index) {
+                            Some(param) => param,
+                            None => {
+                                return exec_err!(
+                                    "regexp_extract_template placeholder {{{index}}} of '{template}' is out of range, there are {} parameters",
+                                    params.len()
+                                )
+                            }
+                        }
+                    }
+                    _ => {
+                        return exec_err!(
+                            "regexp_extract_template placeholder {{{placeholder}}} of '{template}' must be a parameter index, write {{{{ and }}}} for literal braces"
+                        )
+                    }
+                };
+                let Some(param) = param else {
+                    return Ok(None);
+                };
+                pattern.push_str(&regex::escape(param));
+                while chars.next_if(|(i, _)| *i <= end).is_some() {}
+            }
+            '}' => {
+                return exec_err!(
+                    "regexp_extract_template brace at byte {start} of '{template}' is not opened, write }}}} for a literal brace"
+                )
+            }
+            c => pattern.push(c),
+        }
+    }
+    Ok(Some(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, ListBuilder};
+
+    fn string_list(rows: Vec<Option<Vec<Option<&str>>>>) -> ListArray {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for row in rows {
+            match row {
+                Some(values) => {
+                    values
+                        .into_iter()
+                        .for_each(|v| builder.values().append_option(v));
+                    builder.append(true);
+                }
+                None => builder.append_null(),
+            }
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_fill_template() {
+        assert_eq!(
+            fill_template(r"{0}=(\d{{2,}}) {1}", &[Some("a.b"), Some("(x|y)*")]).unwrap(),
+            Some(r"a\.b=(\d{2,}) \(x\|y\)\*".to_string())
+        );
+        assert_eq!(
+            fill_template("{1}{0}{1}", &[Some("^"), Some("$")]).unwrap(),
+            Some(r"\$\^\$".to_string())
+        );
+        assert_eq!(fill_template("{0}", &[None]).unwrap(), None);
+
+        for (template, expected) in [
+            (
+                "{2}",
+                "placeholder {2} of '{2}' is out of range, there are 1 parameters",
+            ),
+            (r"\d{3}", r"placeholder {3} of '\d{3}' is out of range"),
+            (
+                "a{1,2}",
+                "placeholder {1,2} of 'a{1,2}' must be a parameter index",
+            ),
+            (
+                "{+0}",
+                "placeholder {+0} of '{+0}' must be a parameter index",
+            ),
+            ("(a{0", "placeholder at byte 2 of '(a{0' is not closed"),
+            ("a}", "brace at byte 1 of 'a}' is not opened"),
+        ] {
+            let err = fill_template(template, &[Some("p")]).unwrap_err();
+            assert!(
+                err.strip_backtrace().starts_with(&format!(
+                    "Execution error: regexp_extract_template {expected}"
+                )),
+                "unexpected error for {template}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_regexp_extract_template_escapes_params() {
+        let values = StringArray::from(vec![
+            Some("ip=10.0.0.1"),
+            Some("ip=10a0b0c1"),
+            Some("ip=10.0.0.1"),
+            None,
+        ]);
+        // the `.` of the parameter only matches a literal `.`
+        let templates = StringArray::from(vec![r"ip=({0})"]);
+        let params = string_list(vec![Some(vec![Some("10.0.0.1")])]);
+
+        let result = regexp_extract_template(&[
+            Arc::new(values.clone()),
+            Arc::new(templates.clone()),
+            Arc::new(params),
+            Arc::new(Int64Array::from(vec![1; 4])),
+        ])
+        .unwrap();
+        let expected =
+            StringArray::from(vec![Some("10.0.0.1"), Some(""), Some("10.0.0.1"), None]);
+        assert_eq!(result.as_ref(), &expected);
+
+        // parameters per row, a NULL parameter yields NULL
+        let params = string_list(vec![
+            Some(vec![Some("10.0.0.1")]),
+            Some(vec![Some("10.0.0.1")]),
+            Some(vec![None]),
+            Some(vec![Some("x")]),
+        ]);
+        let result = regexp_extract_template(&[
+            Arc::new(values),
+            Arc::new(templates),
+            Arc::new(params),
+            Arc::new(Int64Array::from(vec![1; 4])),
+        ])
+        .unwrap();
+        let expected = StringArray::from(vec![Some("10.0.0.1"), Some(""), None, None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
@@ -0,0 +1,260 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, regexp_extract};
+use arrow::array::{Array, ArrayRef, AsArray, TimestampNanosecondArray};
+use arrow::datatypes::{DataType, TimeUnit};
+use chrono::format::{parse, Parsed, StrftimeItems};
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match of the specified capture group from a string and parses it as a timestamp, like `to_timestamp(regexp_extract(str, regexp, idx), format)`. Returns NULL if the regular expression does not match or the capture cannot be parsed with the format.",
+    syntax_example = "regexp_extract_timestamp(str, regexp, idx, format)",
+    sql_example = r#"```sql
+> select regexp_extract_timestamp('[2024-03-05 10:15:00] GET /', '\[([^\]]+)\]', 1, '%Y-%m-%d %H:%M:%S');
++-----------------------------------------------------------------------------------------------------------------------+
+| regexp_extract_timestamp(Utf8("[2024-03-05 10:15:00] GET /"),Utf8("\[([^\]]+)\]"),Int64(1),Utf8("%Y-%m-%d %H:%M:%S")) |
++-----------------------------------------------------------------------------------------------------------------------+
+| 2024-03-05T10:15:00                                                                                                   |
++-----------------------------------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "idx",
+        description = "Index of the capture group to extract. Group 0 is the entire match. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "format",
+        description = "[Chrono format](https://docs.rs/chrono/latest/chrono/format/strftime/index.html) of the captured timestamp. A capture without an offset is taken to be in UTC. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractTimestampFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractTimestampFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractTimestampFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractTimestampFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_timestamp"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Timestamp(TimeUnit::Nanosecond, None))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1, 3], regexp_extract_timestamp)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 4 {
+            return plan_err!(
+                "regexp_extract_timestamp requires 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match (idx, data_type) {
+                (2, Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Null) => {
+                    Ok(Int64)
+                }
+                (2, other) => plan_err!(
+                    "regexp_extract_timestamp group index must be an integer, got {other}"
+                ),
+                (_, Utf8 | LargeUtf8 | Utf8View | Null) => Ok(Utf8),
+                (_, other) => plan_err!(
+                    "regexp_extract_timestamp argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Extracts the capture group `args[2]` of the first match of `args[1]` from
+/// each string of `args[0]` and parses it with the chrono format `args[3]`.
+///
+/// The pattern and the format may be single element arrays, in which case
+/// they are applied to every row. All string arguments are expected to be
+/// `Utf8`.
+pub fn regexp_extract_timestamp(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 4 {
+        return exec_err!(
+            "regexp_extract_timestamp was called with {} arguments. It requires 4.",
+            args.len()
+        );
+    }
+    if args[3].data_type() != &DataType::Utf8 {
+        return exec_err!(
+            "Unsupported data type {:?} for the format of function regexp_extract_timestamp",
+            args[3].data_type()
+        );
+    }
+
+    let extracted = regexp_extract(&args[..3])?;
+    let extracted = extracted.as_string::<i32>();
+    let formats = args[3].as_string::<i32>();
+    if formats.len() != 1 && formats.len() != extracted.len() {
+        return exec_err!(
+            "regexp_extract_timestamp format array must be the same length as the values array; got {} and {}",
+            formats.len(),
+            extracted.len()
+        );
+    }
+
+    let result = (0..extracted.len())
+        .map(|i| {
+            let format_index = if formats.len() == 1 { 0 } else { i };
+            if extracted.is_null(i) || formats.is_null(format_index) {
+                return None;
+            }
+            parse_timestamp_nanos(extracted.value(i), formats.value(format_index))
+        })
+        .collect::<TimestampNanosecondArray>();
+    Ok(Arc::new(result))
+}
+
+/// Parses `value` with the chrono `format` into nanoseconds since the epoch,
+/// returning `None` if it does not match the format or is out of range.
+fn parse_timestamp_nanos(value: &str, format: &str) -> Option<i64> {
+    let mut parsed = Parsed::new();
+    parse(&mut parsed, value, StrftimeItems::new(format)).ok()?;
+    let datetime = match parsed.to_datetime() {
+        Ok(datetime) => datetime.naive_utc(),
+        // the format has no offset, the timestamp is taken to be in UTC
+        Err(_) => parsed
+            .to_naive_datetime_with_offset(0)
+            .or_else(|_| parsed.to_naive_date().map(|date| date.into()))
+            .ok()?,
+    };
+    datetime.and_utc().timestamp_nanos_opt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+
+    #[test]
+    fn test_regexp_extract_timestamp() {
+        let values = StringArray::from(vec![
+            Some("ts=2024-03-05T10:15:00Z level=info"),
+            Some("ts=2024-03-05T10:15:00+02:00 level=info"),
+            Some("ts=2024-13-45T99:00:00Z level=info"),
+            Some("level=info"),
+            None,
+        ]);
+        let patterns = StringArray::from(vec![r"ts=(\S+)"]);
+        let group_indices = Int64Array::from(vec![1; 5]);
+        let formats = StringArray::from(vec!["%+"]);
+
+        let result = regexp_extract_timestamp(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+            Arc::new(formats),
+        ])
+        .unwrap();
+
+        // an unparseable capture and a value without a match yield NULL
+        let expected = TimestampNanosecondArray::from(vec![
+            Some(1_709_633_700_000_000_000),
+            Some(1_709_626_500_000_000_000),
+            None,
+            None,
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_timestamp_custom_format() {
+        let values = StringArray::from(vec![
+            r#"127.0.0.1 - - [05/Mar/2024:10:15:00 +0000] "GET / HTTP/1.1" 200"#,
+            r#"127.0.0.1 - - [2024-03-05 10:15:00] "GET / HTTP/1.1" 200"#,
+            "05.03.2024",
+        ]);
+        let patterns = StringArray::from(vec![r"\[([^\]]+)\]", r"\[([^\]]+)\]", "(.+)"]);
+        let group_indices = Int64Array::from(vec![1, 1, 1]);
+        // the second row uses the format of the first one, it does not parse
+        let formats = StringArray::from(vec![
+            "%d/%b/%Y:%H:%M:%S %z",
+            "%d/%b/%Y:%H:%M:%S %z",
+            "%d.%m.%Y",
+        ]);
+
+        let result = regexp_extract_timestamp(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(group_indices),
+            Arc::new(formats),
+        ])
+        .unwrap();
+
+        let expected = TimestampNanosecondArray::from(vec![
+            Some(1_709_633_700_000_000_000),
+            None,
+            Some(1_709_596_800_000_000_000),
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
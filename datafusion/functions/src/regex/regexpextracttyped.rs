@@ -0,0 +1,439 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{
+    invoke_materialized, RegexpExtractConfig, RowPatterns,
+};
+use arrow::array::{
+    Array, ArrayRef, AsArray, Float64Array, Int64Array, StringArray, StructArray,
+};
+use arrow::buffer::NullBuffer;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::{exec_err, internal_err, plan_err, Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ReturnFieldArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts the named capture groups of the first [regular expression](https://docs.rs/regex/latest/regex/#syntax) match from a string into a struct, parsing each group as the type the schema gives it. This parses a log line into typed columns in one pass. A field is NULL if its group did not participate in the match, is not a group of the regular expression or cannot be parsed as the type. If the regular expression does not match, all the fields are NULL.",
+    syntax_example = "regexp_extract_typed(str, regexp, schema)",
+    sql_example = r#"```sql
+> select regexp_extract_typed('GET /index 200 0.25', '(?<path>/\S*) (?<status>\d+) (?<secs>\S+)', 'status Int64, secs Float64, path Utf8');
++---------------------------------------------------------------------------------------------------------------------------------------------------+
+| regexp_extract_typed(Utf8("GET /index 200 0.25"),Utf8("(?<path>/\S*) (?<status>\d+) (?<secs>\S+)"),Utf8("status Int64, secs Float64, path Utf8")) |
++---------------------------------------------------------------------------------------------------------------------------------------------------+
+| {status: 200, secs: 0.25, path: /index}                                                                                                           |
++---------------------------------------------------------------------------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "schema",
+        description = "Constant string of the fields of the result, separated by commas. Each field is the name of a capture group followed by its type: `Int64`, `Float64` or `Utf8`."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractTypedFunc {
+    signature: Signature,
+    max_groups: usize,
+}
+
+impl Default for RegexpExtractTypedFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractTypedFunc {
+    pub fn new() -> Self {
+        Self::new_with_config(&ConfigOptions::default())
+    }
+
+    /// Creates the function with the maximum number of groups of its schema
+    /// taken from `regexp_extract.typed_max_groups`.
+    pub fn new_with_config(config: &ConfigOptions) -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            max_groups: RegexpExtractConfig::from_config(config).typed_max_groups,
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractTypedFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_typed"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be called instead")
+    }
+
+    fn with_updated_config(&self, config: &ConfigOptions) -> Option<ScalarUDF> {
+        Some(Self::new_with_config(config).into())
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        let schema = args.scalar_arguments.get(2).copied().flatten();
+        let fields = typed_fields(schema)?;
+        if fields.len() > self.max_groups {
+            return plan_err!(
+                "regexp_extract_typed schema lists {} groups, more than the maximum of {}. The maximum can be raised with regexp_extract.typed_max_groups",
+                fields.len(),
+                self.max_groups
+            );
+        }
+        Ok(Field::new(self.name(), DataType::Struct(fields), true).into())
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        let args = &args.args;
+        if args.len() != 3 {
+            return exec_err!(
+                "regexp_extract_typed was called with {} arguments. It requires 3.",
+                args.len()
+            );
+        }
+        let schema = match &args[2] {
+            ColumnarValue::Scalar(schema) => Some(schema),
+            ColumnarValue::Array(_) => None,
+        };
+        let fields = typed_fields(schema)?;
+
+        let args = &args[..2];
+        invoke_materialized(args, &[1], |args| regexp_extract_typed(args, &fields))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 3 {
+            return plan_err!(
+                "regexp_extract_typed requires 3 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match data_type {
+                Utf8 | LargeUtf8 | Utf8View | Null => Ok(Utf8),
+                other => plan_err!(
+                    "regexp_extract_typed argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Parses the constant `schema` argument into the fields of the struct
+/// returned by `regexp_extract_typed`.
+fn typed_fields(schema: Option<&ScalarValue>) -> Result<Fields> {
+    let Some(schema) = schema.and_then(|schema| schema.try_as_str().flatten()) else {
+        return plan_err!(
+            "regexp_extract_typed requires its schema argument to be a constant string"
+        );
+    };
+    parse_typed_schema(schema)
+}
+
+/// Parses a schema like `status Int64, secs Float64, path Utf8` into the
+/// fields of the same names and types.
+pub fn parse_typed_schema(schema: &str) -> Result<Fields> {
+    let mut names = HashSet::new();
+    let fields = schema
+        .split(',')
+        .map(|entry| {
+            let (name, data_type) = match entry.split_whitespace().collect::<Vec<_>>()[..]
+            {
+                [name, data_type] => (name, data_type),
+                _ => {
+                    return plan_err!(
+                        "regexp_extract_typed schema entry '{}' must be a group name followed by a type",
+                        entry.trim()
+                    )
+                }
+            };
+            let data_type = match data_type {
+                t if t.eq_ignore_ascii_case("Int64") => DataType::Int64,
+                t if t.eq_ignore_ascii_case("Float64") => DataType::Float64,
+                t if t.eq_ignore_ascii_case("Utf8") => DataType::Utf8,
+                other => {
+                    return plan_err!(
+                        "regexp_extract_typed type of group '{name}' must be one of Int64, Float64, Utf8, got {other}"
+                    )
+                }
+            };
+            if !names.insert(name) {
+                return plan_err!(
+                    "regexp_extract_typed schema lists the group '{name}' more than once"
+                );
+            }
+            Ok(Field::new(name, data_type, true))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Fields::from(fields))
+}
+
+/// Extracts the named capture groups of the first match of the pattern
+/// `args[1]` in each string of `args[0]` into a struct of `fields`, parsing
+/// each group as the type of its field.
+///
+/// The pattern may be a single element array, in which case it is applied to
+/// every row. The strings are expected to be `Utf8` and `fields` to be
+/// `Int64`, `Float64` or `Utf8`, see [`parse_typed_schema`]. A NULL string or
+/// pattern yields a NULL struct, and a group that is missing or cannot be
+/// parsed a NULL field.
+pub fn regexp_extract_typed(args: &[ArrayRef], fields: &Fields) -> Result<ArrayRef> {
+    if args.len() != 2 {
+        return exec_err!(
+            "regexp_extract_typed was called with {} arguments. It requires 2 besides the schema.",
+            args.len()
+        );
+    }
+    if let Some(arg) = args.iter().find(|arg| arg.data_type() != &DataType::Utf8) {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_typed",
+            arg.data_type()
+        );
+    }
+
+    let values = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let mut patterns =
+        RowPatterns::try_new("regexp_extract_typed", pattern_array, values.len())?;
+
+    // the captured strings of each field, parsed once all rows are matched
+    let mut captures = vec![Vec::with_capacity(values.len()); fields.len()];
+    for i in 0..values.len() {
+        if values.is_null(i) || patterns.is_null(i) {
+            captures.iter_mut().for_each(|field| field.push(None));
+            continue;
+        }
+        let regex = patterns.get(i)?;
+        push_named_groups(&mut captures, fields, regex, values.value(i));
+    }
+
+    let arrays = fields
+        .iter()
+        .zip(captures)
+        .map(|(field, captures)| -> ArrayRef {
+            let captures = captures.into_iter();
+            match field.data_type() {
+                DataType::Int64 => Arc::new(
+                    captures
+                        .map(|c| c.and_then(|c| c.parse::<i64>().ok()))
+                        .collect::<Int64Array>(),
+                ),
+                DataType::Float64 => Arc::new(
+                    captures
+                        .map(|c| c.and_then(|c| c.parse::<f64>().ok()))
+                        .collect::<Float64Array>(),
+                ),
+                _ => Arc::new(captures.collect::<StringArray>()),
+            }
+        })
+        .collect();
+    let nulls = NullBuffer::union(
+        values.nulls(),
+        pattern_nulls(pattern_array, values.len()).as_ref(),
+    );
+    Ok(Arc::new(StructArray::try_new(
+        fields.clone(),
+        arrays,
+        nulls,
+    )?))
+}
+
+/// Returns the nulls of the rows whose pattern is NULL.
+fn pattern_nulls(pattern_array: &StringArray, len: usize) -> Option<NullBuffer> {
+    match pattern_array.len() {
+        1 if pattern_array.is_null(0) => Some(NullBuffer::new_null(len)),
+        1 => None,
+        _ => pattern_array.nulls().cloned(),
+    }
+}
+
+/// Appends the group of each of `fields` captured by the first match of
+/// `regex` in `value` to the captures of the field, or `None` if there is no
+/// such group or it did not participate in the match.
+fn push_named_groups<'h>(
+    captures: &mut [Vec<Option<&'h str>>],
+    fields: &Fields,
+    regex: &Regex,
+    value: &'h str,
+) {
+    let matched = regex.captures(value);
+    for (field, field_captures) in fields.iter().zip(captures) {
+        let group = matched
+            .as_ref()
+            .and_then(|matched| matched.name(field.name()));
+        field_captures.push(group.map(|group| group.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regexp_extract_typed() {
+        let values = StringArray::from(vec![
+            Some("GET /index 200 0.25"),
+            Some("GET /missing 404 slow"),
+            Some("POST /form x1 1e3"),
+            Some("not a request"),
+            None,
+        ]);
+        let patterns =
+            StringArray::from(vec![r"(?<path>/\S*) (?<status>\S+) (?<secs>\S+)"]);
+        let fields = parse_typed_schema("status Int64, secs float64,path Utf8").unwrap();
+
+        let result =
+            regexp_extract_typed(&[Arc::new(values), Arc::new(patterns)], &fields)
+                .unwrap();
+        let result = result.as_struct();
+
+        // the fields that do not parse are NULL, the struct is NULL only for
+        // a NULL string
+        let status = Int64Array::from(vec![Some(200), Some(404), None, None, None]);
+        let secs = Float64Array::from(vec![Some(0.25), None, Some(1000.0), None, None]);
+        let path = StringArray::from(vec![
+            Some("/index"),
+            Some("/missing"),
+            Some("/form"),
+            None,
+            None,
+        ]);
+        assert_eq!(result.column(0).as_ref(), &status);
+        assert_eq!(result.column(1).as_ref(), &secs);
+        assert_eq!(result.column(2).as_ref(), &path);
+        assert_eq!(result.null_count(), 1);
+        assert!(result.is_null(4));
+        assert_eq!(
+            result
+                .fields()
+                .iter()
+                .map(|f| f.name().as_str())
+                .collect::<Vec<_>>(),
+            ["status", "secs", "path"]
+        );
+    }
+
+    #[test]
+    fn test_regexp_extract_typed_array_patterns() {
+        let values = StringArray::from(vec!["n=1", "n=2", "n=3"]);
+        let patterns =
+            StringArray::from(vec![Some(r"n=(?<n>\d)"), None, Some(r"(?<m>\d)")]);
+        let fields = parse_typed_schema("n Int64").unwrap();
+
+        let result =
+            regexp_extract_typed(&[Arc::new(values), Arc::new(patterns)], &fields)
+                .unwrap();
+        let result = result.as_struct();
+
+        // a group the pattern does not have yields NULL
+        assert_eq!(
+            result.column(0).as_ref(),
+            &Int64Array::from(vec![Some(1), None, None])
+        );
+        assert!(result.is_null(1));
+        assert!(result.is_valid(2));
+    }
+
+    #[test]
+    fn test_regexp_extract_typed_max_groups() {
+        let mut config = ConfigOptions::default();
+        config.extensions.insert(RegexpExtractConfig {
+            typed_max_groups: 2,
+            ..Default::default()
+        });
+        let func = RegexpExtractTypedFunc::new_with_config(&config);
+        let arg_fields: Vec<FieldRef> = (0..3)
+            .map(|_| Arc::new(Field::new("a", DataType::Utf8, true)))
+            .collect();
+        let return_field = |schema: &str| {
+            let schema = ScalarValue::from(schema);
+            func.return_field_from_args(ReturnFieldArgs {
+                arg_fields: &arg_fields,
+                scalar_arguments: &[None, None, Some(&schema)],
+            })
+        };
+
+        let field = return_field("a Int64, b Utf8").unwrap();
+        assert!(
+            matches!(field.data_type(), DataType::Struct(fields) if fields.len() == 2)
+        );
+
+        let err = return_field("a Int64, b Utf8, c Float64").unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: regexp_extract_typed schema lists 3 groups, more than the maximum of 2. The maximum can be raised with regexp_extract.typed_max_groups"
+        );
+    }
+
+    #[test]
+    fn test_parse_typed_schema_errors() {
+        for (schema, expected) in [
+            (
+                "a Int64, b",
+                "schema entry 'b' must be a group name followed by a type",
+            ),
+            (
+                "a Int32",
+                "type of group 'a' must be one of Int64, Float64, Utf8, got Int32",
+            ),
+            (
+                "a Int64, a Utf8",
+                "schema lists the group 'a' more than once",
+            ),
+        ] {
+            let err = parse_typed_schema(schema).unwrap_err();
+            assert_eq!(
+                err.strip_backtrace(),
+                format!("Error during planning: regexp_extract_typed {expected}")
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, regexp_extract};
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array, StringArray};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Extracts a literal word from a string if it appears as a whole word, that is not preceded or followed by a word character. Returns an empty string if the word only appears inside larger words or not at all. The word is matched literally, characters such as `.` or `+` have no special meaning.",
+    syntax_example = "regexp_extract_word(str, word)",
+    sql_example = r#"```sql
+> select regexp_extract_word('the cat sat', 'cat');
++------------------------------------------------------+
+| regexp_extract_word(Utf8("the cat sat"),Utf8("cat")) |
++------------------------------------------------------+
+| cat                                                  |
++------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "word",
+        description = "Word to extract, which should start and end with a word character. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpExtractWordFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpExtractWordFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpExtractWordFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpExtractWordFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_extract_word"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_extract_word)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 2 {
+            return plan_err!(
+                "regexp_extract_word requires 2 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match data_type {
+                Utf8 | LargeUtf8 | Utf8View | Null => Ok(Utf8),
+                other => plan_err!(
+                    "regexp_extract_word argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Extracts the word `args[1]` from each string of `args[0]` if it appears
+/// as a whole word.
+///
+/// The words may be a single element array, in which case the word is looked
+/// for in every row. Both arguments are expected to be `Utf8`.
+pub fn regexp_extract_word(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 2 {
+        return exec_err!(
+            "regexp_extract_word was called with {} arguments. It requires 2.",
+            args.len()
+        );
+    }
+    if let Some(arg) = args.iter().find(|arg| arg.data_type() != &DataType::Utf8) {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_extract_word",
+            arg.data_type()
+        );
+    }
+
+    // the word is matched literally, anchored to word boundaries
+    let patterns = args[1]
+        .as_string::<i32>()
+        .iter()
+        .map(|word| word.map(|word| format!(r"\b{}\b", regex::escape(word))))
+        .collect::<StringArray>();
+    let entire_match = Int64Array::from_value(0, args[0].len());
+
+    regexp_extract(&[
+        Arc::clone(&args[0]),
+        Arc::new(patterns),
+        Arc::new(entire_match),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regexp_extract_word() {
+        let values = StringArray::from(vec![
+            Some("the cat sat"),
+            Some("concatenate"),
+            Some("cats and dogs"),
+            Some("cat"),
+            Some("a bobcat"),
+            None,
+        ]);
+        let words = StringArray::from(vec!["cat"]);
+
+        let result = regexp_extract_word(&[Arc::new(values), Arc::new(words)]).unwrap();
+        // the word inside larger words is not extracted
+        let expected = StringArray::from(vec![
+            Some("cat"),
+            Some(""),
+            Some(""),
+            Some("cat"),
+            Some(""),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_extract_word_escapes_the_word() {
+        let values = StringArray::from(vec![
+            Some("version 1.5 released"),
+            Some("version 105 released"),
+            Some("a (b) c"),
+            Some("x"),
+        ]);
+        let words = StringArray::from(vec![Some("1.5"), Some("1.5"), Some("b"), None]);
+
+        let result = regexp_extract_word(&[Arc::new(values), Arc::new(words)]).unwrap();
+        // `.` only matches itself
+        let expected = StringArray::from(vec![Some("1.5"), Some(""), Some("b"), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
@@ -0,0 +1,209 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::compile_and_cache_pattern;
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array, StringArrayType};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns the number of capture groups of a [regular expression](https://docs.rs/regex/latest/regex/#syntax), not counting group 0 of the entire match. The valid group indices of `regexp_extract` range from 0 to this number.",
+    syntax_example = "regexp_group_count(regexp)",
+    sql_example = r#"```sql
+> select regexp_group_count('(?<year>\d+)-(\d+)(?:-\d+)?');
++---------------------------------------------------------+
+| regexp_group_count(Utf8("(?<year>\d+)-(\d+)(?:-\d+)?")) |
++---------------------------------------------------------+
+| 2                                                       |
++---------------------------------------------------------+
+```"#,
+    argument(
+        name = "regexp",
+        description = "Regular expression to count the capture groups of.
+            Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpGroupCountFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpGroupCountFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpGroupCountFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View]),
+                    TypeSignature::Exact(vec![Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpGroupCountFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_group_count"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Int64)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        match args.args.as_slice() {
+            [ColumnarValue::Scalar(pattern)] => {
+                let result = regexp_group_count(&[pattern.to_array()?]);
+                // If all inputs are scalar, keeps output as scalar
+                let result = result.and_then(|arr| ScalarValue::try_from_array(&arr, 0));
+                result.map(ColumnarValue::Scalar)
+            }
+            [ColumnarValue::Array(patterns)] => {
+                regexp_group_count(&[Arc::clone(patterns)]).map(ColumnarValue::Array)
+            }
+            args => exec_err!(
+                "regexp_group_count was called with {} arguments. It requires 1.",
+                args.len()
+            ),
+        }
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the number of capture groups of each pattern of `args[0]`.
+///
+/// Each distinct pattern is compiled once.
+pub fn regexp_group_count(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 1 {
+        return exec_err!(
+            "regexp_group_count was called with {} arguments. It requires 1.",
+            args.len()
+        );
+    }
+
+    match args[0].data_type() {
+        DataType::Utf8 => group_counts(args[0].as_string::<i32>()),
+        DataType::LargeUtf8 => group_counts(args[0].as_string::<i64>()),
+        DataType::Utf8View => group_counts(args[0].as_string_view()),
+        other => {
+            exec_err!("Unsupported data type {other:?} for function regexp_group_count")
+        }
+    }
+}
+
+fn group_counts<'a, S>(patterns: S) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a>,
+{
+    let mut regex_cache = HashMap::new();
+    let result = (0..patterns.len())
+        .map(|i| {
+            if patterns.is_null(i) {
+                return Ok(None);
+            }
+            let regex =
+                compile_and_cache_pattern(patterns.value(i), None, &mut regex_cache)?;
+            // the group 0 of the entire match is always present
+            Ok(Some(regex.captures_len() as i64 - 1))
+        })
+        .collect::<Result<Int64Array>>()?;
+    Ok(Arc::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{StringArray, StringViewArray};
+
+    #[test]
+    fn test_regexp_group_count() {
+        let patterns = StringArray::from(vec![
+            Some(r"(\d+)-(\d+)"),
+            Some("abc"),
+            Some("(?:a)(?i:b)"),
+            Some(r"(?<year>\d+)(x)?(?P<day>\d+)"),
+            Some(r"((a)(b))"),
+            Some(""),
+            None,
+        ]);
+
+        let result = regexp_group_count(&[Arc::new(patterns)]).unwrap();
+        // non-capturing groups are not counted
+        let expected = Int64Array::from(vec![
+            Some(2),
+            Some(0),
+            Some(0),
+            Some(3),
+            Some(3),
+            Some(0),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_group_count_utf8view() {
+        let patterns = StringViewArray::from(vec!["(a)", "(a)", "b"]);
+
+        let result = regexp_group_count(&[Arc::new(patterns)]).unwrap();
+        let expected = Int64Array::from(vec![1, 1, 0]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_group_count_invalid_pattern() {
+        let err = regexp_group_count(&[Arc::new(StringArray::from(vec!["(a"]))])
+            .expect_err("invalid pattern should fail");
+        assert!(
+            err.strip_backtrace()
+                .starts_with("Execution error: unable to compile pattern '(a'"),
+            "unexpected error: {err}"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::compile_pattern;
+use arrow::array::{ListArray, ListBuilder, StringBuilder};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns the names of the capture groups of a [regular expression](https://docs.rs/regex/latest/regex/#syntax), in the order of the groups, with NULL for unnamed groups. The entire match is not included, so the element at index `i` is the name of capture group `i`. Useful to build `regexp_extract` calls for the groups of a pattern.",
+    syntax_example = "regexp_group_names(regexp)",
+    sql_example = r#"```sql
+> select regexp_group_names('(?<year>\d+)-(\d+)-(?<day>\d+)');
++------------------------------------------------------------+
+| regexp_group_names(Utf8("(?<year>\d+)-(\d+)-(?<day>\d+)")) |
++------------------------------------------------------------+
+| [year, NULL, day]                                          |
++------------------------------------------------------------+
+```"#,
+    argument(
+        name = "regexp",
+        description = "Regular expression to list the capture group names of. Must be a constant."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpGroupNamesFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpGroupNamesFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpGroupNamesFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View]),
+                    TypeSignature::Exact(vec![Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpGroupNamesFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_group_names"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::new_list(DataType::Utf8, true))
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        let [ColumnarValue::Scalar(pattern)] = args.args.as_slice() else {
+            return exec_err!("regexp_group_names pattern must be a constant");
+        };
+        let pattern = match pattern {
+            ScalarValue::Utf8(pattern)
+            | ScalarValue::LargeUtf8(pattern)
+            | ScalarValue::Utf8View(pattern) => pattern,
+            other => {
+                return exec_err!(
+                    "Unsupported data type {:?} for function regexp_group_names",
+                    other.data_type()
+                )
+            }
+        };
+
+        let names = match pattern {
+            Some(pattern) => group_names(&compile_pattern(pattern, None)?),
+            None => {
+                let mut builder = ListBuilder::new(StringBuilder::new());
+                builder.append_null();
+                builder.finish()
+            }
+        };
+        ScalarValue::try_from_array(&names, 0).map(ColumnarValue::Scalar)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns a single element list of the capture group names of `regex`,
+/// without the unnamed group 0 of the entire match.
+fn group_names(regex: &Regex) -> ListArray {
+    let mut builder = ListBuilder::new(StringBuilder::new());
+    regex
+        .capture_names()
+        .skip(1)
+        .for_each(|name| builder.values().append_option(name));
+    builder.append(true);
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, AsArray, StringArray};
+    use arrow::datatypes::Field;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_expr::ScalarFunctionArgs;
+    use std::sync::Arc;
+
+    fn invoke(pattern: ColumnarValue) -> Result<ColumnarValue> {
+        RegexpGroupNamesFunc::new().invoke_with_args(ScalarFunctionArgs {
+            args: vec![pattern],
+            arg_fields: vec![Field::new("arg_0", DataType::Utf8, true).into()],
+            number_rows: 1,
+            return_field: Field::new("f", DataType::new_list(DataType::Utf8, true), true)
+                .into(),
+            config_options: Arc::new(ConfigOptions::default()),
+        })
+    }
+
+    #[test]
+    fn test_regexp_group_names() -> Result<()> {
+        let pattern = ScalarValue::from(r"(?<year>\d+)-(\d+)-(?P<day>\d+)(?:Z)?");
+        let ColumnarValue::Scalar(ScalarValue::List(names)) =
+            invoke(ColumnarValue::Scalar(pattern))?
+        else {
+            panic!("expected a list scalar");
+        };
+
+        // non-capturing groups are not listed
+        let names = names.value(0);
+        let names = names.as_string::<i32>().iter().collect::<Vec<_>>();
+        assert_eq!(names, vec![Some("year"), None, Some("day")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_group_names_without_groups() -> Result<()> {
+        let ColumnarValue::Scalar(ScalarValue::List(names)) =
+            invoke(ColumnarValue::Scalar(ScalarValue::from("abc")))?
+        else {
+            panic!("expected a list scalar");
+        };
+        assert!(names.is_valid(0));
+        assert_eq!(names.value(0).len(), 0);
+
+        let ColumnarValue::Scalar(ScalarValue::List(names)) =
+            invoke(ColumnarValue::Scalar(ScalarValue::Utf8(None)))?
+        else {
+            panic!("expected a list scalar");
+        };
+        assert!(names.is_null(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_group_names_requires_constant() {
+        let patterns = Arc::new(StringArray::from(vec!["(a)"]));
+        let err = invoke(ColumnarValue::Array(patterns))
+            .expect_err("pattern column should fail");
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_group_names pattern must be a constant"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use crate::regex::regexpextract::{invoke_materialized, RowPatterns};
+use arrow::array::{Array, ArrayRef, AsArray, StringArray};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::{Captures, Regex, Replacer};
+use std::any::Any;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Wraps every match of a [regular expression](https://docs.rs/regex/latest/regex/#syntax) in a string with an opening and a closing marker, for example to highlight the matches of a search. Returns the whole string with the markers inserted. The matches do not overlap, and empty matches are not wrapped. The markers are inserted literally, characters such as `$` have no special meaning.",
+    syntax_example = "regexp_highlight(str, regexp, open, close)",
+    sql_example = r#"```sql
+> select regexp_highlight('a cat and a cap', 'ca[tp]', '<b>', '</b>');
++-----------------------------------------------------------------------------------+
+| regexp_highlight(Utf8("a cat and a cap"),Utf8("ca[tp]"),Utf8("<b>"),Utf8("</b>")) |
++-----------------------------------------------------------------------------------+
+| a <b>cat</b> and a <b>cap</b>                                                     |
++-----------------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "str", prefix = "String"),
+    argument(
+        name = "regexp",
+        description = "Regular expression to match against.
+            Can be a constant, column, or function."
+    ),
+    argument(
+        name = "open",
+        description = "Marker inserted before each match. Can be a constant, column, or function."
+    ),
+    argument(
+        name = "close",
+        description = "Marker inserted after each match. Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpHighlightFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpHighlightFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpHighlightFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpHighlightFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_highlight"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        invoke_materialized(&args.args, &[1], regexp_highlight)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        use DataType::*;
+        if arg_types.len() != 4 {
+            return plan_err!(
+                "regexp_highlight requires 4 arguments, got {}",
+                arg_types.len()
+            );
+        }
+
+        arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, data_type)| match data_type {
+                Utf8 | LargeUtf8 | Utf8View | Null => Ok(Utf8),
+                other => plan_err!(
+                    "regexp_highlight argument {idx} must be a string, got {other}"
+                ),
+            })
+            .collect()
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Wraps every match of `args[1]` in each string of `args[0]` with the
+/// markers `args[2]` and `args[3]`.
+///
+/// The pattern may be a single element array, in which case it is applied to
+/// every row. All arguments are expected to be `Utf8`.
+pub fn regexp_highlight(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 4 {
+        return exec_err!(
+            "regexp_highlight was called with {} arguments. It requires 4.",
+            args.len()
+        );
+    }
+    if let Some(arg) = args.iter().find(|arg| arg.data_type() != &DataType::Utf8) {
+        return exec_err!(
+            "Unsupported data type {:?} for function regexp_highlight",
+            arg.data_type()
+        );
+    }
+
+    let values = args[0].as_string::<i32>();
+    let pattern_array = args[1].as_string::<i32>();
+    let open_array = args[2].as_string::<i32>();
+    let close_array = args[3].as_string::<i32>();
+    let mut patterns =
+        RowPatterns::try_new("regexp_highlight", pattern_array, values.len())?;
+
+    let result = (0..values.len())
+        .map(|i| {
+            if values.is_null(i)
+                || patterns.is_null(i)
+                || open_array.is_null(i)
+                || close_array.is_null(i)
+            {
+                return Ok(None);
+            }
+            let regex = patterns.get(i)?;
+            let markers = Markers {
+                open: open_array.value(i),
+                close: close_array.value(i),
+            };
+            Ok(Some(highlight(regex, values.value(i), markers)))
+        })
+        .collect::<Result<StringArray>>()?;
+    Ok(Arc::new(result))
+}
+
+/// Returns `value` with every non-empty match of `regex` wrapped in the
+/// `markers`.
+fn highlight(regex: &Regex, value: &str, markers: Markers) -> String {
+    regex.replace_all(value, markers).into_owned()
+}
+
+/// A [`Replacer`] that keeps the entire match and surrounds it with the
+/// markers, which unlike a `open$0close` replacement string are never
+/// expanded.
+struct Markers<'a> {
+    open: &'a str,
+    close: &'a str,
+}
+
+impl Replacer for Markers<'_> {
+    fn replace_append(&mut self, caps: &Captures<'_>, dst: &mut String) {
+        let matched = &caps[0];
+        if matched.is_empty() {
+            return;
+        }
+        dst.push_str(self.open);
+        dst.push_str(matched);
+        dst.push_str(self.close);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regexp_highlight() {
+        let values = StringArray::from(vec![
+            Some("a cat and a cap"),
+            Some("no match"),
+            Some("catcat"),
+            Some(""),
+            None,
+        ]);
+        let patterns = StringArray::from(vec!["ca[tp]"]);
+        let opens = StringArray::from(vec!["<b>"; 5]);
+        let closes = StringArray::from(vec!["</b>"; 5]);
+
+        let result = regexp_highlight(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(opens),
+            Arc::new(closes),
+        ])
+        .unwrap();
+        let expected = StringArray::from(vec![
+            Some("a <b>cat</b> and a <b>cap</b>"),
+            Some("no match"),
+            Some("<b>cat</b><b>cat</b>"),
+            Some(""),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_highlight_markers() {
+        let values = StringArray::from(vec!["1 and 22", "1 and 22", "aab", "x"]);
+        let patterns = StringArray::from(vec![r"\d+", r"\d+", "a*", r"\d"]);
+        // the markers are inserted literally, and may be NULL or empty
+        let opens = StringArray::from(vec![Some("$0["), Some(""), Some("("), None]);
+        let closes = StringArray::from(vec![Some("]"), Some("!"), Some(")"), Some("")]);
+
+        let result = regexp_highlight(&[
+            Arc::new(values),
+            Arc::new(patterns),
+            Arc::new(opens),
+            Arc::new(closes),
+        ])
+        .unwrap();
+        // the empty matches of `a*` around `b` are not wrapped
+        let expected = StringArray::from(vec![
+            Some("$0[1] and $0[22]"),
+            Some("1! and 22!"),
+            Some("(aa)b"),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
@@ -75,6 +75,54 @@ use crate::regex::compile_and_cache_regex;
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct RegexpInstrFunc {
     signature: Signature,
+    offset_unit: OffsetUnit,
+}
+
+/// The unit of the positions returned by [`RegexpInstrFunc`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetUnit {
+    /// Characters, that is Unicode scalar values
+    #[default]
+    Char,
+    /// UTF-8 bytes
+    Byte,
+    /// UTF-16 code units, which JavaScript and many JSON tools index strings
+    /// by. A character outside the Basic Multilingual Plane, such as an emoji,
+    /// is a surrogate pair of two code units.
+    Utf16,
+}
+
+impl OffsetUnit {
+    /// Returns the 1-based offset in this unit of the byte offset
+    /// `byte_offset` in `value`, which must be at a character boundary.
+    fn offset(self, value: &str, byte_offset: usize) -> Result<i64, ArrowError> {
+        let prefix = &value[..byte_offset];
+        // an ASCII prefix has as many characters and code units as bytes,
+        // without counting them
+        let offset = match self {
+            OffsetUnit::Byte => prefix.len(),
+            _ if prefix.is_ascii() => prefix.len(),
+            OffsetUnit::Char => prefix.chars().count(),
+            OffsetUnit::Utf16 => prefix.chars().map(char::len_utf16).sum(),
+        };
+        i64::try_from(offset)
+            .ok()
+            .and_then(|offset| offset.checked_add(1))
+            .ok_or_else(|| {
+                ArrowError::ComputeError(format!(
+                    "regexp_instr() {} offset {offset} does not fit in Int64",
+                    self.name()
+                ))
+            })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            OffsetUnit::Char => "character",
+            OffsetUnit::Byte => "byte",
+            OffsetUnit::Utf16 => "UTF-16",
+        }
+    }
 }
 
 impl Default for RegexpInstrFunc {
@@ -104,8 +152,16 @@ impl RegexpInstrFunc {
                 ],
                 Volatility::Immutable,
             ),
+            offset_unit: OffsetUnit::default(),
         }
     }
+
+    /// Returns the positions in `offset_unit` instead of in characters. The
+    /// `start` argument is still a character position.
+    pub fn with_offset_unit(mut self, offset_unit: OffsetUnit) -> Self {
+        self.offset_unit = offset_unit;
+        self
+    }
 }
 
 impl ScalarUDFImpl for RegexpInstrFunc {
@@ -145,7 +201,7 @@ impl ScalarUDFImpl for RegexpInstrFunc {
             .map(|arg| arg.to_array(inferred_length))
             .collect::<Result<Vec<_>>>()?;
 
-        let result = regexp_instr_func(&args);
+        let result = regexp_instr_func_with_offset_unit(&args, self.offset_unit);
         if is_scalar {
             // If all inputs are scalar, keeps output as scalar
             let result = result.and_then(|arr| ScalarValue::try_from_array(&arr, 0));
@@ -161,6 +217,15 @@ impl ScalarUDFImpl for RegexpInstrFunc {
 }
 
 pub fn regexp_instr_func(args: &[ArrayRef]) -> Result<ArrayRef> {
+    regexp_instr_func_with_offset_unit(args, OffsetUnit::default())
+}
+
+/// Like [`regexp_instr_func`], but returns the positions in `offset_unit`
+/// instead of in characters.
+pub fn regexp_instr_func_with_offset_unit(
+    args: &[ArrayRef],
+    offset_unit: OffsetUnit,
+) -> Result<ArrayRef> {
     let args_len = args.len();
     if !(2..=6).contains(&args_len) {
         return exec_err!("regexp_instr was called with {args_len} arguments. It requires at least 2 and at most 6.");
@@ -176,13 +241,14 @@ pub fn regexp_instr_func(args: &[ArrayRef]) -> Result<ArrayRef> {
         }
     }
 
-    regexp_instr(
+    regexp_instr_with_offset_unit(
         values,
         &args[1],
         if args_len > 2 { Some(&args[2]) } else { None },
         if args_len > 3 { Some(&args[3]) } else { None },
         if args_len > 4 { Some(&args[4]) } else { None },
         if args_len > 5 { Some(&args[5]) } else { None },
+        offset_unit,
     )
     .map_err(|e| e.into())
 }
@@ -212,6 +278,26 @@ pub fn regexp_instr(
     nth_array: Option<&dyn Datum>,
     flags_array: Option<&dyn Datum>,
     subexpr_array: Option<&dyn Datum>,
+) -> Result<ArrayRef, ArrowError> {
+    regexp_instr_with_offset_unit(
+        values,
+        regex_array,
+        start_array,
+        nth_array,
+        flags_array,
+        subexpr_array,
+        OffsetUnit::default(),
+    )
+}
+
+fn regexp_instr_with_offset_unit(
+    values: &dyn Array,
+    regex_array: &dyn Datum,
+    start_array: Option<&dyn Datum>,
+    nth_array: Option<&dyn Datum>,
+    flags_array: Option<&dyn Datum>,
+    subexpr_array: Option<&dyn Datum>,
+    offset_unit: OffsetUnit,
 ) -> Result<ArrayRef, ArrowError> {
     let (regex_array, _) = regex_array.get();
     let start_array = start_array.map(|start| {
@@ -232,53 +318,59 @@ pub fn regexp_instr(
     });
 
     match (values.data_type(), regex_array.data_type(), flags_array) {
-        (Utf8, Utf8, None) => regexp_instr_inner(
+        (Utf8, Utf8, None) => regexp_instr_inner_with_offset_unit(
             values.as_string::<i32>(),
             regex_array.as_string::<i32>(),
             start_array.map(|start| start.as_primitive::<Int64Type>()),
             nth_array.map(|nth| nth.as_primitive::<Int64Type>()),
             None,
             subexpr_array.map(|subexpr| subexpr.as_primitive::<Int64Type>()),
+            offset_unit,
         ),
-        (Utf8, Utf8, Some(flags_array)) if *flags_array.data_type() == Utf8 => regexp_instr_inner(
+        (Utf8, Utf8, Some(flags_array)) if *flags_array.data_type() == Utf8 => regexp_instr_inner_with_offset_unit(
             values.as_string::<i32>(),
             regex_array.as_string::<i32>(),
             start_array.map(|start| start.as_primitive::<Int64Type>()),
             nth_array.map(|nth| nth.as_primitive::<Int64Type>()),
             Some(flags_array.as_string::<i32>()),
             subexpr_array.map(|subexpr| subexpr.as_primitive::<Int64Type>()),
+            offset_unit,
         ),
-        (LargeUtf8, LargeUtf8, None) => regexp_instr_inner(
+        (LargeUtf8, LargeUtf8, None) => regexp_instr_inner_with_offset_unit(
             values.as_string::<i64>(),
             regex_array.as_string::<i64>(),
             start_array.map(|start| start.as_primitive::<Int64Type>()),
             nth_array.map(|nth| nth.as_primitive::<Int64Type>()),
             None,
             subexpr_array.map(|subexpr| subexpr.as_primitive::<Int64Type>()),
+            offset_unit,
         ),
-        (LargeUtf8, LargeUtf8, Some(flags_array)) if *flags_array.data_type() == LargeUtf8 => regexp_instr_inner(
+        (LargeUtf8, LargeUtf8, Some(flags_array)) if *flags_array.data_type() == LargeUtf8 => regexp_instr_inner_with_offset_unit(
             values.as_string::<i64>(),
             regex_array.as_string::<i64>(),
             start_array.map(|start| start.as_primitive::<Int64Type>()),
             nth_array.map(|nth| nth.as_primitive::<Int64Type>()),
             Some(flags_array.as_string::<i64>()),
             subexpr_array.map(|subexpr| subexpr.as_primitive::<Int64Type>()),
+            offset_unit,
         ),
-        (Utf8View, Utf8View, None) => regexp_instr_inner(
+        (Utf8View, Utf8View, None) => regexp_instr_inner_with_offset_unit(
             values.as_string_view(),
             regex_array.as_string_view(),
             start_array.map(|start| start.as_primitive::<Int64Type>()),
             nth_array.map(|nth| nth.as_primitive::<Int64Type>()),
             None,
             subexpr_array.map(|subexpr| subexpr.as_primitive::<Int64Type>()),
+            offset_unit,
         ),
-        (Utf8View, Utf8View, Some(flags_array)) if *flags_array.data_type() == Utf8View => regexp_instr_inner(
+        (Utf8View, Utf8View, Some(flags_array)) if *flags_array.data_type() == Utf8View => regexp_instr_inner_with_offset_unit(
             values.as_string_view(),
             regex_array.as_string_view(),
             start_array.map(|start| start.as_primitive::<Int64Type>()),
             nth_array.map(|nth| nth.as_primitive::<Int64Type>()),
             Some(flags_array.as_string_view()),
             subexpr_array.map(|subexpr| subexpr.as_primitive::<Int64Type>()),
+            offset_unit,
         ),
         _ => Err(ArrowError::ComputeError(
             "regexp_instr() expected the input arrays to be of type Utf8, LargeUtf8, or Utf8View and the data types of the values, regex_array, and flags_array to match".to_string(),
@@ -295,6 +387,30 @@ pub fn regexp_instr_inner<'a, S>(
     flags_array: Option<S>,
     subexp_array: Option<&Int64Array>,
 ) -> Result<ArrayRef, ArrowError>
+where
+    S: StringArrayType<'a>,
+{
+    regexp_instr_inner_with_offset_unit(
+        values,
+        regex_array,
+        start_array,
+        nth_array,
+        flags_array,
+        subexp_array,
+        OffsetUnit::default(),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn regexp_instr_inner_with_offset_unit<'a, S>(
+    values: S,
+    regex_array: S,
+    start_array: Option<&Int64Array>,
+    nth_array: Option<&Int64Array>,
+    flags_array: Option<S>,
+    subexp_array: Option<&Int64Array>,
+    offset_unit: OffsetUnit,
+) -> Result<ArrayRef, ArrowError>
 where
     S: StringArrayType<'a>,
 {
@@ -343,6 +459,7 @@ where
             *nth,
             *subexp,
             *flags,
+            offset_unit,
             &mut regex_cache,
         ),
     })
@@ -356,14 +473,15 @@ fn handle_subexp(
     subexpr: i64,
     value: &str,
     byte_start_offset: usize,
+    offset_unit: OffsetUnit,
 ) -> Result<Option<i64>, ArrowError> {
     if let Some(captures) = pattern.captures(search_slice) {
         if let Some(matched) = captures.get(subexpr as usize) {
-            // Convert byte offset relative to search_slice back to 1-based character offset
+            // Convert byte offset relative to search_slice back to 1-based offset
             // relative to the original `value` string.
-            let start_char_offset =
-                value[..byte_start_offset + matched.start()].chars().count() as i64 + 1;
-            return Ok(Some(start_char_offset));
+            return offset_unit
+                .offset(value, byte_start_offset + matched.start())
+                .map(Some);
         }
     }
     Ok(Some(0)) // Return 0 if the subexpression was not found
@@ -375,18 +493,35 @@ fn get_nth_match(
     n: i64,
     byte_start_offset: usize,
     value: &str,
+    offset_unit: OffsetUnit,
 ) -> Result<Option<i64>, ArrowError> {
     if let Some(mat) = pattern.find_iter(search_slice).nth((n - 1) as usize) {
-        // Convert byte offset relative to search_slice back to 1-based character offset
+        // Convert byte offset relative to search_slice back to 1-based offset
         // relative to the original `value` string.
-        let match_start_byte_offset = byte_start_offset + mat.start();
-        let match_start_char_offset =
-            value[..match_start_byte_offset].chars().count() as i64 + 1;
-        Ok(Some(match_start_char_offset))
+        offset_unit
+            .offset(value, byte_start_offset + mat.start())
+            .map(Some)
     } else {
         Ok(Some(0)) // Return 0 if the N-th match was not found
     }
 }
+
+/// Returns the byte offset of the 1-based character offset `start` in
+/// `value`, or `None` if `value` has fewer characters.
+fn byte_offset(value: &str, start: i64) -> Option<usize> {
+    let char_index = usize::try_from(start - 1).ok()?;
+    // up to an ASCII prefix every character is a single byte
+    if value
+        .as_bytes()
+        .get(..=char_index)
+        .is_some_and(|prefix| prefix.is_ascii())
+    {
+        return Some(char_index);
+    }
+    value.char_indices().nth(char_index).map(|(idx, _)| idx)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn get_index<'strings, 'cache>(
     value: Option<&str>,
     pattern: &'strings str,
@@ -394,6 +529,7 @@ fn get_index<'strings, 'cache>(
     n: i64,
     subexpr: i64,
     flags: Option<&'strings str>,
+    offset_unit: OffsetUnit,
     regex_cache: &'cache mut HashMap<(&'strings str, Option<&'strings str>), Regex>,
 ) -> Result<Option<i64>, ArrowError>
 where
@@ -418,38 +554,42 @@ where
         ));
     }
 
-    // --- Simplified byte_start_offset calculation ---
-    let total_chars = value.chars().count() as i64;
-    let byte_start_offset: usize = if start > total_chars {
+    let Some(byte_start_offset) = byte_offset(value, start) else {
         // If start is beyond the total characters, it means we start searching
         // after the string effectively. No matches possible.
         return Ok(Some(0));
-    } else {
-        // Get the byte offset for the (start - 1)-th character (0-based)
-        value
-            .char_indices()
-            .nth((start - 1) as usize)
-            .map(|(idx, _)| idx)
-            .unwrap_or(0) // Should not happen if start is valid and <= total_chars
     };
-    // --- End simplified calculation ---
 
     let search_slice = &value[byte_start_offset..];
 
     // Handle subexpression capturing first, as it takes precedence
     if subexpr > 0 {
-        return handle_subexp(pattern, search_slice, subexpr, value, byte_start_offset);
+        return handle_subexp(
+            pattern,
+            search_slice,
+            subexpr,
+            value,
+            byte_start_offset,
+            offset_unit,
+        );
     }
 
     // Use nth to get the N-th match (n is 1-based, nth is 0-based)
-    get_nth_match(pattern, search_slice, n, byte_start_offset, value)
+    get_nth_match(
+        pattern,
+        search_slice,
+        n,
+        byte_start_offset,
+        value,
+        offset_unit,
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use arrow::array::Int64Array;
-    use arrow::array::{GenericStringArray, StringViewArray};
+    use arrow::array::{GenericStringArray, StringArray, StringViewArray};
     use arrow::datatypes::Field;
     use datafusion_common::config::ConfigOptions;
     use datafusion_expr::ScalarFunctionArgs;
@@ -474,6 +614,120 @@ mod tests {
         test_case_sensitive_regexp_instr_array_nth::<StringViewArray>();
     }
 
+    #[test]
+    fn test_regexp_instr_long_multibyte_value() {
+        let prefix = "é".repeat(100_000);
+        let cases = [
+            (format!("{prefix}needle"), "needle", 1, 0, 100_001),
+            (format!("{prefix}needle"), "(n)eedle", 1, 1, 100_001),
+            // the search starts within the multibyte characters
+            (format!("{prefix}needle"), "needle", 50_000, 0, 100_001),
+            (format!("{prefix}needle"), "é", 100_000, 0, 100_000),
+            (format!("ab{prefix}x"), "x", 3, 0, 100_003),
+            // an ASCII prefix followed by a multibyte character
+            ("aaaaéx".to_string(), "x", 1, 0, 6),
+            (format!("{prefix}needle"), "needle", 100_008, 0, 0),
+        ];
+
+        for (value, regex, start, subexp, expected) in cases {
+            let result = regexp_instr_with_scalar_values(&[
+                ScalarValue::Utf8(Some(value)),
+                ScalarValue::Utf8(Some(regex.to_string())),
+                ScalarValue::Int64(Some(start)),
+                ScalarValue::Int64(Some(1)),
+                ScalarValue::Utf8(None),
+                ScalarValue::Int64(Some(subexp)),
+            ]);
+            match result {
+                Ok(ColumnarValue::Scalar(ScalarValue::Int64(v))) => {
+                    assert_eq!(v, Some(expected), "{regex} from {start}");
+                }
+                other => panic!("Unexpected result {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_regexp_instr_offsets() {
+        let value = "abé日x";
+        assert_eq!(byte_offset(value, 1), Some(0));
+        assert_eq!(byte_offset(value, 3), Some(2));
+        assert_eq!(byte_offset(value, 4), Some(4));
+        assert_eq!(byte_offset(value, 5), Some(7));
+        assert_eq!(byte_offset(value, 6), None);
+        assert_eq!(byte_offset(value, i64::MAX), None);
+
+        assert_eq!(OffsetUnit::Char.offset(value, 0).unwrap(), 1);
+        assert_eq!(OffsetUnit::Char.offset(value, 2).unwrap(), 3);
+        assert_eq!(OffsetUnit::Char.offset(value, 7).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_regexp_instr_utf16_offset_unit() {
+        // the emoji is 4 bytes, 1 character and 2 UTF-16 code units, and é is
+        // 2 bytes, 1 character and 1 code unit
+        let value = "é😀ab";
+        assert_eq!(OffsetUnit::Byte.offset(value, 6).unwrap(), 7);
+        assert_eq!(OffsetUnit::Char.offset(value, 6).unwrap(), 3);
+        assert_eq!(OffsetUnit::Utf16.offset(value, 6).unwrap(), 4);
+
+        let values = Arc::new(StringArray::from(vec![
+            Some("é😀ab"),
+            Some("😀😀x"),
+            Some("ab"),
+            Some("none"),
+            None,
+        ])) as ArrayRef;
+        let patterns = Arc::new(StringArray::from(vec!["[abx]"; 5])) as ArrayRef;
+        let positions = |offset_unit| {
+            regexp_instr_func_with_offset_unit(
+                &[Arc::clone(&values), Arc::clone(&patterns)],
+                offset_unit,
+            )
+            .unwrap()
+        };
+
+        let byte = positions(OffsetUnit::Byte);
+        let char = positions(OffsetUnit::Char);
+        let utf16 = positions(OffsetUnit::Utf16);
+        assert_eq!(
+            byte.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(7), Some(9), Some(1), Some(0), None])
+        );
+        assert_eq!(
+            char.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(3), Some(3), Some(1), Some(0), None])
+        );
+        assert_eq!(
+            utf16.as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(4), Some(5), Some(1), Some(0), None])
+        );
+
+        // the UDF keeps the start argument in characters
+        let udf = RegexpInstrFunc::new().with_offset_unit(OffsetUnit::Utf16);
+        let result = udf
+            .invoke_with_args(ScalarFunctionArgs {
+                args: vec![
+                    ColumnarValue::Scalar(ScalarValue::from("😀a😀a")),
+                    ColumnarValue::Scalar(ScalarValue::from("a")),
+                    ColumnarValue::Scalar(ScalarValue::from(3i64)),
+                ],
+                arg_fields: vec![
+                    Arc::new(Field::new("arg_0", Utf8, true)),
+                    Arc::new(Field::new("arg_1", Utf8, true)),
+                    Arc::new(Field::new("arg_2", Int64, true)),
+                ],
+                number_rows: 1,
+                return_field: Arc::new(Field::new("f", Int64, true)),
+                config_options: Arc::new(ConfigOptions::default()),
+            })
+            .unwrap();
+        let ColumnarValue::Scalar(ScalarValue::Int64(Some(position))) = result else {
+            panic!("expected an Int64 scalar, got {result:?}");
+        };
+        assert_eq!(position, 6);
+    }
+
     fn regexp_instr_with_scalar_values(args: &[ScalarValue]) -> Result<ColumnarValue> {
         let args_values: Vec<ColumnarValue> = args
             .iter()
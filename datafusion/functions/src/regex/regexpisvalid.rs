@@ -0,0 +1,196 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use arrow::array::{Array, ArrayRef, AsArray, BooleanArray, StringArrayType};
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex::Regex;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns whether a [regular expression](https://docs.rs/regex/latest/regex/#syntax) compiles, without matching it against anything. Invalid patterns, including patterns that exceed the compiled size limit, return false instead of an error, so a table of patterns can be checked before they are used by `regexp_extract` and the other regular expression functions.",
+    syntax_example = "regexp_is_valid(regexp)",
+    sql_example = r#"```sql
+> select regexp_is_valid('(\d+)-(\d+)'), regexp_is_valid('(\d+');
++--------------------------------------+-------------------------------+
+| regexp_is_valid(Utf8("(\d+)-(\d+)")) | regexp_is_valid(Utf8("(\d+")) |
++--------------------------------------+-------------------------------+
+| true                                 | false                         |
++--------------------------------------+-------------------------------+
+```"#,
+    argument(
+        name = "regexp",
+        description = "Regular expression to validate.
+            Can be a constant, column, or function."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpIsValidFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpIsValidFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpIsValidFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View]),
+                    TypeSignature::Exact(vec![Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpIsValidFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_is_valid"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        match args.args.as_slice() {
+            [ColumnarValue::Scalar(pattern)] => {
+                let result = regexp_is_valid(&[pattern.to_array()?]);
+                // If all inputs are scalar, keeps output as scalar
+                let result = result.and_then(|arr| ScalarValue::try_from_array(&arr, 0));
+                result.map(ColumnarValue::Scalar)
+            }
+            [ColumnarValue::Array(patterns)] => {
+                regexp_is_valid(&[Arc::clone(patterns)]).map(ColumnarValue::Array)
+            }
+            args => exec_err!(
+                "regexp_is_valid was called with {} arguments. It requires 1.",
+                args.len()
+            ),
+        }
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns whether each pattern of `args[0]` compiles.
+///
+/// Each distinct pattern is compiled once.
+pub fn regexp_is_valid(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 1 {
+        return exec_err!(
+            "regexp_is_valid was called with {} arguments. It requires 1.",
+            args.len()
+        );
+    }
+
+    match args[0].data_type() {
+        DataType::Utf8 => validities(args[0].as_string::<i32>()),
+        DataType::LargeUtf8 => validities(args[0].as_string::<i64>()),
+        DataType::Utf8View => validities(args[0].as_string_view()),
+        other => {
+            exec_err!("Unsupported data type {other:?} for function regexp_is_valid")
+        }
+    }
+}
+
+fn validities<'a, S>(patterns: S) -> Result<ArrayRef>
+where
+    S: StringArrayType<'a>,
+{
+    let mut validity_cache = HashMap::new();
+    let result = (0..patterns.len())
+        .map(|i| {
+            patterns.is_valid(i).then(|| {
+                *validity_cache
+                    .entry(patterns.value(i))
+                    .or_insert_with_key(|pattern| Regex::new(pattern).is_ok())
+            })
+        })
+        .collect::<BooleanArray>();
+    Ok(Arc::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{StringArray, StringViewArray};
+
+    #[test]
+    fn test_regexp_is_valid() {
+        let patterns = StringArray::from(vec![
+            Some(r"(\d+)-(\d+)"),
+            Some(r"(\d+"),
+            Some(""),
+            Some(r"(?<name>a)(?<name>b)"),
+            Some(r"\p{NotAProperty}"),
+            Some(r"(\d+)-(\d+)"),
+            None,
+        ]);
+
+        let result = regexp_is_valid(&[Arc::new(patterns)]).unwrap();
+        let expected = BooleanArray::from(vec![
+            Some(true),
+            Some(false),
+            Some(true),
+            Some(false),
+            Some(false),
+            Some(true),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_regexp_is_valid_size_limit() {
+        // syntactically valid, but too large to compile with the default limit
+        let patterns = StringViewArray::from(vec![r"\w{1000}{1000}", r"\w{10}"]);
+
+        let result = regexp_is_valid(&[Arc::new(patterns)]).unwrap();
+        let expected = BooleanArray::from(vec![false, true]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+}
@@ -0,0 +1,217 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regex expressions
+use arrow::datatypes::DataType;
+use datafusion_common::{exec_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+use datafusion_macros::user_doc;
+use regex_syntax::hir::literal::{ExtractKind, Extractor};
+use std::any::Any;
+
+#[user_doc(
+    doc_section(label = "Regular Expression Functions"),
+    description = "Returns the literal prefix that every match of a [regular expression](https://docs.rs/regex/latest/regex/#syntax) starts with, or an empty string if the matches do not share a prefix. For an anchored regular expression this is the prefix a matching string starts with, which can be used to build prefix indexes or to filter strings before matching.",
+    syntax_example = "regexp_prefix(regexp)",
+    sql_example = r#"```sql
+> select regexp_prefix('^GET /api/(\w+)'), regexp_prefix('err(or|no)');
++----------------------------------------+-----------------------------------+
+| regexp_prefix(Utf8("^GET /api/(\w+)")) | regexp_prefix(Utf8("err(or|no)")) |
++----------------------------------------+-----------------------------------+
+| GET /api/                              | err                               |
++----------------------------------------+-----------------------------------+
+```"#,
+    argument(
+        name = "regexp",
+        description = "Regular expression to return the literal prefix of. Must be a constant."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegexpPrefixFunc {
+    signature: Signature,
+}
+
+impl Default for RegexpPrefixFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegexpPrefixFunc {
+    pub fn new() -> Self {
+        use DataType::*;
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![Utf8View]),
+                    TypeSignature::Exact(vec![Utf8]),
+                    TypeSignature::Exact(vec![LargeUtf8]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpPrefixFunc {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_prefix"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: datafusion_expr::ScalarFunctionArgs,
+    ) -> Result<ColumnarValue> {
+        let [ColumnarValue::Scalar(pattern)] = args.args.as_slice() else {
+            return exec_err!("regexp_prefix pattern must be a constant");
+        };
+        let pattern = match pattern {
+            ScalarValue::Utf8(pattern)
+            | ScalarValue::LargeUtf8(pattern)
+            | ScalarValue::Utf8View(pattern) => pattern,
+            other => {
+                return exec_err!(
+                    "Unsupported data type {:?} for function regexp_prefix",
+                    other.data_type()
+                )
+            }
+        };
+
+        let prefix = pattern.as_deref().map(literal_prefix).transpose()?;
+        Ok(ColumnarValue::Scalar(ScalarValue::Utf8(prefix)))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+/// Returns the longest literal that every match of `pattern` starts with.
+///
+/// The prefixes are extracted from the parsed pattern by the literal extractor
+/// of `regex_syntax`, which is also used by the regex crate to find candidate
+/// matches. A common prefix that ends inside of a multibyte character, such as
+/// the shared first byte of `[éè]`, is cut back to the last whole character.
+fn literal_prefix(pattern: &str) -> Result<String> {
+    let hir = regex_syntax::parse(pattern).map_err(|e| {
+        DataFusionError::Execution(format!("unable to compile pattern '{pattern}': {e}"))
+    })?;
+    let mut extractor = Extractor::new();
+    extractor.kind(ExtractKind::Prefix);
+    let prefixes = extractor.extract(&hir);
+
+    // an infinite set of prefixes does not constrain the start of the matches
+    let prefix = prefixes.longest_common_prefix().unwrap_or_default();
+    let prefix = match std::str::from_utf8(prefix) {
+        Ok(prefix) => prefix,
+        Err(e) => std::str::from_utf8(&prefix[..e.valid_up_to()])
+            .expect("the prefix is valid up to the error"),
+    };
+    Ok(prefix.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+    use arrow::datatypes::Field;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_expr::ScalarFunctionArgs;
+    use std::sync::Arc;
+
+    fn invoke(pattern: ColumnarValue) -> Result<ColumnarValue> {
+        RegexpPrefixFunc::new().invoke_with_args(ScalarFunctionArgs {
+            args: vec![pattern],
+            arg_fields: vec![Field::new("arg_0", DataType::Utf8, true).into()],
+            number_rows: 1,
+            return_field: Field::new("f", DataType::Utf8, true).into(),
+            config_options: Arc::new(ConfigOptions::default()),
+        })
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        let prefix = |pattern| literal_prefix(pattern).unwrap();
+        // anchored patterns
+        assert_eq!(prefix(r"^GET /api/(\w+)"), "GET /api/");
+        assert_eq!(prefix(r"\Aké(\d)"), "ké");
+        assert_eq!(prefix(r"^(ab)(c)d?"), "abc");
+        // unanchored patterns, the prefix of their matches
+        assert_eq!(prefix(r"err(or|no)"), "err");
+        assert_eq!(prefix(r"abc"), "abc");
+        assert_eq!(prefix(r"(foo|foobar)\d"), "foo");
+        // patterns without a common literal prefix
+        assert_eq!(prefix(r"\d+-abc"), "");
+        assert_eq!(prefix(r"a|b"), "");
+        assert_eq!(prefix(r"a*b"), "");
+        assert_eq!(prefix(r"(?i)ab"), "");
+        assert_eq!(prefix(""), "");
+        // the common prefix is cut back to the last whole character
+        assert_eq!(prefix(r"x[éè]"), "x");
+    }
+
+    #[test]
+    fn test_regexp_prefix() -> Result<()> {
+        let ColumnarValue::Scalar(prefix) =
+            invoke(ColumnarValue::Scalar(ScalarValue::from(r"^id=(\d+)")))?
+        else {
+            panic!("expected a scalar");
+        };
+        assert_eq!(prefix, ScalarValue::from("id="));
+
+        let ColumnarValue::Scalar(prefix) =
+            invoke(ColumnarValue::Scalar(ScalarValue::Utf8(None)))?
+        else {
+            panic!("expected a scalar");
+        };
+        assert_eq!(prefix, ScalarValue::Utf8(None));
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_prefix_errors() {
+        let err = invoke(ColumnarValue::Scalar(ScalarValue::from("(a")))
+            .expect_err("invalid pattern should fail");
+        assert!(
+            err.strip_backtrace()
+                .starts_with("Execution error: unable to compile pattern '(a'"),
+            "unexpected error: {err}"
+        );
+
+        let patterns = Arc::new(StringArray::from(vec!["(a)"]));
+        let err = invoke(ColumnarValue::Array(patterns))
+            .expect_err("pattern column should fail");
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: regexp_prefix pattern must be a constant"
+        );
+    }
+}
@@ -43,6 +43,7 @@ use prost::Message;
 use datafusion::arrow::array::ArrayRef;
 use datafusion::arrow::compute::kernels::sort::SortOptions;
 use datafusion::arrow::datatypes::{DataType, Field, IntervalUnit, Schema};
+use datafusion::assert_batches_eq;
 use datafusion::datasource::empty::EmptyTable;
 use datafusion::datasource::file_format::csv::CsvSink;
 use datafusion::datasource::file_format::json::{JsonFormat, JsonSink};
@@ -94,7 +95,8 @@ use datafusion::physical_plan::windows::{
     WindowAggExec,
 };
 use datafusion::physical_plan::{
-    displayable, ExecutionPlan, InputOrderMode, Partitioning, PhysicalExpr, Statistics,
+    collect, displayable, ExecutionPlan, InputOrderMode, Partitioning, PhysicalExpr,
+    Statistics,
 };
 use datafusion::prelude::{ParquetReadOptions, SessionContext};
 use datafusion::scalar::ScalarValue;
@@ -111,6 +113,7 @@ use datafusion_expr::{
     Accumulator, AccumulatorFactoryFunction, AggregateUDF, ColumnarValue, ScalarUDF,
     Signature, SimpleAggregateUDF, WindowFrame, WindowFrameBound, WindowUDF,
 };
+use datafusion_functions::regex::regexpextract::RegexpExtractFunc;
 use datafusion_functions_aggregate::average::avg_udaf;
 use datafusion_functions_aggregate::nth_value::nth_value_udaf;
 use datafusion_functions_aggregate::string_agg::string_agg_udaf;
@@ -2227,6 +2230,98 @@ async fn roundtrip_memory_source() -> Result<()> {
     roundtrip_test(plan)
 }
 
+/// Serializes the literal pattern of `regexp_extract`, so that it is not
+/// compiled again for every batch of the deserialized plan.
+#[derive(Debug)]
+struct RegexpExtractCodec;
+
+impl PhysicalExtensionCodec for RegexpExtractCodec {
+    fn try_decode(
+        &self,
+        _buf: &[u8],
+        _inputs: &[Arc<dyn ExecutionPlan>],
+        _ctx: &TaskContext,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        not_impl_err!("No extension codec provided")
+    }
+
+    fn try_encode(
+        &self,
+        _node: Arc<dyn ExecutionPlan>,
+        _buf: &mut Vec<u8>,
+    ) -> Result<()> {
+        not_impl_err!("No extension codec provided")
+    }
+
+    fn try_decode_udf(&self, name: &str, buf: &[u8]) -> Result<Arc<ScalarUDF>> {
+        if name == "regexp_extract" {
+            let udf = RegexpExtractFunc::new().with_serialized_pattern(buf)?;
+            Ok(Arc::new(ScalarUDF::from(udf)))
+        } else {
+            not_impl_err!("unrecognized scalar UDF implementation, cannot decode")
+        }
+    }
+
+    fn try_encode_udf(&self, node: &ScalarUDF, buf: &mut Vec<u8>) -> Result<()> {
+        if let Some(pattern) = node
+            .inner()
+            .as_any()
+            .downcast_ref::<RegexpExtractFunc>()
+            .and_then(RegexpExtractFunc::serialized_pattern)
+        {
+            buf.extend(pattern);
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn roundtrip_regexp_extract_serialized_pattern() -> Result<()> {
+    let ctx = SessionContext::new();
+    let plan = ctx
+        .sql(
+            r"select regexp_extract(column1, '(\d+)-(\d+)', 2, 'i') as extracted
+            from values ('100-200'), ('abc'), (null)",
+        )
+        .await?
+        .create_physical_plan()
+        .await?;
+
+    let restored =
+        roundtrip_test_and_return(Arc::clone(&plan), &ctx, &RegexpExtractCodec)?;
+    let projection = restored
+        .as_any()
+        .downcast_ref::<ProjectionExec>()
+        .expect("a projection");
+    let pattern = projection.expr()[0]
+        .expr
+        .as_any()
+        .downcast_ref::<ScalarFunctionExpr>()
+        .expect("a scalar function")
+        .fun()
+        .inner()
+        .as_any()
+        .downcast_ref::<RegexpExtractFunc>()
+        .expect("regexp_extract")
+        .serialized_pattern();
+    assert!(pattern.is_some(), "the literal pattern should be restored");
+
+    let expected = [
+        "+-----------+",
+        "| extracted |",
+        "+-----------+",
+        "| 200       |",
+        "|           |",
+        "|           |",
+        "+-----------+",
+    ];
+    let batches = collect(plan, ctx.task_ctx()).await?;
+    assert_batches_eq!(expected, &batches);
+    let batches = collect(restored, ctx.task_ctx()).await?;
+    assert_batches_eq!(expected, &batches);
+    Ok(())
+}
+
 #[tokio::test]
 async fn roundtrip_listing_table_with_schema_metadata() -> Result<()> {
     let ctx = SessionContext::new();